@@ -4,6 +4,8 @@ use std::path::{Path, PathBuf};
 
 use crate::errors::Result;
 use crate::utils::sha256_file_hex;
+use airbender_core::host::panics::PANIC_TABLE_FILE;
+use airbender_core::host::regions::REGION_TABLE_FILE;
 
 /// Resolved output paths for dist app directory.
 ///
@@ -15,6 +17,8 @@ pub struct DistApp {
     bin: PathBuf,
     elf: PathBuf,
     text: PathBuf,
+    panics: PathBuf,
+    regions: PathBuf,
 }
 
 impl DistApp {
@@ -24,6 +28,8 @@ impl DistApp {
             bin: dist_dir.join("app.bin"),
             elf: dist_dir.join("app.elf"),
             text: dist_dir.join("app.text"),
+            panics: dist_dir.join(PANIC_TABLE_FILE),
+            regions: dist_dir.join(REGION_TABLE_FILE),
             dir: dist_dir,
         }
     }
@@ -51,6 +57,16 @@ impl DistApp {
     pub fn text(&self) -> &Path {
         &self.text
     }
+
+    /// Path to `panics.toml` - panic location table written for compact-panic guests.
+    pub fn panics(&self) -> &Path {
+        &self.panics
+    }
+
+    /// Path to `regions.toml` - region name table written for guests with named regions.
+    pub fn regions(&self) -> &Path {
+        &self.regions
+    }
 }
 
 /// Output produced by one successful build/package invocation.
@@ -80,6 +96,10 @@ pub struct DistArtifacts {
     pub app_elf: DistArtifact,
     /// Text-section image used by the transpiler.
     pub app_text: DistArtifact,
+    /// Panic location table, present for guests built with compact panics.
+    pub panic_table: Option<DistArtifact>,
+    /// Region name table, present for guests with named cycle-marker regions.
+    pub region_table: Option<DistArtifact>,
     /// Path to `manifest.toml`.
     pub manifest_path: PathBuf,
 }
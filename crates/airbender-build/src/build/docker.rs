@@ -247,6 +247,29 @@ impl<'a> ReproducibleBuild<'a> {
         Ok(Self { params })
     }
 
+    /// Path of `project_path`, given relative to the guest project, inside the container.
+    pub(crate) fn container_path(&self, project_path: &str) -> String {
+        format!("{}/{project_path}", self.workdir())
+    }
+
+    /// Guest project directory inside the container.
+    fn workdir(&self) -> String {
+        let project_abs = self
+            .params
+            .project_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.params.project_dir.to_path_buf());
+        let mount_root_abs = self
+            .params
+            .mount_root
+            .canonicalize()
+            .unwrap_or_else(|_| self.params.mount_root.to_path_buf());
+        let project_rel = project_abs
+            .strip_prefix(&mount_root_abs)
+            .unwrap_or(Path::new(""));
+        format!("/src/{}", project_rel.display())
+    }
+
     /// Runs the build container and copies `app.bin`, `app.elf`, `app.text` into `dist_dir`.
     pub(crate) fn run(
         &self,
@@ -270,20 +293,7 @@ impl<'a> ReproducibleBuild<'a> {
         name: &str,
     ) -> Result<()> {
         let tag = docker_image_tag();
-        let project_abs = self
-            .params
-            .project_dir
-            .canonicalize()
-            .unwrap_or_else(|_| self.params.project_dir.to_path_buf());
-        let mount_root_abs = self
-            .params
-            .mount_root
-            .canonicalize()
-            .unwrap_or_else(|_| self.params.mount_root.to_path_buf());
-        let project_rel = project_abs
-            .strip_prefix(&mount_root_abs)
-            .unwrap_or(Path::new(""));
-        let workdir = format!("/src/{}", project_rel.display());
+        let workdir = self.workdir();
         let build_cmd = build_container_cmd(
             &self.params.bin_name,
             &self.params.target,
@@ -430,6 +440,9 @@ mod tests {
             dist_app: crate::build::DistApp::new(tmp.join("dist")),
             mount_root: tmp.clone(),
             panic_immediate_abort: false,
+            compact_panics: false,
+            atomics: false,
+            memory_layout: None,
             git: crate::resolver::GitMetadata::default(),
         };
         let result = ReproducibleBuild::new(&params);
@@ -4,6 +4,9 @@ use crate::build::ReproducibleBuild;
 use crate::build::{DistArtifact, DistArtifacts, LocalBuild};
 use crate::constants::DEFAULT_APP_NAME;
 use crate::errors::Result;
+use crate::layout::{self, LAYOUT_SCRIPT_PATH, PANIC_SITES_SCRIPT_PATH};
+use crate::panics;
+use crate::regions;
 use crate::resolver::ResolvedBuildParams;
 use crate::{ArtifactEntry, BuildMetadata, Manifest, Profile, MANIFEST_VERSION_V1};
 use std::fs;
@@ -58,19 +61,53 @@ impl BuildConfig {
         let cwd = std::env::current_dir()?;
         let params = ResolvedBuildParams::resolve(self, &cwd)?;
 
-        // Build extra config for `panic_immediate_abort`.
-        // The same --config must be passed to both build and objcopy to prevent
-        // a fall back to a cached artifact built without it.
-        let extra_config = params
-            .panic_immediate_abort
-            .then_some(r#"build.rustflags=["-Zunstable-options","-Cpanic=immediate-abort"]"#);
+        let layout_script = params
+            .memory_layout
+            .map(|memory| layout::write_linker_script(&params.project_dir, &memory))
+            .transpose()?;
+        let panic_sites_script = params
+            .compact_panics
+            .then(|| layout::write_panic_sites_script(&params.project_dir))
+            .transpose()?;
 
         fs::create_dir_all(params.dist_app.dir())?;
 
+        // Build extra config for `panic_immediate_abort`, atomics, compact panics and the memory
+        // layout script. The same --config must be passed to both build and objcopy to prevent
+        // a fall back to a cached artifact built without it.
         if self.reproducible {
-            ReproducibleBuild::new(&params)?.run(self.profile, &self.cargo_args, extra_config)?;
+            let build = ReproducibleBuild::new(&params)?;
+            let sites_script = panic_sites_script
+                .is_some()
+                .then(|| build.container_path(PANIC_SITES_SCRIPT_PATH));
+            let script = layout_script
+                .is_some()
+                .then(|| build.container_path(LAYOUT_SCRIPT_PATH));
+            let extra_config = layout::extra_config(
+                params.panic_immediate_abort,
+                params.atomics,
+                sites_script.as_deref(),
+                script.as_deref(),
+            );
+            build.run(self.profile, &self.cargo_args, extra_config.as_deref())?;
         } else {
-            LocalBuild::new(&params).run(self.profile, &self.cargo_args, extra_config)?;
+            let sites_script = panic_sites_script.map(|path| path.display().to_string());
+            let script = layout_script.map(|path| path.display().to_string());
+            let extra_config = layout::extra_config(
+                params.panic_immediate_abort,
+                params.atomics,
+                sites_script.as_deref(),
+                script.as_deref(),
+            );
+            LocalBuild::new(&params).run(
+                self.profile,
+                &self.cargo_args,
+                extra_config.as_deref(),
+            )?;
+        }
+
+        if let Some(layout) = &params.memory_layout {
+            layout::check_fits(layout, params.dist_app.elf())?;
         }
 
         let artifacts = DistArtifacts {
@@ -78,6 +115,8 @@ impl BuildConfig {
             app_bin: DistArtifact::new(params.dist_app.bin().to_path_buf())?,
             app_elf: DistArtifact::new(params.dist_app.elf().to_path_buf())?,
             app_text: DistArtifact::new(params.dist_app.text().to_path_buf())?,
+            panic_table: write_panic_table(&params)?,
+            region_table: write_region_table(&params)?,
             manifest_path: params.dist_app.manifest().to_path_buf(),
         };
 
@@ -113,6 +152,15 @@ impl BuildConfig {
                 git_commit: params.git.commit,
                 is_dirty: params.git.is_dirty,
             },
+            memory: params.memory_layout,
+            panics: artifacts.panic_table.as_ref().map(|table| ArtifactEntry {
+                path: file_name(&table.path),
+                sha256: table.sha256.clone(),
+            }),
+            regions: artifacts.region_table.as_ref().map(|table| ArtifactEntry {
+                path: file_name(&table.path),
+                sha256: table.sha256.clone(),
+            }),
         };
         manifest.write_to_file(params.dist_app.manifest())?;
 
@@ -120,6 +168,33 @@ impl BuildConfig {
     }
 }
 
+/// Writes the panic location table for compact-panic builds and drops a stale one otherwise.
+fn write_panic_table(params: &ResolvedBuildParams) -> Result<Option<DistArtifact>> {
+    let table_path = params.dist_app.panics();
+    if !params.compact_panics {
+        if table_path.exists() {
+            fs::remove_file(table_path)?;
+        }
+        return Ok(None);
+    }
+    panics::write_panic_table(params.dist_app.elf(), table_path)?;
+    Ok(Some(DistArtifact::new(table_path.to_path_buf())?))
+}
+
+/// Writes the region name table when the guest has named regions and drops a stale one otherwise.
+fn write_region_table(params: &ResolvedBuildParams) -> Result<Option<DistArtifact>> {
+    let table_path = params.dist_app.regions();
+    let table = regions::read_region_table(params.dist_app.elf())?;
+    if table.entries.is_empty() {
+        if table_path.exists() {
+            fs::remove_file(table_path)?;
+        }
+        return Ok(None);
+    }
+    table.write_to_file(table_path)?;
+    Ok(Some(DistArtifact::new(table_path.to_path_buf())?))
+}
+
 /// Builds and packages guest artifacts using the provided configuration.
 pub fn build_dist(config: &BuildConfig) -> Result<DistArtifacts> {
     config.build_dist()
@@ -0,0 +1,230 @@
+//! Section headers of the 32-bit little-endian `app.elf` files guests are linked into.
+
+use crate::errors::{BuildError, Result};
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_NOBITS: u32 = 8;
+const SHF_ALLOC: u32 = 0x2;
+const SHF_EXECINSTR: u32 = 0x4;
+
+/// One section header, with the file contents of the section.
+pub(crate) struct SectionHeader<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) kind: u32,
+    pub(crate) flags: u32,
+    pub(crate) addr: u32,
+    pub(crate) size: u32,
+    /// Empty for sections without file contents, such as `.bss`.
+    pub(crate) data: &'a [u8],
+}
+
+impl SectionHeader<'_> {
+    /// Whether the section occupies guest memory.
+    pub(crate) fn is_loaded(&self) -> bool {
+        self.flags & SHF_ALLOC != 0 && matches!(self.kind, SHT_PROGBITS | SHT_NOBITS)
+    }
+}
+
+/// One loaded, non-executable section of the ELF image.
+pub(crate) struct Section<'a> {
+    pub(crate) addr: u32,
+    pub(crate) data: &'a [u8],
+}
+
+/// Every section header of `elf`.
+pub(crate) fn section_headers(elf: &[u8]) -> Result<Vec<SectionHeader<'_>>> {
+    let invalid = |reason: &str| BuildError::InvalidConfig(format!("invalid app.elf: {reason}"));
+    if elf.get(..6) != Some(b"\x7fELF\x01\x01".as_slice()) {
+        return Err(invalid("not a 32-bit little-endian ELF file"));
+    }
+    let header_word = |offset: usize| read_u32(elf, offset).ok_or_else(|| invalid("truncated"));
+    let header_half = |offset: usize| {
+        elf.get(offset..offset + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+            .ok_or_else(|| invalid("truncated"))
+    };
+    let table_offset = header_word(0x20)? as usize;
+    let entry_size = header_half(0x2e)?;
+    let entries = header_half(0x30)?;
+    let names_index = header_half(0x32)?;
+
+    let names_entry = table_offset + names_index * entry_size;
+    let names_offset = header_word(names_entry + 16)? as usize;
+    let names_size = header_word(names_entry + 20)? as usize;
+    let names = elf
+        .get(names_offset..names_offset + names_size)
+        .ok_or_else(|| invalid("section names out of bounds"))?;
+
+    let mut headers = Vec::with_capacity(entries);
+    for index in 0..entries {
+        let entry = table_offset + index * entry_size;
+        let field = |offset: usize| header_word(entry + offset);
+        let name = names
+            .get(field(0)? as usize..)
+            .and_then(|rest| rest.split(|&byte| byte == 0).next())
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .unwrap_or("");
+        let (kind, flags, addr) = (field(4)?, field(8)?, field(12)?);
+        let (offset, size) = (field(16)? as usize, field(20)?);
+        let data = if kind == SHT_NOBITS {
+            &[][..]
+        } else {
+            elf.get(offset..offset + size as usize)
+                .ok_or_else(|| invalid("section data out of bounds"))?
+        };
+        headers.push(SectionHeader {
+            name,
+            kind,
+            flags,
+            addr,
+            size,
+            data,
+        });
+    }
+    Ok(headers)
+}
+
+/// Loaded data sections of `elf`.
+pub(crate) fn data_sections(elf: &[u8]) -> Result<Vec<Section<'_>>> {
+    Ok(section_headers(elf)?
+        .into_iter()
+        .filter(|header| {
+            header.kind == SHT_PROGBITS && header.is_loaded() && header.flags & SHF_EXECINSTR == 0
+        })
+        .map(|header| Section {
+            addr: header.addr,
+            data: header.data,
+        })
+        .collect())
+}
+
+/// The UTF-8 string of `len` bytes at guest address `addr`, if it lies in a loaded section.
+pub(crate) fn read_str<'a>(sections: &[Section<'a>], addr: u32, len: u32) -> Option<&'a str> {
+    let section = sections
+        .iter()
+        .find(|section| addr >= section.addr && addr - section.addr < section.data.len() as u32)?;
+    let start = (addr - section.addr) as usize;
+    let bytes = section.data.get(start..start.checked_add(len as usize)?)?;
+    std::str::from_utf8(bytes).ok()
+}
+
+/// End address of the loaded image, `.bss` included.
+pub(crate) fn image_end(elf: &[u8]) -> Result<u64> {
+    Ok(section_headers(elf)?
+        .iter()
+        .filter(|header| header.is_loaded())
+        .map(|header| u64::from(header.addr) + u64::from(header.size))
+        .max()
+        .unwrap_or(0))
+}
+
+pub(crate) fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Section for [`elf_with_sections`]: name, type, flags, address, size, and contents.
+    pub(crate) type TestSection<'a> = (&'a str, u32, u32, u32, u32, &'a [u8]);
+
+    /// Minimal ELF file holding `sections` after the null section and the section names.
+    pub(crate) fn elf_with_sections(sections: &[TestSection<'_>]) -> Vec<u8> {
+        let mut names = vec![0u8];
+        let mut name_offsets = Vec::new();
+        for (name, ..) in sections
+            .iter()
+            .copied()
+            .chain([(".shstrtab", 0, 0, 0, 0, &[][..])])
+        {
+            name_offsets.push(names.len() as u32);
+            names.extend_from_slice(name.as_bytes());
+            names.push(0);
+        }
+
+        let mut elf = vec![0u8; 0x34];
+        elf[..6].copy_from_slice(b"\x7fELF\x01\x01");
+        let mut headers = vec![[0u32; 10]];
+        for (index, &(_, kind, flags, addr, size, data)) in sections.iter().enumerate() {
+            let offset = elf.len() as u32;
+            elf.extend_from_slice(data);
+            headers.push([
+                name_offsets[index],
+                kind,
+                flags,
+                addr,
+                offset,
+                size,
+                0,
+                0,
+                4,
+                0,
+            ]);
+        }
+        let names_offset = elf.len() as u32;
+        elf.extend_from_slice(&names);
+        let names_name = name_offsets[sections.len()];
+        headers.push([
+            names_name,
+            3,
+            0,
+            0,
+            names_offset,
+            names.len() as u32,
+            0,
+            0,
+            1,
+            0,
+        ]);
+
+        let table_offset = elf.len() as u32;
+        for header in &headers {
+            elf.extend(header.iter().flat_map(|word| word.to_le_bytes()));
+        }
+        elf[0x20..0x24].copy_from_slice(&table_offset.to_le_bytes());
+        elf[0x2e..0x30].copy_from_slice(&40u16.to_le_bytes());
+        elf[0x30..0x32].copy_from_slice(&(headers.len() as u16).to_le_bytes());
+        elf[0x32..0x34].copy_from_slice(&((headers.len() - 1) as u16).to_le_bytes());
+        elf
+    }
+
+    #[test]
+    fn reads_named_sections_and_image_end() {
+        let elf = elf_with_sections(&[
+            (
+                ".text",
+                SHT_PROGBITS,
+                SHF_ALLOC | SHF_EXECINSTR,
+                0,
+                8,
+                &[0; 8],
+            ),
+            (".rodata", SHT_PROGBITS, SHF_ALLOC, 0x20_0000, 4, b"abcd"),
+            (".bss", SHT_NOBITS, SHF_ALLOC | 0x1, 0x20_0004, 0x100, &[]),
+            (".comment", SHT_PROGBITS, 0, 0, 2, b"x\0"),
+        ]);
+
+        let headers = section_headers(&elf).expect("headers");
+        let names: Vec<_> = headers.iter().map(|header| header.name).collect();
+        assert_eq!(
+            names,
+            ["", ".text", ".rodata", ".bss", ".comment", ".shstrtab"]
+        );
+
+        let data = data_sections(&elf).expect("data sections");
+        assert_eq!(data.len(), 1);
+        assert_eq!((data[0].addr, data[0].data), (0x20_0000, &b"abcd"[..]));
+
+        assert_eq!(image_end(&elf).expect("image end"), 0x20_0104);
+    }
+
+    #[test]
+    fn rejects_non_elf_input() {
+        assert!(matches!(
+            data_sections(b"\x7fELF\x02\x01rest"),
+            Err(BuildError::InvalidConfig(message)) if message.contains("32-bit")
+        ));
+    }
+}
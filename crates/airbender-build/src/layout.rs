@@ -0,0 +1,210 @@
+//! Linker script carrying the guest memory layout to the runtime.
+//!
+//! The script only defines the `_airbender_stack_size` and `_airbender_heap_size` symbols;
+//! memory regions still come from the runtime's own `memory.x`/`link.x`. Guests are built with
+//! `--cfg airbender_memory_layout`, which makes the runtime read the symbols at boot and
+//! shrink the heap to match. A zero value leaves that bound unconstrained.
+//!
+//! The runtime panics at boot if the stack does not fit, so builds check the linked image
+//! with [`check_fits`] and reject such layouts up front.
+//!
+//! Compact-panic builds get a second script that keeps the `compact_panic!` site records in
+//! `app.elf` as a non-allocated section, so they never reach `app.bin`.
+
+use crate::elf;
+use crate::errors::{BuildError, Result};
+use crate::MemoryLayout;
+use airbender_core::host::manifest::GUEST_RAM_BYTES;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Script location relative to the guest project directory.
+///
+/// It lives inside the project so reproducible builds see it through the `/src` mount.
+pub(crate) const LAYOUT_SCRIPT_PATH: &str = "target/airbender/memory-layout.x";
+
+/// Compact-panic script location relative to the guest project directory.
+pub(crate) const PANIC_SITES_SCRIPT_PATH: &str = "target/airbender/panic-sites.x";
+
+/// `INFO` sections are not allocated, and `KEEP` holds on to the otherwise unreferenced records.
+const PANIC_SITES_SCRIPT: &str = "\
+/* Generated by airbender-build for compact panics. */
+SECTIONS
+{
+  .airbender_panics 0 (INFO) : { KEEP(*(.airbender_panics)) }
+}
+";
+
+/// Renders the linker script for `layout`.
+pub(crate) fn linker_script(layout: &MemoryLayout) -> String {
+    format!(
+        "/* Generated by airbender-build from [package.metadata.airbender] memory settings. */\n\
+         _airbender_stack_size = {:#010x};\n\
+         _airbender_heap_size = {:#010x};\n",
+        layout.stack.unwrap_or(0),
+        layout.heap.unwrap_or(0)
+    )
+}
+
+/// Writes the linker script for `layout` into the project and returns its path.
+pub(crate) fn write_linker_script(project_dir: &Path, layout: &MemoryLayout) -> Result<PathBuf> {
+    let path = project_dir.join(LAYOUT_SCRIPT_PATH);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, linker_script(layout))?;
+    Ok(path)
+}
+
+/// Writes the compact-panic linker script into the project and returns its path.
+pub(crate) fn write_panic_sites_script(project_dir: &Path) -> Result<PathBuf> {
+    let path = project_dir.join(PANIC_SITES_SCRIPT_PATH);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, PANIC_SITES_SCRIPT)?;
+    Ok(path)
+}
+
+/// Rejects `layout` if its stack and heap do not fit in guest RAM above the image in `elf_path`.
+pub(crate) fn check_fits(layout: &MemoryLayout, elf_path: &Path) -> Result<()> {
+    let elf = fs::read(elf_path)?;
+    check_fits_above(layout, elf::image_end(&elf)?)
+}
+
+fn check_fits_above(layout: &MemoryLayout, image_end: u64) -> Result<()> {
+    let available = GUEST_RAM_BYTES.saturating_sub(image_end);
+    let required = layout.required_bytes();
+    if required > available {
+        return Err(BuildError::InvalidConfig(format!(
+            "memory layout needs {required} bytes of stack and heap, but only {available} \
+             bytes of RAM are left above the program image ending at {image_end:#010x}"
+        )));
+    }
+    Ok(())
+}
+
+/// Builds the `--config` value forwarded to `cargo build` and `cargo objcopy`.
+///
+/// `atomics` lowers atomic operations to plain loads and stores for guests using the runtime's
+/// `critical-section` or `portable-atomic` support, which relies on that for the single-core
+/// guest. `panic_sites_script` is set for compact-panic builds. Those keep the regular abort strategy
+/// even with `panic_immediate_abort`, since immediate aborts skip the panic handler that
+/// reports the location. Script paths are as seen by the compiler, which differs from the host
+/// path for reproducible builds.
+pub(crate) fn extra_config(
+    panic_immediate_abort: bool,
+    atomics: bool,
+    panic_sites_script: Option<&str>,
+    layout_script: Option<&str>,
+) -> Option<String> {
+    let mut flags = Vec::new();
+    if panic_immediate_abort && panic_sites_script.is_none() {
+        flags.extend([
+            "-Zunstable-options".to_string(),
+            "-Cpanic=immediate-abort".to_string(),
+        ]);
+    }
+    if atomics {
+        flags.push("-Cpasses=lower-atomic".to_string());
+    }
+    if let Some(script) = panic_sites_script {
+        flags.push(format!("-Clink-arg=-T{script}"));
+        flags.push("--cfg=airbender_compact_panics".to_string());
+    }
+    if let Some(script) = layout_script {
+        flags.push(format!("-Clink-arg=-T{script}"));
+        flags.push("--cfg=airbender_memory_layout".to_string());
+    }
+    if flags.is_empty() {
+        return None;
+    }
+    let flags = flags
+        .iter()
+        .map(|flag| format!("\"{flag}\""))
+        .collect::<Vec<_>>()
+        .join(",");
+    Some(format!("build.rustflags=[{flags}]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linker_script_defines_zero_for_unset_sizes() {
+        let script = linker_script(&MemoryLayout {
+            stack: Some(256 << 10),
+            heap: None,
+        });
+        assert!(script.contains("_airbender_stack_size = 0x00040000;\n"));
+        assert!(script.contains("_airbender_heap_size = 0x00000000;\n"));
+    }
+
+    #[test]
+    fn rejects_layouts_that_do_not_fit_above_the_image() {
+        let image_end = 0x0030_0000;
+        let available = (GUEST_RAM_BYTES - image_end) as u32;
+        let fits = MemoryLayout {
+            stack: Some(available / 2),
+            heap: Some(available / 2),
+        };
+        assert!(check_fits_above(&fits, image_end).is_ok());
+        assert!(check_fits_above(&MemoryLayout::default(), GUEST_RAM_BYTES).is_ok());
+
+        let too_large = MemoryLayout {
+            stack: Some(available),
+            heap: Some(1),
+        };
+        let err = check_fits_above(&too_large, image_end).expect_err("layout does not fit");
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "invalid config: memory layout needs {} bytes of stack and heap, but only \
+                 {available} bytes of RAM are left above the program image ending at 0x00300000",
+                u64::from(available) + 1
+            )
+        );
+    }
+
+    #[test]
+    fn extra_config_combines_flags() {
+        assert_eq!(extra_config(false, false, None, None), None);
+        assert_eq!(
+            extra_config(true, false, None, None).as_deref(),
+            Some(r#"build.rustflags=["-Zunstable-options","-Cpanic=immediate-abort"]"#)
+        );
+        assert_eq!(
+            extra_config(
+                false,
+                false,
+                Some("/src/guest/target/airbender/panic-sites.x"),
+                Some("/src/guest/target/airbender/memory-layout.x")
+            )
+            .as_deref(),
+            Some(
+                r#"build.rustflags=["-Clink-arg=-T/src/guest/target/airbender/panic-sites.x","--cfg=airbender_compact_panics","-Clink-arg=-T/src/guest/target/airbender/memory-layout.x","--cfg=airbender_memory_layout"]"#
+            )
+        );
+        assert_eq!(
+            extra_config(true, false, Some("panic-sites.x"), None).as_deref(),
+            Some(
+                r#"build.rustflags=["-Clink-arg=-Tpanic-sites.x","--cfg=airbender_compact_panics"]"#
+            )
+        );
+    }
+
+    #[test]
+    fn extra_config_lowers_atomics_for_atomics_guests() {
+        assert_eq!(
+            extra_config(false, true, None, None).as_deref(),
+            Some(r#"build.rustflags=["-Cpasses=lower-atomic"]"#)
+        );
+        assert_eq!(
+            extra_config(false, true, None, Some("memory-layout.x")).as_deref(),
+            Some(
+                r#"build.rustflags=["-Cpasses=lower-atomic","-Clink-arg=-Tmemory-layout.x","--cfg=airbender_memory_layout"]"#
+            )
+        );
+    }
+}
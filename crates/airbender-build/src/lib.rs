@@ -3,14 +3,21 @@
 mod build;
 mod config;
 mod constants;
+mod elf;
 mod errors;
+mod layout;
 mod metadata;
+mod panics;
+mod regions;
 mod resolver;
 mod utils;
 
 pub use airbender_core::host::manifest::{
-    ArtifactEntry, BuildMetadata, Manifest, Profile, CODEC_VERSION_V0, MANIFEST_VERSION_V1,
+    ArtifactEntry, BuildMetadata, Manifest, MemoryLayout, Profile, CODEC_VERSION_V0,
+    MANIFEST_VERSION_V1,
 };
+pub use airbender_core::host::panics::{PanicLocation, PanicTable, PANIC_TABLE_FILE};
+pub use airbender_core::host::regions::{RegionName, RegionTable, REGION_TABLE_FILE};
 pub use build::clean_reproducible_volumes;
 pub use config::{build_dist, BuildConfig};
 pub use constants::{DEFAULT_GUEST_TARGET, DEFAULT_GUEST_TOOLCHAIN};
@@ -1,7 +1,7 @@
 //! Typed view of the guest project's `Cargo.toml` loaded from `cargo metadata`.
 
 use crate::errors::{BuildError, Result};
-use crate::Profile;
+use crate::{MemoryLayout, Profile};
 use cargo_metadata::{Metadata, MetadataCommand, Package};
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -18,14 +18,22 @@ pub(crate) struct CargoMetadata {
     pub(crate) workspace_root: std::path::PathBuf,
     /// Typed `[package.metadata.airbender]` settings, defaulting to empty if absent.
     pub(crate) airbender: AirbenderConfig,
+    /// Whether the guest enables the runtime's atomics support (see [`ATOMICS_FEATURES`]).
+    pub(crate) atomics: bool,
 }
 
+/// `airbender-sdk` and `airbender-rt` features that turn on the runtime's atomics support.
+const ATOMICS_FEATURES: [&str; 2] = ["critical-section", "portable-atomic"];
+
 /// Contents of `[package.metadata.airbender]` in the guest `Cargo.toml`.
 #[derive(Deserialize, Default)]
 pub(crate) struct AirbenderConfig {
     /// Per-profile build settings, keyed by profile name (`"debug"`, `"release"`).
     #[serde(default)]
     profile: HashMap<String, AirbenderProfileConfig>,
+    /// Guest memory layout under `[package.metadata.airbender] memory = { ... }`.
+    #[serde(default)]
+    memory: Option<AirbenderMemoryConfig>,
 }
 
 /// Per-profile airbender build settings under
@@ -35,6 +43,16 @@ struct AirbenderProfileConfig {
     /// Enable `panic_immediate_abort` build-std feature for this profile.
     #[serde(default, rename = "panic-immediate-abort")]
     panic_immediate_abort: bool,
+    /// Report panics as location IDs resolved through the dist `panics.toml` table.
+    #[serde(default, rename = "compact-panics")]
+    compact_panics: bool,
+}
+
+/// Stack and heap sizes such as `"256K"` or `"16M"`, both optional.
+#[derive(Deserialize, Default)]
+struct AirbenderMemoryConfig {
+    stack: Option<String>,
+    heap: Option<String>,
 }
 
 fn load_metadata(manifest_path: &Path) -> Result<Metadata> {
@@ -82,6 +100,17 @@ fn find_package<'a>(metadata: &'a Metadata, manifest_path: &Path) -> Result<&'a
         .ok_or(BuildError::MissingField("package.name"))
 }
 
+/// Whether `package` enables one of [`ATOMICS_FEATURES`] on its SDK or runtime dependency.
+fn uses_atomics(package: &Package) -> bool {
+    package.dependencies.iter().any(|dependency| {
+        matches!(dependency.name.as_str(), "airbender-sdk" | "airbender-rt")
+            && dependency
+                .features
+                .iter()
+                .any(|feature| ATOMICS_FEATURES.contains(&feature.as_str()))
+    })
+}
+
 impl CargoMetadata {
     /// Loads the guest project manifest from the `Cargo.toml` at `manifest_path`.
     ///
@@ -104,6 +133,7 @@ impl CargoMetadata {
             bin_targets,
             workspace_root,
             airbender,
+            atomics: uses_atomics(package),
         })
     }
 
@@ -118,6 +148,39 @@ impl CargoMetadata {
             .map(|p| p.panic_immediate_abort)
             .unwrap_or(false)
     }
+
+    /// Returns the effective `compact-panics` setting for the given profile.
+    ///
+    /// Reads `package.metadata.airbender.profile.<profile>.compact-panics`.
+    /// Defaults to `false` if the key is absent.
+    pub(crate) fn compact_panics(&self, profile: Profile) -> bool {
+        self.airbender
+            .profile
+            .get(profile.as_str())
+            .is_some_and(|p| p.compact_panics)
+    }
+
+    /// Returns the memory layout from `package.metadata.airbender.memory`, if any.
+    pub(crate) fn memory_layout(&self) -> Result<Option<MemoryLayout>> {
+        let Some(memory) = &self.airbender.memory else {
+            return Ok(None);
+        };
+        let parse = |key: &str, value: &Option<String>| {
+            value
+                .as_deref()
+                .map(MemoryLayout::parse_size)
+                .transpose()
+                .map_err(|err| {
+                    BuildError::InvalidConfig(format!(
+                        "package.metadata.airbender.memory.{key}: {err}"
+                    ))
+                })
+        };
+        Ok(Some(MemoryLayout {
+            stack: parse("stack", &memory.stack)?,
+            heap: parse("heap", &memory.heap)?,
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -132,6 +195,7 @@ mod tests {
             bin_targets: vec!["guest".to_string()],
             workspace_root: std::path::PathBuf::new(),
             airbender: serde_json::from_str(json).expect("parse airbender config"),
+            atomics: false,
         }
     }
 
@@ -146,6 +210,33 @@ mod tests {
         assert!(m.panic_immediate_abort(Profile::Debug));
     }
 
+    #[test]
+    fn compact_panics_reads_correct_profile() {
+        let m = make_metadata(r#"{"profile": {"release": {"compact-panics": true}}}"#);
+        assert!(m.compact_panics(Profile::Release));
+        assert!(!m.compact_panics(Profile::Debug));
+        assert!(!m.panic_immediate_abort(Profile::Release));
+    }
+
+    #[test]
+    fn memory_layout_parses_sizes() {
+        let m = make_metadata(r#"{"memory": {"stack": "256K", "heap": "16M"}}"#);
+        assert_eq!(
+            m.memory_layout().expect("layout"),
+            Some(MemoryLayout {
+                stack: Some(256 << 10),
+                heap: Some(16 << 20),
+            })
+        );
+        assert_eq!(make_metadata("{}").memory_layout().expect("layout"), None);
+
+        let m = make_metadata(r#"{"memory": {"stack": "lots"}}"#);
+        assert!(matches!(
+            m.memory_layout(),
+            Err(BuildError::InvalidConfig(message)) if message.contains("memory.stack")
+        ));
+    }
+
     #[test]
     fn airbender_config_tolerates_missing_metadata() {
         let null: AirbenderConfig =
@@ -181,6 +272,23 @@ mod tests {
         let m = CargoMetadata::load(&temp_dir.path().join("Cargo.toml")).expect("load manifest");
         assert!(!m.panic_immediate_abort(Profile::Release));
         assert!(!m.panic_immediate_abort(Profile::Debug));
+        assert!(!m.atomics);
+    }
+
+    #[test]
+    fn load_detects_runtime_atomics_features() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        std::fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"atomics\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+             [dependencies]\nairbender = { package = \"airbender-sdk\", version = \"0.1\", \
+             features = [\"portable-atomic\"] }\n",
+        )
+        .expect("write Cargo.toml");
+        std::fs::create_dir(temp_dir.path().join("src")).expect("create src");
+        std::fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").expect("write main.rs");
+        let m = CargoMetadata::load(&temp_dir.path().join("Cargo.toml")).expect("load manifest");
+        assert!(m.atomics);
     }
 
     #[test]
@@ -0,0 +1,222 @@
+//! Panic site table extracted from `app.elf` for compact-panic guests.
+//!
+//! The compact panic handler reports a panic as the site ID of its `core::panic::Location`.
+//! Those locations are constants in the loaded image, so the table lists every location found
+//! there. Every `compact_panic!` call site also leaves a record with its message in the
+//! `.airbender_panics` section, which the compact-panic linker script keeps in `app.elf`
+//! without loading it.
+
+use crate::elf::{data_sections, read_str, read_u32, section_headers};
+use crate::errors::{BuildError, Result};
+use airbender_core::host::panics::{PanicLocation, PanicTable};
+use airbender_core::panic_site::{decode_record, site_id, PANIC_SITE_SECTION};
+use std::fs;
+use std::path::Path;
+
+/// Reads `elf_path`, collects its panic sites, and writes them to `table_path`.
+pub(crate) fn write_panic_table(elf_path: &Path, table_path: &Path) -> Result<()> {
+    let elf = fs::read(elf_path)?;
+    read_panic_sites(&elf)?.write_to_file(table_path)?;
+    Ok(())
+}
+
+/// Collects the panic locations of `elf`, with the `compact_panic!` messages where known.
+fn read_panic_sites(elf: &[u8]) -> Result<PanicTable> {
+    // `PanicTable::new` keeps the first entry per ID, so sites with messages go first.
+    let mut locations = read_site_records(elf)?;
+    locations.extend(read_locations(elf)?);
+    Ok(PanicTable::new(locations))
+}
+
+/// Longest file name accepted for a panic location.
+const MAX_FILE_LEN: u32 = 4096;
+
+/// Finds the `core::panic::Location` constants in the loaded data of `elf`.
+///
+/// On the 32-bit guest target a location is four words: the file name pointer and length,
+/// the line, and the column. Words that only look like one add IDs that are never reported,
+/// which is harmless.
+fn read_locations(elf: &[u8]) -> Result<Vec<PanicLocation>> {
+    let sections = data_sections(elf)?;
+    let mut locations = Vec::new();
+    for section in &sections {
+        for offset in (0..section.data.len().saturating_sub(15)).step_by(4) {
+            let word = |index: usize| read_u32(section.data, offset + index * 4).unwrap_or(0);
+            let (file_addr, file_len, line, column) = (word(0), word(1), word(2), word(3));
+            if file_len == 0 || file_len > MAX_FILE_LEN || line == 0 || column == 0 {
+                continue;
+            }
+            let Some(file) = read_str(&sections, file_addr, file_len) else {
+                continue;
+            };
+            if !file.ends_with(".rs") {
+                continue;
+            }
+            locations.push(PanicLocation {
+                id: site_id(file, line, column),
+                file: file.to_string(),
+                line,
+                column,
+                message: String::new(),
+            });
+        }
+    }
+    Ok(locations)
+}
+
+/// Decodes the panic site section of `elf`; a guest without `compact_panic!` calls has none.
+fn read_site_records(elf: &[u8]) -> Result<Vec<PanicLocation>> {
+    let invalid = |reason: &str| {
+        BuildError::InvalidConfig(format!("invalid {PANIC_SITE_SECTION} section: {reason}"))
+    };
+    let headers = section_headers(elf)?;
+    let Some(section) = headers
+        .iter()
+        .find(|header| header.name == PANIC_SITE_SECTION)
+    else {
+        return Ok(Vec::new());
+    };
+    if section.is_loaded() {
+        return Err(invalid(
+            "it is part of the guest image; link with the compact-panic linker script",
+        ));
+    }
+
+    let mut locations = Vec::new();
+    let mut rest = section.data;
+    while !rest.is_empty() {
+        let (site, len) = decode_record(rest).ok_or_else(|| {
+            invalid(&format!(
+                "malformed record at offset {:#x}",
+                section.data.len() - rest.len()
+            ))
+        })?;
+        locations.push(PanicLocation {
+            id: site.id,
+            file: site.file.to_string(),
+            line: site.line,
+            column: site.column,
+            message: site.message.to_string(),
+        });
+        rest = rest.get(len..).unwrap_or_default();
+    }
+    Ok(locations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::tests::elf_with_sections;
+    use airbender_core::panic_site::{encode_record, record_len};
+
+    const SHT_PROGBITS: u32 = 1;
+    const SHF_ALLOC: u32 = 0x2;
+
+    #[test]
+    fn reads_sites_from_the_unloaded_section() {
+        const FIRST: usize = record_len("src/main.rs", "input must be even");
+        const SECOND: usize = record_len("src/lib.rs", "");
+        let mut records =
+            encode_record::<FIRST>("src/main.rs", 12, 5, "input must be even").to_vec();
+        records.extend(encode_record::<SECOND>("src/lib.rs", 3, 9, ""));
+        let elf = elf_with_sections(&[(
+            PANIC_SITE_SECTION,
+            SHT_PROGBITS,
+            0,
+            0,
+            records.len() as u32,
+            &records,
+        )]);
+
+        let table = read_panic_sites(&elf).expect("table");
+        assert_eq!(table.entries.len(), 2);
+        assert_eq!(
+            table
+                .resolve(site_id("src/main.rs", 12, 5))
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("src/main.rs:12:5: input must be even")
+        );
+        assert_eq!(
+            table
+                .resolve(site_id("src/lib.rs", 3, 9))
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("src/lib.rs:3:9")
+        );
+
+        let no_sites =
+            elf_with_sections(&[(".rodata", SHT_PROGBITS, SHF_ALLOC, 0x1000, 4, b"abcd")]);
+        assert_eq!(
+            read_panic_sites(&no_sites).expect("table"),
+            PanicTable::default()
+        );
+    }
+
+    #[test]
+    fn reads_locations_from_loaded_data() {
+        // "src/main.rs" at 0x1000, then a location pointing at it and one that does not.
+        let mut rodata = b"src/main.rs\0".to_vec();
+        for word in [0x1000, 11, 7, 13, 0x9000, 11, 7, 13] {
+            rodata.extend(u32::to_le_bytes(word));
+        }
+        const LEN: usize = record_len("src/main.rs", "boom");
+        let record = encode_record::<LEN>("src/main.rs", 12, 5, "boom");
+        let elf = elf_with_sections(&[
+            (
+                ".rodata",
+                SHT_PROGBITS,
+                SHF_ALLOC,
+                0x1000,
+                rodata.len() as u32,
+                &rodata,
+            ),
+            (PANIC_SITE_SECTION, SHT_PROGBITS, 0, 0, LEN as u32, &record),
+        ]);
+
+        let table = read_panic_sites(&elf).expect("table");
+        assert_eq!(table.entries.len(), 2);
+        assert_eq!(
+            table
+                .resolve(site_id("src/main.rs", 7, 13))
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("src/main.rs:7:13")
+        );
+        assert_eq!(
+            table
+                .resolve(site_id("src/main.rs", 12, 5))
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("src/main.rs:12:5: boom")
+        );
+    }
+
+    #[test]
+    fn rejects_loaded_or_malformed_sections() {
+        const LEN: usize = record_len("src/main.rs", "boom");
+        let record = encode_record::<LEN>("src/main.rs", 1, 1, "boom");
+
+        let loaded = elf_with_sections(&[(
+            PANIC_SITE_SECTION,
+            SHT_PROGBITS,
+            SHF_ALLOC,
+            0x1000,
+            LEN as u32,
+            &record,
+        )]);
+        let err = read_panic_sites(&loaded).expect_err("loaded section");
+        assert!(err.to_string().contains("part of the guest image"));
+
+        let truncated = elf_with_sections(&[(
+            PANIC_SITE_SECTION,
+            SHT_PROGBITS,
+            0,
+            0,
+            LEN as u32 - 4,
+            &record[..LEN - 4],
+        )]);
+        let err = read_panic_sites(&truncated).expect_err("truncated record");
+        assert!(err.to_string().contains("malformed record at offset 0x0"));
+    }
+}
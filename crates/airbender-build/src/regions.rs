@@ -0,0 +1,151 @@
+//! Region name table extracted from `app.elf` for guests with named cycle-marker regions.
+//!
+//! `record_cycles!` places a `RegionLabel` record for every region in the loaded
+//! `.airbender_regions` section: the label magic, followed by the pointer and length of the
+//! region name. Label IDs are the addresses of these records, so the table maps every record
+//! in that section to its name.
+
+use crate::elf::{data_sections, read_str, read_u32, section_headers, Section};
+use crate::errors::{BuildError, Result};
+use airbender_core::cycle::{REGION_LABEL_MAGIC, REGION_LABEL_SECTION};
+use airbender_core::host::regions::{RegionName, RegionTable};
+use std::fs;
+use std::path::Path;
+
+/// Size of one label record.
+const RECORD_BYTES: usize = 12;
+
+/// Longest region name accepted as part of a label record.
+const MAX_NAME_LEN: u32 = 1024;
+
+/// Reads `elf_path` and collects its region labels.
+pub(crate) fn read_region_table(elf_path: &Path) -> Result<RegionTable> {
+    let elf = fs::read(elf_path)?;
+    read_labels(&elf)
+}
+
+/// Decodes the label section of `elf`; a guest without named regions has none.
+fn read_labels(elf: &[u8]) -> Result<RegionTable> {
+    let invalid = |reason: &str| {
+        BuildError::InvalidConfig(format!("invalid {REGION_LABEL_SECTION} section: {reason}"))
+    };
+    let headers = section_headers(elf)?;
+    let Some(section) = headers
+        .iter()
+        .find(|header| header.name == REGION_LABEL_SECTION)
+    else {
+        return Ok(RegionTable::default());
+    };
+    if !section.is_loaded() {
+        return Err(invalid("it is not part of the guest image"));
+    }
+    if section.data.len() % RECORD_BYTES != 0 {
+        return Err(invalid("its size is not a whole number of records"));
+    }
+
+    let sections = data_sections(elf)?;
+    let mut regions = Vec::new();
+    for offset in (0..section.data.len()).step_by(RECORD_BYTES) {
+        let words: [u32; 3] =
+            std::array::from_fn(|index| read_u32(section.data, offset + index * 4).unwrap());
+        let name = label_at(&sections, words)
+            .ok_or_else(|| invalid(&format!("malformed record at offset {offset:#x}")))?;
+        regions.push(RegionName {
+            id: section.addr + offset as u32,
+            name: name.to_string(),
+        });
+    }
+    Ok(RegionTable::new(regions))
+}
+
+/// Decodes `[magic, name_ptr, name_len]` if it is a label record.
+fn label_at<'a>(sections: &[Section<'a>], words: [u32; 3]) -> Option<&'a str> {
+    let [magic, name_ptr, name_len] = words;
+    if magic != REGION_LABEL_MAGIC || !(1..=MAX_NAME_LEN).contains(&name_len) {
+        return None;
+    }
+    read_str(sections, name_ptr, name_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::tests::elf_with_sections;
+
+    const SHT_PROGBITS: u32 = 1;
+    const SHF_ALLOC: u32 = 0x2;
+    const SHF_WRITE: u32 = 0x1;
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn elf_with_labels(flags: u32, labels: &[u8]) -> Vec<u8> {
+        let names = b"hashverify";
+        // Data outside the label section that looks like a record is not a label.
+        let lookalike = words(&[REGION_LABEL_MAGIC, 0x1000, 4]);
+        elf_with_sections(&[
+            (".rodata", SHT_PROGBITS, SHF_ALLOC, 0x1000, 10, names),
+            (
+                ".data",
+                SHT_PROGBITS,
+                SHF_ALLOC | SHF_WRITE,
+                0x2000,
+                12,
+                &lookalike,
+            ),
+            (
+                REGION_LABEL_SECTION,
+                SHT_PROGBITS,
+                flags,
+                0x3000,
+                labels.len() as u32,
+                labels,
+            ),
+        ])
+    }
+
+    #[test]
+    fn reads_label_records_from_their_section() {
+        let labels = words(&[REGION_LABEL_MAGIC, 0x1000, 4, REGION_LABEL_MAGIC, 0x1004, 6]);
+        let table = read_labels(&elf_with_labels(SHF_ALLOC, &labels)).expect("table");
+        assert_eq!(table.entries.len(), 2);
+        assert_eq!(
+            table.resolve(0x3000).map(|region| region.name.as_str()),
+            Some("hash")
+        );
+        assert_eq!(
+            table.resolve(0x300c).map(|region| region.name.as_str()),
+            Some("verify")
+        );
+
+        let no_labels =
+            elf_with_sections(&[(".rodata", SHT_PROGBITS, SHF_ALLOC, 0x1000, 4, b"abcd")]);
+        assert_eq!(
+            read_labels(&no_labels).expect("table"),
+            RegionTable::default()
+        );
+    }
+
+    #[test]
+    fn rejects_unloaded_or_malformed_sections() {
+        let label = words(&[REGION_LABEL_MAGIC, 0x1000, 4]);
+        let err = read_labels(&elf_with_labels(0, &label)).expect_err("unloaded section");
+        assert!(err.to_string().contains("not part of the guest image"));
+
+        let err = read_labels(&elf_with_labels(SHF_ALLOC, &label[..8])).expect_err("truncated");
+        assert!(err.to_string().contains("not a whole number of records"));
+
+        for record in [
+            [0x1234_5678, 0x1000, 4],        // wrong magic
+            [REGION_LABEL_MAGIC, 0x5000, 4], // name outside the image
+        ] {
+            let err = read_labels(&elf_with_labels(SHF_ALLOC, &words(&record)))
+                .expect_err("malformed record");
+            assert!(err.to_string().contains("malformed record at offset 0x0"));
+        }
+    }
+}
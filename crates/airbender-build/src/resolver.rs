@@ -4,7 +4,7 @@ use crate::build::DistApp;
 use crate::config::BuildConfig;
 use crate::errors::{BuildError, Result};
 use crate::metadata::CargoMetadata;
-use crate::DEFAULT_GUEST_TARGET;
+use crate::{MemoryLayout, DEFAULT_GUEST_TARGET};
 use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 
@@ -29,6 +29,12 @@ pub struct ResolvedBuildParams {
     pub mount_root: PathBuf,
     /// Effective `panic_immediate_abort` flag after merging CLI and manifest settings.
     pub panic_immediate_abort: bool,
+    /// Whether the guest reports panics as location IDs (`compact-panics` metadata).
+    pub compact_panics: bool,
+    /// Whether the guest enables the runtime's `critical-section` or `portable-atomic` support.
+    pub atomics: bool,
+    /// Guest memory layout from `[package.metadata.airbender]`, if configured.
+    pub memory_layout: Option<MemoryLayout>,
     /// Git metadata for the project.
     pub git: GitMetadata,
 }
@@ -51,6 +57,8 @@ impl ResolvedBuildParams {
         let bin_name = Self::resolve_bin_name(build_config, &project_metadata)?;
         let mount_root = Self::resolve_mount_root(build_config, &project_metadata, cwd);
         let panic_immediate_abort = project_metadata.panic_immediate_abort(build_config.profile);
+        let compact_panics = project_metadata.compact_panics(build_config.profile);
+        let memory_layout = project_metadata.memory_layout()?;
         // Omit bin_name from the manifest when it matches the package name — the common case.
         // Downstream tooling treats an absent bin_name as identical to package_name.
         let manifest_bin_name =
@@ -66,6 +74,9 @@ impl ResolvedBuildParams {
             dist_app: DistApp::new(dist_dir),
             mount_root,
             panic_immediate_abort,
+            compact_panics,
+            atomics: project_metadata.atomics,
+            memory_layout,
             git,
         })
     }
@@ -290,6 +301,8 @@ mod tests {
         assert_eq!(params.manifest_bin_name, None);
         assert_eq!(params.target, DEFAULT_GUEST_TARGET);
         assert!(!params.panic_immediate_abort);
+        assert!(!params.compact_panics);
+        assert_eq!(params.memory_layout, None);
         assert_eq!(params.dist_app.dir(), dir.path().join("dist/app"));
     }
 
@@ -311,6 +324,24 @@ mod tests {
         assert!(!params.panic_immediate_abort);
     }
 
+    #[test]
+    fn resolve_accepts_compact_panics_with_immediate_abort() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        write_minimal_project(dir.path());
+        let manifest_path = dir.path().join("Cargo.toml");
+        let mut manifest = std::fs::read_to_string(&manifest_path).expect("read Cargo.toml");
+        manifest.push_str(
+            "\n[package.metadata.airbender.profile.release]\n\
+             compact-panics = true\npanic-immediate-abort = true\n",
+        );
+        std::fs::write(&manifest_path, manifest).expect("write Cargo.toml");
+
+        let config = BuildConfig::new(dir.path());
+        let params = ResolvedBuildParams::resolve(&config, dir.path()).expect("resolve");
+        assert!(params.compact_panics);
+        assert!(params.panic_immediate_abort);
+    }
+
     #[test]
     fn resolve_manifest_bin_name_omitted_when_matches_package() {
         let dir = tempfile::tempdir().expect("create temp dir");
@@ -459,6 +490,7 @@ mod tests {
             bin_targets: bin_targets.iter().map(|s| s.to_string()).collect(),
             airbender: Default::default(),
             workspace_root: PathBuf::new(),
+            atomics: false,
         }
     }
 
@@ -0,0 +1,106 @@
+use airbender_build::{build_dist, BuildConfig};
+use std::fs;
+use std::path::Path;
+
+/// Absolute path to the `airbender-sdk` crate, resolved relative to this crate's manifest.
+fn sdk_path() -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../airbender-sdk")
+        .canonicalize()
+        .expect("airbender-sdk must exist next to airbender-build")
+}
+
+fn write_file(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// Scaffolds a guest using `once_cell::race` and `portable_atomic` through the SDK's
+/// `portable-atomic` feature.
+///
+/// Its Cargo config leaves out `-C passes=lower-atomic`, so the build only succeeds if
+/// `airbender-build` adds the atomics flags itself.
+fn scaffold_atomics_guest(dir: &Path) {
+    write_file(
+        &dir.join("Cargo.toml"),
+        &format!(
+            r#"[package]
+name = "atomics-probe"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+airbender = {{ package = "airbender-sdk", path = "{}", features = ["portable-atomic"] }}
+once_cell = {{ version = "1", default-features = false, features = ["race", "critical-section"] }}
+portable-atomic = {{ version = "1", default-features = false }}
+"#,
+            sdk_path().display()
+        ),
+    );
+
+    write_file(
+        &dir.join(".cargo/config.toml"),
+        r#"[build]
+target = "riscv32im-risc0-zkvm-elf"
+rustflags = [
+  "-C", "target-feature=+m,-unaligned-scalar-mem,+relax",
+  "-C", "link-arg=-Tmemory.x",
+  "-C", "link-arg=-Tlink.x",
+  "-C", "link-arg=--save-temps",
+  "-C", "force-frame-pointers",
+  "--cfg", "getrandom_backend=\"custom\"",
+]
+
+[env]
+CC = "clang"
+
+[unstable]
+build-std = ["alloc", "core", "panic_abort", "compiler_builtins", "std", "proc_macro"]
+build-std-features = ["compiler-builtins-mem"]
+"#,
+    );
+
+    write_file(
+        &dir.join("rust-toolchain.toml"),
+        r#"[toolchain]
+channel = "nightly-2026-02-10"
+"#,
+    );
+
+    write_file(
+        &dir.join("src/main.rs"),
+        r#"#![no_std]
+#![no_main]
+
+use core::num::NonZeroUsize;
+use once_cell::race::OnceNonZeroUsize;
+use portable_atomic::{AtomicU64, Ordering};
+
+static FIRST: OnceNonZeroUsize = OnceNonZeroUsize::new();
+static TOTAL: AtomicU64 = AtomicU64::new(0);
+
+#[airbender::main]
+fn main() -> u32 {
+    let first = FIRST.get_or_init(|| NonZeroUsize::new(7).unwrap()).get();
+    TOTAL.fetch_add(first as u64, Ordering::Relaxed);
+    TOTAL.load(Ordering::Relaxed) as u32
+}
+"#,
+    );
+}
+
+/// Verifies that guests enabling the runtime's atomics support build without adding atomics
+/// flags to their own Cargo config.
+#[test]
+fn atomics_guest_builds_without_config_flags() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let guest = tmp.path().join("guest");
+    scaffold_atomics_guest(&guest);
+
+    let mut config = BuildConfig::new(&guest);
+    config.dist_dir = Some(tmp.path().join("dist"));
+    let artifacts = build_dist(&config).expect("build atomics guest");
+
+    let bin = fs::read(&artifacts.app_bin.path).expect("read app.bin");
+    assert!(!bin.is_empty(), "app.bin must not be empty");
+}
@@ -0,0 +1,105 @@
+//! Binding of public outputs to the input words consumed by the guest.
+//!
+//! The input digest is Keccak-256 over the little-endian bytes of every word read from the
+//! input stream. A bound output replaces the user output with Keccak-256 over the user output
+//! words followed by the input digest words, so verifying the output also pins the inputs.
+//!
+//! `std` guests also fold their program environment preamble into the digest, after all input
+//! words: [`PREAMBLE_REQUEST_MARKER`] followed by the preamble words.
+
+use crate::journal::digest_to_words;
+use crate::preamble::PREAMBLE_REQUEST_MARKER;
+use sha3::Digest;
+
+/// Running digest over input words read by the guest.
+#[derive(Clone, Default)]
+pub struct InputHasher {
+    hasher: sha3::Keccak256,
+}
+
+impl InputHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update_word(&mut self, word: u32) {
+        self.hasher.update(word.to_le_bytes());
+    }
+
+    /// Fold the preamble section of a `std` guest.
+    pub fn update_preamble(&mut self, preamble_words: &[u32]) {
+        self.update_word(PREAMBLE_REQUEST_MARKER);
+        for word in preamble_words {
+            self.update_word(*word);
+        }
+    }
+
+    /// Digest of all words folded so far, laid out as output words.
+    pub fn digest_words(&self) -> [u32; 8] {
+        digest_to_words(self.hasher.clone().finalize().into())
+    }
+}
+
+/// Compute the input digest words for a full input word stream.
+pub fn input_digest_words(words: &[u32]) -> [u32; 8] {
+    let mut hasher = InputHasher::new();
+    for word in words {
+        hasher.update_word(*word);
+    }
+    hasher.digest_words()
+}
+
+/// Compute the input digest words of a `std` guest from its input words and preamble words.
+pub fn input_digest_words_with_preamble(words: &[u32], preamble_words: &[u32]) -> [u32; 8] {
+    let mut hasher = InputHasher::new();
+    for word in words {
+        hasher.update_word(*word);
+    }
+    hasher.update_preamble(preamble_words);
+    hasher.digest_words()
+}
+
+/// Combine user output words with an input digest into the committed output words.
+pub fn bind_output(output: &[u32; 8], input_digest: &[u32; 8]) -> [u32; 8] {
+    let mut hasher = sha3::Keccak256::new();
+    for word in output.iter().chain(input_digest) {
+        hasher.update(word.to_le_bytes());
+    }
+    digest_to_words(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incremental_digest_matches_full_stream() {
+        let words = [1u32, 2, 3, u32::MAX];
+        let mut hasher = InputHasher::new();
+        for word in words {
+            hasher.update_word(word);
+        }
+        assert_eq!(hasher.digest_words(), input_digest_words(&words));
+    }
+
+    #[test]
+    fn bound_output_depends_on_inputs() {
+        let output = [42u32, 0, 0, 0, 0, 0, 0, 0];
+        let first = bind_output(&output, &input_digest_words(&[1]));
+        let second = bind_output(&output, &input_digest_words(&[2]));
+        assert_ne!(first, second);
+        assert_ne!(first, output);
+    }
+
+    #[test]
+    fn preamble_changes_digest() {
+        let words = [1u32, 2];
+        let plain = input_digest_words(&words);
+        let empty = input_digest_words_with_preamble(&words, &[0, 0, 0]);
+        assert_ne!(plain, empty);
+        assert_ne!(
+            empty,
+            input_digest_words_with_preamble(&words, &[0, 0, 1, 7])
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Named cycle-marker regions shared between host and guest crates.
+//!
+//! A named region is bracketed by two cycle markers. Next to each one the guest writes
+//! [`CYCLE_REGION_MARKER`] and the two words of a [`RegionEvent`]: the region label ID with
+//! the event kind in its low bits, and the index of the marker the event belongs to. The label
+//! ID is the address of a [`RegionLabel`] record. `record_cycles!` places the records in the
+//! loaded [`REGION_LABEL_SECTION`] section, which build tooling reads from `app.elf` to map IDs
+//! back to region names.
+
+/// Transport word announcing a region event.
+pub const CYCLE_REGION_MARKER: u32 = 0x5245_474e; // "REGN"
+
+/// ELF section holding the [`RegionLabel`] records, one after another.
+pub const REGION_LABEL_SECTION: &str = ".airbender_regions";
+
+/// First word of every [`RegionLabel`] record in the guest image.
+pub const REGION_LABEL_MAGIC: u32 = 0x524c_424c; // "RLBL"
+
+const KIND_MASK: u32 = 0x3;
+const KIND_ENTER: u32 = 1;
+const KIND_EXIT: u32 = 2;
+
+/// Name record of one region, placed in the guest image by `record_cycles!`.
+///
+/// On `riscv32` the record is three words: the magic, the name pointer, and the name length.
+#[repr(C)]
+pub struct RegionLabel {
+    magic: u32,
+    name_ptr: *const u8,
+    name_len: usize,
+}
+
+// Labels are immutable statics pointing at string literals.
+unsafe impl Sync for RegionLabel {}
+
+impl RegionLabel {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            magic: REGION_LABEL_MAGIC,
+            name_ptr: name.as_ptr(),
+            name_len: name.len(),
+        }
+    }
+
+    /// Label ID reported to the host: the address of the record.
+    pub fn id(&'static self) -> u32 {
+        core::ptr::from_ref(self) as usize as u32
+    }
+}
+
+/// Whether a region event opens or closes its region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegionKind {
+    Enter,
+    Exit,
+}
+
+/// One region boundary, tied to the cycle marker emitted with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegionEvent {
+    /// Label ID, a word-aligned [`RegionLabel`] address.
+    pub label: u32,
+    pub kind: RegionKind,
+    /// Index of the cycle marker in the run, counting from zero.
+    pub marker: u32,
+}
+
+impl RegionEvent {
+    /// Number of words following [`CYCLE_REGION_MARKER`].
+    pub const WORDS: usize = 2;
+
+    pub const fn to_words(&self) -> [u32; Self::WORDS] {
+        let kind = match self.kind {
+            RegionKind::Enter => KIND_ENTER,
+            RegionKind::Exit => KIND_EXIT,
+        };
+        [self.label | kind, self.marker]
+    }
+
+    pub const fn from_words(words: [u32; Self::WORDS]) -> Option<Self> {
+        let kind = match words[0] & KIND_MASK {
+            KIND_ENTER => RegionKind::Enter,
+            KIND_EXIT => RegionKind::Exit,
+            _ => return None,
+        };
+        Some(Self {
+            label: words[0] & !KIND_MASK,
+            kind,
+            marker: words[1],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RegionEvent, RegionKind};
+
+    #[test]
+    fn region_event_words_roundtrip() {
+        for kind in [RegionKind::Enter, RegionKind::Exit] {
+            let event = RegionEvent {
+                label: 0x0001_2340,
+                kind,
+                marker: 7,
+            };
+            assert_eq!(RegionEvent::from_words(event.to_words()), Some(event));
+        }
+        assert_eq!(RegionEvent::from_words([0x0001_2340, 7]), None);
+        assert_eq!(RegionEvent::from_words([0x0001_2343, 7]), None);
+    }
+}
@@ -0,0 +1,25 @@
+//! Guest exit signalling shared between host and guest crates.
+
+/// Transport word announcing that the next UART frame carries the guest error message.
+///
+/// The guest writes it before exiting with an error from a `Result`-returning entry point.
+pub const GUEST_ERROR_MARKER: u32 = 0x4552_5252; // "ERRR"
+
+/// Transport word written by the runtime before the UART frames carrying a panic message.
+pub const GUEST_PANIC_MARKER: u32 = 0x5041_4e43; // "PANC"
+
+/// Transport word written by the runtime right before exiting through the error path.
+///
+/// Hosts use it to tell error exits (including panics) apart from successful ones.
+pub const GUEST_ABORT_MARKER: u32 = 0x4142_5254; // "ABRT"
+
+/// Transport word written by the runtime when it finds the stack guard region overwritten.
+///
+/// It is followed by a UART frame with a `STACK_OVERFLOW` message and an error exit.
+pub const GUEST_STACK_OVERFLOW_MARKER: u32 = 0x5354_4f56; // "STOV"
+
+/// Transport word written by the panic handler and `compact_panic!` in compact-panic guests.
+///
+/// It is followed by one word with the panic site ID, resolved through the dist `panics.toml`
+/// table, and an error exit.
+pub const GUEST_PANIC_LOCATION_MARKER: u32 = 0x504c_4f43; // "PLOC"
@@ -1,8 +1,41 @@
 //! Guest-side output commitment traits shared between host and guest crates.
 
+use core::fmt;
+
 /// Values that can be committed to the public output registers (`x10..x17`).
 pub trait Commit {
     fn commit_words(&self) -> [u32; 8];
+
+    /// Describe `words` as a value of this type, for example in verification errors.
+    fn fmt_words(&self, words: &[u32; 8], out: &mut dyn fmt::Write) -> fmt::Result {
+        write!(out, "{words:?}")
+    }
+}
+
+/// Values that can be decoded back from the public output registers.
+pub trait FromCommit: Sized {
+    fn from_commit_words(words: &[u32; 8]) -> Self;
+}
+
+/// Fixed-width values that can be packed as fields of a `#[derive(Commit)]` type.
+pub trait CommitField: Sized {
+    /// Number of output words occupied by the value.
+    const WORDS: usize;
+
+    /// Write the value into exactly `Self::WORDS` words.
+    fn write_words(&self, words: &mut [u32]);
+
+    /// Read the value back from exactly `Self::WORDS` words.
+    fn read_words(words: &[u32]) -> Self;
+
+    /// Describe the value stored in `words`.
+    fn fmt_field(words: &[u32], out: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+impl<T: CommitField> FromCommit for T {
+    fn from_commit_words(words: &[u32; 8]) -> Self {
+        T::read_words(&words[..T::WORDS])
+    }
 }
 
 impl Commit for () {
@@ -48,6 +81,112 @@ impl Commit for [u32; 8] {
     }
 }
 
+impl FromCommit for () {
+    fn from_commit_words(_words: &[u32; 8]) -> Self {}
+}
+
+macro_rules! debug_fmt_field {
+    () => {
+        fn fmt_field(words: &[u32], out: &mut dyn fmt::Write) -> fmt::Result {
+            write!(out, "{:?}", Self::read_words(words))
+        }
+    };
+}
+
+impl CommitField for u32 {
+    const WORDS: usize = 1;
+
+    fn write_words(&self, words: &mut [u32]) {
+        words[0] = *self;
+    }
+
+    fn read_words(words: &[u32]) -> Self {
+        words[0]
+    }
+
+    debug_fmt_field!();
+}
+
+impl CommitField for u64 {
+    const WORDS: usize = 2;
+
+    fn write_words(&self, words: &mut [u32]) {
+        words[0] = *self as u32;
+        words[1] = (*self >> 32) as u32;
+    }
+
+    fn read_words(words: &[u32]) -> Self {
+        u64::from(words[0]) | (u64::from(words[1]) << 32)
+    }
+
+    debug_fmt_field!();
+}
+
+impl CommitField for i64 {
+    const WORDS: usize = 2;
+
+    fn write_words(&self, words: &mut [u32]) {
+        (*self as u64).write_words(words)
+    }
+
+    fn read_words(words: &[u32]) -> Self {
+        u64::read_words(words) as i64
+    }
+
+    debug_fmt_field!();
+}
+
+impl CommitField for bool {
+    const WORDS: usize = 1;
+
+    fn write_words(&self, words: &mut [u32]) {
+        words[0] = u32::from(*self);
+    }
+
+    fn read_words(words: &[u32]) -> Self {
+        words[0] != 0
+    }
+
+    debug_fmt_field!();
+}
+
+impl CommitField for [u32; 8] {
+    const WORDS: usize = 8;
+
+    fn write_words(&self, words: &mut [u32]) {
+        words.copy_from_slice(self);
+    }
+
+    fn read_words(words: &[u32]) -> Self {
+        let mut value = [0u32; 8];
+        value.copy_from_slice(words);
+        value
+    }
+
+    debug_fmt_field!();
+}
+
+/// Bytes are packed little-endian into words, matching `u32::from_le_bytes`.
+impl CommitField for [u8; 32] {
+    const WORDS: usize = 8;
+
+    fn write_words(&self, words: &mut [u32]) {
+        for (word, chunk) in words.iter_mut().zip(self.chunks_exact(4)) {
+            *word = u32::from_le_bytes(chunk.try_into().expect("chunk has 4 bytes"));
+        }
+    }
+
+    fn read_words(words: &[u32]) -> Self {
+        let mut value = [0u8; 32];
+        for (chunk, word) in value.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        value
+    }
+
+    debug_fmt_field!();
+}
+
 impl<T: Commit, E: core::fmt::Debug> Commit for Result<T, E> {
     fn commit_words(&self) -> [u32; 8] {
         match self {
@@ -55,6 +194,13 @@ impl<T: Commit, E: core::fmt::Debug> Commit for Result<T, E> {
             Err(e) => panic!("committed a Result::Err: {:?}", e),
         }
     }
+
+    fn fmt_words(&self, words: &[u32; 8], out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Ok(val) => val.fmt_words(words, out),
+            Err(_) => write!(out, "{words:?}"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -86,6 +232,27 @@ mod tests {
         assert_eq!(words[1], 0);
     }
 
+    #[test]
+    fn from_commit_roundtrips_builtin_layouts() {
+        let value: u64 = 0x11223344_55667788;
+        assert_eq!(u64::from_commit_words(&value.commit_words()), value);
+        assert_eq!(i64::from_commit_words(&(-5i64).commit_words()), -5);
+        assert!(bool::from_commit_words(&true.commit_words()));
+        assert_eq!(u32::from_commit_words(&42u32.commit_words()), 42);
+    }
+
+    #[test]
+    fn commit_field_bytes_layout() {
+        let mut bytes = [0u8; 32];
+        bytes[0] = 0x01;
+        bytes[4] = 0x02;
+        let mut words = [0u32; 8];
+        bytes.write_words(&mut words);
+        assert_eq!(words[0], 1);
+        assert_eq!(words[1], 2);
+        assert_eq!(<[u8; 32]>::read_words(&words), bytes);
+    }
+
     #[test]
     #[should_panic(expected = "committed a Result::Err")]
     fn commit_words_result_err_panics() {
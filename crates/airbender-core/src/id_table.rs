@@ -0,0 +1,136 @@
+//! TOML tables of guest records keyed by the IDs guests report.
+//!
+//! Build tooling collects such records from `app.elf` into a dist file, and host tooling loads
+//! it to turn the IDs a run reports back into something readable. [`crate::panics`] and
+//! [`crate::regions`] are the two tables.
+
+use crate::manifest::ManifestError;
+use serde::de::DeserializeOwned;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Record of an [`IdTable`].
+pub trait IdEntry {
+    /// TOML array-of-tables name of the records, such as `region` for `[[region]]`.
+    const KEY: &'static str;
+
+    /// ID the guest reports for this record.
+    fn id(&self) -> u32;
+}
+
+/// Records of one guest image, sorted by ID.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdTable<T> {
+    pub entries: Vec<T>,
+}
+
+impl<T> Default for IdTable<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T: IdEntry> IdTable<T> {
+    /// Builds a table from `entries` in any order; later duplicates of an ID are dropped.
+    pub fn new(mut entries: Vec<T>) -> Self {
+        entries.sort_by_key(IdEntry::id);
+        entries.dedup_by_key(|entry| entry.id());
+        Self { entries }
+    }
+
+    /// Record reported with `id`, if the table has it.
+    pub fn resolve(&self, id: u32) -> Option<&T> {
+        self.entries
+            .binary_search_by_key(&id, IdEntry::id)
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+}
+
+impl<T: IdEntry + Serialize + DeserializeOwned> IdTable<T> {
+    /// Read a table from a TOML file.
+    pub fn read_from_file(path: &Path) -> Result<Self, ManifestError> {
+        let content = fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Write this table to a TOML file.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), ManifestError> {
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+impl<T: IdEntry + Serialize> Serialize for IdTable<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(T::KEY, &self.entries)?;
+        map.end()
+    }
+}
+
+impl<'de, T: IdEntry + Deserialize<'de>> Deserialize<'de> for IdTable<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut tables = BTreeMap::<String, Vec<T>>::deserialize(deserializer)?;
+        Ok(Self::new(tables.remove(T::KEY).unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Label {
+        id: u32,
+        name: String,
+    }
+
+    impl IdEntry for Label {
+        const KEY: &'static str = "label";
+
+        fn id(&self) -> u32 {
+            self.id
+        }
+    }
+
+    fn label(id: u32, name: &str) -> Label {
+        Label {
+            id,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn resolves_ids_after_toml_roundtrip() {
+        let table = IdTable::new(vec![
+            label(0x2040, "verify"),
+            label(0x1000, "hash"),
+            label(0x2040, "duplicate"),
+        ]);
+        assert_eq!(table.entries.len(), 2);
+        let toml = toml::to_string(&table).expect("serialize");
+        assert!(toml.contains("[[label]]"));
+
+        let parsed: IdTable<Label> = toml::from_str(&toml).expect("parse");
+        assert_eq!(parsed, table);
+        assert_eq!(parsed.resolve(0x1000), Some(&label(0x1000, "hash")));
+        assert_eq!(parsed.resolve(0x2040), Some(&label(0x2040, "verify")));
+        assert_eq!(parsed.resolve(0x2044), None);
+    }
+
+    #[test]
+    fn sorts_unsorted_files_and_reads_empty_ones() {
+        let unsorted = "[[label]]\nid = 8\nname = \"b\"\n\n[[label]]\nid = 4\nname = \"a\"\n";
+        let parsed: IdTable<Label> = toml::from_str(unsorted).expect("parse");
+        assert_eq!(parsed.entries, vec![label(4, "a"), label(8, "b")]);
+
+        let empty: IdTable<Label> = toml::from_str("").expect("parse empty");
+        assert_eq!(empty, IdTable::default());
+    }
+}
@@ -0,0 +1,139 @@
+//! Public journal layout shared between host and guest crates.
+//!
+//! The journal is an append-only byte stream of entries, where each entry is:
+//! - a little-endian `u32` payload byte length,
+//! - the payload bytes themselves.
+//!
+//! The guest folds every entry into a running Keccak-256 digest and commits it to the
+//! public output registers, while the raw entries are streamed to the host through the
+//! transport as `JOURNAL_MARKER` followed by one framed payload (see [`crate::wire`]).
+
+use alloc::vec::Vec;
+use core::fmt;
+use sha3::Digest;
+
+/// Transport word announcing that a framed journal entry follows.
+pub const JOURNAL_MARKER: u32 = 0x4a52_4e4c; // "JRNL"
+
+const LEN_BYTES: usize = core::mem::size_of::<u32>();
+
+/// Errors that can occur while splitting journal bytes into entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalError {
+    Truncated { offset: usize },
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::Truncated { offset } => {
+                write!(f, "journal entry at byte offset {offset} is truncated")
+            }
+        }
+    }
+}
+
+/// Running digest over journal entries.
+#[derive(Clone, Default)]
+pub struct JournalHasher {
+    hasher: sha3::Keccak256,
+}
+
+impl JournalHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold one entry (length prefix and payload) into the digest.
+    pub fn update_entry(&mut self, payload: &[u8]) {
+        self.hasher.update((payload.len() as u32).to_le_bytes());
+        self.hasher.update(payload);
+    }
+
+    /// Digest of all entries folded so far, laid out as output words.
+    pub fn digest_words(&self) -> [u32; 8] {
+        digest_to_words(self.hasher.clone().finalize().into())
+    }
+}
+
+/// Encode one payload as a journal entry (length prefix and payload).
+pub fn encode_entry(payload: &[u8]) -> Vec<u8> {
+    let mut entry = Vec::with_capacity(LEN_BYTES + payload.len());
+    entry.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    entry.extend_from_slice(payload);
+    entry
+}
+
+/// Compute the committed digest words for raw journal bytes.
+pub fn journal_digest_words(journal: &[u8]) -> [u32; 8] {
+    digest_to_words(sha3::Keccak256::digest(journal).into())
+}
+
+/// Split raw journal bytes into entry payloads.
+pub fn split_entries(journal: &[u8]) -> Result<Vec<&[u8]>, JournalError> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while offset < journal.len() {
+        let (payload, next) = entry_at(journal, offset)?;
+        entries.push(payload);
+        offset = next;
+    }
+    Ok(entries)
+}
+
+/// Read the entry starting at `offset`, returning its payload and the next entry offset.
+pub fn entry_at(journal: &[u8], offset: usize) -> Result<(&[u8], usize), JournalError> {
+    let len_end = offset + LEN_BYTES;
+    let Some(len_bytes) = journal.get(offset..len_end) else {
+        return Err(JournalError::Truncated { offset });
+    };
+    let len = u32::from_le_bytes(len_bytes.try_into().expect("slice has 4 bytes")) as usize;
+    let Some(payload) = journal.get(len_end..len_end + len) else {
+        return Err(JournalError::Truncated { offset });
+    };
+    Ok((payload, len_end + len))
+}
+
+pub(crate) fn digest_to_words(digest: [u8; 32]) -> [u32; 8] {
+    let mut words = [0u32; 8];
+    for (word, chunk) in words.iter_mut().zip(digest.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().expect("chunk has 4 bytes"));
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hasher_matches_digest_of_encoded_entries() {
+        let mut hasher = JournalHasher::new();
+        hasher.update_entry(b"header");
+        hasher.update_entry(&[1, 2, 3]);
+
+        let mut journal = encode_entry(b"header");
+        journal.extend(encode_entry(&[1, 2, 3]));
+
+        assert_eq!(hasher.digest_words(), journal_digest_words(&journal));
+    }
+
+    #[test]
+    fn split_entries_roundtrip() {
+        let mut journal = encode_entry(b"alpha");
+        journal.extend(encode_entry(b""));
+        journal.extend(encode_entry(b"gamma"));
+
+        let entries = split_entries(&journal).expect("split entries");
+        assert_eq!(entries, [&b"alpha"[..], &b""[..], &b"gamma"[..]]);
+    }
+
+    #[test]
+    fn split_entries_rejects_truncated_payload() {
+        let mut journal = encode_entry(b"alpha");
+        journal.pop();
+
+        let err = split_entries(&journal).expect_err("truncated journal must fail");
+        assert_eq!(err, JournalError::Truncated { offset: 0 });
+    }
+}
@@ -3,13 +3,32 @@
 
 extern crate alloc;
 
+pub mod binding;
+pub mod cycle;
+pub mod exit;
 pub mod guest;
+pub mod journal;
+pub mod log;
+pub mod memory;
+pub mod oracle;
+pub mod panic_site;
+pub mod preamble;
+pub mod rng;
 pub mod wire;
 
+#[cfg(feature = "host")]
+pub mod id_table;
 #[cfg(feature = "host")]
 pub mod manifest;
+#[cfg(feature = "host")]
+pub mod panics;
+#[cfg(feature = "host")]
+pub mod regions;
 
 #[cfg(feature = "host")]
 pub mod host {
+    pub use crate::id_table;
     pub use crate::manifest;
+    pub use crate::panics;
+    pub use crate::regions;
 }
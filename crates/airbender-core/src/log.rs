@@ -0,0 +1,47 @@
+//! Guest log records shared between host and guest crates.
+//!
+//! A record is the [`GUEST_LOG_MARKER`] word, a [`Level`] word, and one UART frame holding
+//! the formatted message.
+
+/// Transport word announcing a log record.
+pub const GUEST_LOG_MARKER: u32 = 0x4c4f_4721; // "LOG!"
+
+/// Severity of a guest log record.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+}
+
+impl Level {
+    pub const fn to_word(self) -> u32 {
+        self as u32
+    }
+
+    pub const fn from_word(word: u32) -> Option<Self> {
+        match word {
+            1 => Some(Self::Error),
+            2 => Some(Self::Warn),
+            3 => Some(Self::Info),
+            4 => Some(Self::Debug),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Level;
+
+    #[test]
+    fn level_words_roundtrip() {
+        for level in [Level::Error, Level::Warn, Level::Info, Level::Debug] {
+            assert_eq!(Level::from_word(level.to_word()), Some(level));
+        }
+        assert_eq!(Level::from_word(0), None);
+        assert_eq!(Level::from_word(5), None);
+    }
+}
@@ -46,6 +46,15 @@ pub struct Manifest {
     pub text: ArtifactEntry,
     /// Build provenance metadata captured at packaging time.
     pub build: BuildMetadata,
+    /// Guest memory layout from `[package.metadata.airbender]`, when one was configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryLayout>,
+    /// Panic location table, written for guests built with compact panics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panics: Option<ArtifactEntry>,
+    /// Region name table, written for guests with named cycle-marker regions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regions: Option<ArtifactEntry>,
 }
 
 /// One artifact entry recorded in the manifest.
@@ -74,6 +83,50 @@ pub struct BuildMetadata {
     pub reproducible: bool,
 }
 
+/// Bytes of RAM the Airbender machine addresses, the ROM region included.
+pub const GUEST_RAM_BYTES: u64 = 1 << 30;
+
+/// Guest stack and heap sizes the runtime was linked with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryLayout {
+    /// Bytes reserved for the stack; unset leaves the stack unconstrained.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<u32>,
+    /// Heap size cap in bytes; unset lets the heap use everything below the stack.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heap: Option<u32>,
+}
+
+impl MemoryLayout {
+    /// RAM the layout needs on top of the program image.
+    pub fn required_bytes(&self) -> u64 {
+        u64::from(self.stack.unwrap_or(0)) + u64::from(self.heap.unwrap_or(0))
+    }
+
+    /// Parse a size such as `"4096"`, `"256K"`, `"16M"` or `"1MiB"` into bytes.
+    pub fn parse_size(value: &str) -> Result<u32, String> {
+        let value = value.trim();
+        let digits = value
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(value.len());
+        let (number, unit) = value.split_at(digits);
+        let multiplier: u64 = match unit.trim() {
+            "" | "B" => 1,
+            "K" | "KB" | "KiB" => 1 << 10,
+            "M" | "MB" | "MiB" => 1 << 20,
+            "G" | "GB" | "GiB" => 1 << 30,
+            other => return Err(format!("unknown size unit `{other}` in `{value}`")),
+        };
+        let number: u64 = number
+            .parse()
+            .map_err(|_| format!("invalid size `{value}`"))?;
+        number
+            .checked_mul(multiplier)
+            .and_then(|bytes| u32::try_from(bytes).ok())
+            .ok_or_else(|| format!("size `{value}` does not fit the 32-bit address space"))
+    }
+}
+
 /// Errors returned by manifest read, write, and parse operations.
 #[derive(Debug, thiserror::Error)]
 pub enum ManifestError {
@@ -154,6 +207,9 @@ mod tests {
                 is_dirty: false,
                 reproducible: false,
             },
+            memory: None,
+            panics: None,
+            regions: None,
         };
         let toml = manifest.to_toml().expect("serialize");
         let first_line = toml
@@ -198,6 +254,9 @@ mod tests {
                 is_dirty: false,
                 reproducible: false,
             },
+            memory: None,
+            panics: None,
+            regions: None,
         };
         let toml = manifest.to_toml().expect("serialize");
         assert!(!toml.contains("reproducible"));
@@ -230,6 +289,9 @@ mod tests {
                 is_dirty: false,
                 reproducible: true,
             },
+            memory: None,
+            panics: None,
+            regions: None,
         };
         let toml = manifest.to_toml().expect("serialize");
         assert!(toml.contains("reproducible = true"));
@@ -267,6 +329,9 @@ mod tests {
                 is_dirty: true,
                 reproducible: false,
             },
+            memory: None,
+            panics: None,
+            regions: None,
         };
 
         let toml = manifest.to_toml().expect("serialize");
@@ -303,6 +368,9 @@ mod tests {
                 is_dirty: false,
                 reproducible: false,
             },
+            memory: None,
+            panics: None,
+            regions: None,
         };
 
         let toml = manifest.to_toml().expect("serialize");
@@ -341,10 +409,46 @@ mod tests {
                 is_dirty: false,
                 reproducible: false,
             },
+            memory: None,
+            panics: None,
+            regions: None,
         };
         manifest.manifest = "v2".to_string();
         let toml = manifest.to_toml().expect("serialize");
         let err = Manifest::parse(&toml).expect_err("error");
         assert!(matches!(err, ManifestError::UnsupportedManifestVersion(_)));
     }
+
+    #[test]
+    fn memory_layout_roundtrips_and_is_omitted_when_absent() {
+        let mut manifest = Manifest::parse(&format!(
+            "package = \"demo\"\nmanifest = \"{MANIFEST_VERSION_V1}\"\ncodec = \"v0\"\n\
+             target = \"riscv32im-risc0-zkvm-elf\"\n\
+             [bin]\npath = \"app.bin\"\nsha256 = \"a\"\n\
+             [elf]\npath = \"app.elf\"\nsha256 = \"b\"\n\
+             [text]\npath = \"app.text\"\nsha256 = \"c\"\n\
+             [build]\nprofile = \"release\"\ngit_branch = \"main\"\ngit_commit = \"abc\"\n"
+        ))
+        .expect("parse");
+        assert_eq!(manifest.memory, None);
+        assert!(!manifest.to_toml().expect("serialize").contains("[memory]"));
+
+        manifest.memory = Some(MemoryLayout {
+            stack: Some(256 << 10),
+            heap: None,
+        });
+        let toml = manifest.to_toml().expect("serialize");
+        assert!(toml.contains("[memory]\nstack = 262144\n"));
+        assert_eq!(Manifest::parse(&toml).expect("parse"), manifest);
+    }
+
+    #[test]
+    fn parses_memory_sizes_with_units() {
+        assert_eq!(MemoryLayout::parse_size("4096"), Ok(4096));
+        assert_eq!(MemoryLayout::parse_size("256K"), Ok(256 << 10));
+        assert_eq!(MemoryLayout::parse_size("16MiB"), Ok(16 << 20));
+        assert!(MemoryLayout::parse_size("12X").is_err());
+        assert!(MemoryLayout::parse_size("8G").is_err());
+        assert!(MemoryLayout::parse_size("K").is_err());
+    }
 }
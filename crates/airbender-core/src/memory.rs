@@ -0,0 +1,63 @@
+//! Guest memory usage record shared between host and guest crates.
+//!
+//! With the runtime `memory-stats` feature, the guest writes [`MEMORY_STATS_MARKER`] followed
+//! by [`MemoryStats::WORDS`] words right before it exits.
+
+/// Transport word announcing a memory usage record.
+pub const MEMORY_STATS_MARKER: u32 = 0x4d45_4d53; // "MEMS"
+
+/// Heap and stack usage of one guest run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Heap bytes held by the allocator at exit.
+    pub heap_bytes: u32,
+    /// Highest value of `heap_bytes` during the run.
+    pub heap_peak_bytes: u32,
+    pub allocations: u32,
+    pub deallocations: u32,
+    /// Deepest stack usage observed through the painted-stack watermark.
+    pub stack_peak_bytes: u32,
+}
+
+impl MemoryStats {
+    /// Number of words following [`MEMORY_STATS_MARKER`].
+    pub const WORDS: usize = 5;
+
+    pub fn to_words(&self) -> [u32; Self::WORDS] {
+        [
+            self.heap_bytes,
+            self.heap_peak_bytes,
+            self.allocations,
+            self.deallocations,
+            self.stack_peak_bytes,
+        ]
+    }
+
+    pub fn from_words(words: [u32; Self::WORDS]) -> Self {
+        let [heap_bytes, heap_peak_bytes, allocations, deallocations, stack_peak_bytes] = words;
+        Self {
+            heap_bytes,
+            heap_peak_bytes,
+            allocations,
+            deallocations,
+            stack_peak_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryStats;
+
+    #[test]
+    fn words_roundtrip() {
+        let stats = MemoryStats {
+            heap_bytes: 64,
+            heap_peak_bytes: 4096,
+            allocations: 12,
+            deallocations: 11,
+            stack_peak_bytes: 1024,
+        };
+        assert_eq!(MemoryStats::from_words(stats.to_words()), stats);
+    }
+}
@@ -0,0 +1,8 @@
+//! Oracle query framing shared between host and guest crates.
+//!
+//! A query is the [`ORACLE_QUERY_MARKER`] word, a caller-chosen tag word, and a wire-framed
+//! request payload. The host answers with one wire-framed response, which the guest reads
+//! back before any remaining input words.
+
+/// Transport word announcing an oracle query.
+pub const ORACLE_QUERY_MARKER: u32 = 0x4f52_434c; // "ORCL"
@@ -0,0 +1,157 @@
+//! Panic site records for compact-panic guests.
+//!
+//! The compact panic handler reports a panic as the [`site_id`] of its location.
+//! `compact_panic!` places one record per call site in the [`PANIC_SITE_SECTION`] section of
+//! `app.elf`. The build links that section as non-allocated, so records and their messages
+//! never reach the loaded image; build tooling reads them from the ELF file into the dist
+//! `panics.toml` table. At run time the guest only reports the site ID.
+//!
+//! A record is six little-endian words (magic, site ID, line, column, file name length, and
+//! message length), followed by the file name and message bytes, zero-padded to a word
+//! boundary.
+
+/// ELF section holding the panic site records.
+pub const PANIC_SITE_SECTION: &str = ".airbender_panics";
+
+/// First word of every panic site record.
+pub const PANIC_SITE_MAGIC: u32 = 0x5053_4954; // "PSIT"
+
+const HEADER_WORDS: usize = 6;
+
+/// Site ID reported to the host: a 32-bit FNV-1a hash of the source position.
+pub const fn site_id(file: &str, line: u32, column: u32) -> u32 {
+    let mut hash = 0x811c_9dc5u32;
+    let mut index = 0;
+    let file = file.as_bytes();
+    while index < file.len() {
+        hash = (hash ^ file[index] as u32).wrapping_mul(0x0100_0193);
+        index += 1;
+    }
+    let position = [line.to_le_bytes(), column.to_le_bytes()];
+    let mut word = 0;
+    while word < position.len() {
+        let mut byte = 0;
+        while byte < 4 {
+            hash = (hash ^ position[word][byte] as u32).wrapping_mul(0x0100_0193);
+            byte += 1;
+        }
+        word += 1;
+    }
+    hash
+}
+
+/// Size in bytes of the record for a site in `file` with `message`.
+pub const fn record_len(file: &str, message: &str) -> usize {
+    (HEADER_WORDS * 4 + file.len() + message.len()).next_multiple_of(4)
+}
+
+/// Encodes a record; `N` must be [`record_len`] of `file` and `message`.
+pub const fn encode_record<const N: usize>(
+    file: &str,
+    line: u32,
+    column: u32,
+    message: &str,
+) -> [u8; N] {
+    assert!(N == record_len(file, message));
+    let header = [
+        PANIC_SITE_MAGIC,
+        site_id(file, line, column),
+        line,
+        column,
+        file.len() as u32,
+        message.len() as u32,
+    ];
+    let mut record = [0u8; N];
+    let mut offset = 0;
+    let mut word = 0;
+    while word < HEADER_WORDS {
+        let bytes = header[word].to_le_bytes();
+        let mut byte = 0;
+        while byte < 4 {
+            record[offset] = bytes[byte];
+            offset += 1;
+            byte += 1;
+        }
+        word += 1;
+    }
+    let text = [file.as_bytes(), message.as_bytes()];
+    let mut part = 0;
+    while part < text.len() {
+        let mut byte = 0;
+        while byte < text[part].len() {
+            record[offset] = text[part][byte];
+            offset += 1;
+            byte += 1;
+        }
+        part += 1;
+    }
+    record
+}
+
+/// A record placed in the guest image by `compact_panic!`, aligned like its header words.
+#[repr(C, align(4))]
+pub struct PanicSiteRecord<const N: usize>(pub [u8; N]);
+
+/// One decoded panic site record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PanicSite<'a> {
+    pub id: u32,
+    pub file: &'a str,
+    pub line: u32,
+    pub column: u32,
+    pub message: &'a str,
+}
+
+/// Decodes the record at the start of `bytes`, returning it with its length in bytes.
+pub fn decode_record(bytes: &[u8]) -> Option<(PanicSite<'_>, usize)> {
+    let word = |index: usize| {
+        let bytes = bytes.get(index * 4..index * 4 + 4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+    if word(0)? != PANIC_SITE_MAGIC {
+        return None;
+    }
+    let (id, line, column) = (word(1)?, word(2)?, word(3)?);
+    let (file_len, message_len) = (word(4)? as usize, word(5)? as usize);
+    let file_start = HEADER_WORDS * 4;
+    let message_start = file_start.checked_add(file_len)?;
+    let end = message_start.checked_add(message_len)?;
+    let file = core::str::from_utf8(bytes.get(file_start..message_start)?).ok()?;
+    let message = core::str::from_utf8(bytes.get(message_start..end)?).ok()?;
+    let site = PanicSite {
+        id,
+        file,
+        line,
+        column,
+        message,
+    };
+    Some((site, end.next_multiple_of(4)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_roundtrip() {
+        const LEN: usize = record_len("src/main.rs", "input must be even");
+        let record: [u8; LEN] = encode_record("src/main.rs", 12, 5, "input must be even");
+        assert_eq!(LEN % 4, 0);
+
+        let (site, len) = decode_record(&record).expect("record");
+        assert_eq!(len, LEN);
+        assert_eq!(
+            site,
+            PanicSite {
+                id: site_id("src/main.rs", 12, 5),
+                file: "src/main.rs",
+                line: 12,
+                column: 5,
+                message: "input must be even",
+            }
+        );
+        assert_ne!(site.id, site_id("src/main.rs", 12, 6));
+        assert_eq!(decode_record(&record[..LEN - 4]), None);
+        assert_eq!(decode_record(&[0; 24]), None);
+    }
+}
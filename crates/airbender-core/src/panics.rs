@@ -0,0 +1,76 @@
+//! Panic site table for guests built with compact panics.
+//!
+//! Compact-panic guests report a panic as a site ID instead of a formatted message. Build
+//! tooling collects the panic locations and `compact_panic!` site records from `app.elf` (see
+//! [`crate::panic_site`]) into this table, and host tooling uses it to turn the reported ID
+//! back into the source location and, for `compact_panic!`, the message.
+
+use crate::id_table::{IdEntry, IdTable};
+use serde::{Deserialize, Serialize};
+
+/// File name of the table inside a dist app directory.
+pub const PANIC_TABLE_FILE: &str = "panics.toml";
+
+/// Panic locations of one guest image, sorted by ID.
+pub type PanicTable = IdTable<PanicLocation>;
+
+/// Source position and message of one panic site.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanicLocation {
+    /// Site ID, as reported by the guest.
+    pub id: u32,
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
+
+impl std::fmt::Display for PanicLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl IdEntry for PanicLocation {
+    const KEY: &'static str = "location";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(id: u32, line: u32) -> PanicLocation {
+        PanicLocation {
+            id,
+            file: "src/main.rs".to_string(),
+            line,
+            column: 5,
+            message: "boom".to_string(),
+        }
+    }
+
+    #[test]
+    fn resolves_ids_after_toml_roundtrip() {
+        let table = PanicTable::new(vec![location(0x2040, 12), location(0x1000, 3)]);
+        let toml = toml::to_string(&table).expect("serialize");
+        assert!(toml.contains("[[location]]"));
+        assert!(toml.contains("message = \"boom\""));
+
+        let parsed: PanicTable = toml::from_str(&toml).expect("parse");
+        assert_eq!(parsed, table);
+        assert_eq!(
+            parsed.resolve(0x2040).map(ToString::to_string).as_deref(),
+            Some("src/main.rs:12:5: boom")
+        );
+        assert_eq!(parsed.resolve(0x2044), None);
+    }
+}
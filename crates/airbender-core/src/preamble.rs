@@ -0,0 +1,98 @@
+//! Program environment preamble for `std` guests.
+//!
+//! The first time a `std` guest asks for its arguments, environment variables or stdin, the
+//! runtime writes [`PREAMBLE_REQUEST_MARKER`] and the host answers with the preamble words,
+//! served before any remaining input words. The preamble is the argument count followed by
+//! one wire frame per argument, the variable count followed by a key frame and a value frame
+//! per variable, and a single stdin frame.
+
+use crate::wire::{frame_words_from_bytes, read_framed_bytes_with, WireError};
+use alloc::vec::Vec;
+
+/// Transport word asking the host for the preamble.
+pub const PREAMBLE_REQUEST_MARKER: u32 = 0x454e_5652; // "ENVR"
+
+/// Arguments, environment variables and stdin handed to a guest.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Preamble {
+    pub args: Vec<Vec<u8>>,
+    pub vars: Vec<(Vec<u8>, Vec<u8>)>,
+    pub stdin: Vec<u8>,
+}
+
+impl Preamble {
+    /// Encode the preamble as transport words.
+    pub fn to_words(&self) -> Result<Vec<u32>, WireError> {
+        let mut words = Vec::new();
+        words.push(count_word(self.args.len())?);
+        for arg in &self.args {
+            words.extend(frame_words_from_bytes(arg)?);
+        }
+        words.push(count_word(self.vars.len())?);
+        for (key, value) in &self.vars {
+            words.extend(frame_words_from_bytes(key)?);
+            words.extend(frame_words_from_bytes(value)?);
+        }
+        words.extend(frame_words_from_bytes(&self.stdin)?);
+        Ok(words)
+    }
+
+    /// Decode a preamble from a word source.
+    pub fn read_with(mut read_word: impl FnMut() -> u32) -> Self {
+        let args = (0..read_word())
+            .map(|_| read_framed_bytes_with(&mut read_word))
+            .collect();
+        let vars = (0..read_word())
+            .map(|_| {
+                let key = read_framed_bytes_with(&mut read_word);
+                (key, read_framed_bytes_with(&mut read_word))
+            })
+            .collect();
+        let stdin = read_framed_bytes_with(&mut read_word);
+        Self { args, vars, stdin }
+    }
+
+    /// Value of the variable `name`; later entries take precedence.
+    pub fn var(&self, name: &[u8]) -> Option<&[u8]> {
+        self.vars
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_slice())
+    }
+}
+
+fn count_word(len: usize) -> Result<u32, WireError> {
+    u32::try_from(len).map_err(|_| WireError::PayloadTooLarge { len })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Preamble;
+    use alloc::vec;
+
+    #[test]
+    fn preamble_roundtrip() {
+        let preamble = Preamble {
+            args: vec![b"tool".to_vec(), b"--verbose".to_vec()],
+            vars: vec![
+                (b"MODE".to_vec(), b"fast".to_vec()),
+                (b"MODE".to_vec(), b"slow".to_vec()),
+            ],
+            stdin: b"line one\nline two\n".to_vec(),
+        };
+        let words = preamble.to_words().expect("encode preamble");
+        let mut cursor = words.iter().copied();
+        let decoded = Preamble::read_with(|| cursor.next().expect("preamble word"));
+
+        assert_eq!(decoded, preamble);
+        assert_eq!(cursor.next(), None);
+        assert_eq!(decoded.var(b"MODE"), Some(&b"slow"[..]));
+        assert_eq!(decoded.var(b"HOME"), None);
+    }
+
+    #[test]
+    fn empty_preamble_is_three_words() {
+        assert_eq!(Preamble::default().to_words().expect("encode"), [0, 0, 0]);
+    }
+}
@@ -0,0 +1,46 @@
+//! Region name table for guests with named cycle-marker regions.
+//!
+//! Guests report regions by label ID, the address of a [`crate::cycle::RegionLabel`] record.
+//! Build tooling collects the records from `app.elf` into this table, and host tooling uses it
+//! to name the regions of a run.
+
+use crate::id_table::{IdEntry, IdTable};
+use serde::{Deserialize, Serialize};
+
+/// File name of the table inside a dist app directory.
+pub const REGION_TABLE_FILE: &str = "regions.toml";
+
+/// Region names of one guest image, sorted by ID.
+pub type RegionTable = IdTable<RegionName>;
+
+/// Name of one region label.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionName {
+    /// Guest address of the label record, as reported by the guest.
+    pub id: u32,
+    pub name: String,
+}
+
+impl IdEntry for RegionName {
+    const KEY: &'static str = "region";
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_regions_as_region_tables() {
+        let table = RegionTable::new(vec![RegionName {
+            id: 0x1000,
+            name: "hash".to_string(),
+        }]);
+        let toml = toml::to_string(&table).expect("serialize");
+        assert!(toml.contains("[[region]]"));
+        assert_eq!(toml::from_str::<RegionTable>(&toml).expect("parse"), table);
+    }
+}
@@ -0,0 +1,110 @@
+//! Seeded deterministic randomness shared between host and guest crates.
+//!
+//! The host places a seed section at the start of the input stream: the [`RNG_SEED_MARKER`]
+//! word followed by [`RNG_SEED_WORDS`] seed words. The guest runtime expands the seed with
+//! [`SeededRng`], a Keccak-256 counter-mode generator, so every run with the same seed
+//! observes the same byte stream.
+
+use sha3::Digest;
+
+/// Transport word announcing the seed section.
+pub const RNG_SEED_MARKER: u32 = 0x5345_4544; // "SEED"
+
+/// Number of seed words following [`RNG_SEED_MARKER`].
+pub const RNG_SEED_WORDS: usize = 8;
+
+/// Seed words as laid out in the input stream.
+pub fn seed_to_words(seed: &[u8; 32]) -> [u32; RNG_SEED_WORDS] {
+    core::array::from_fn(|i| {
+        u32::from_le_bytes(seed[i * 4..i * 4 + 4].try_into().expect("4-byte chunk"))
+    })
+}
+
+/// Inverse of [`seed_to_words`].
+pub fn seed_from_words(words: &[u32; RNG_SEED_WORDS]) -> [u8; 32] {
+    let mut seed = [0u8; 32];
+    for (chunk, word) in seed.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    seed
+}
+
+/// Deterministic byte generator expanding a 32-byte seed.
+///
+/// Block `i` is Keccak-256 over the seed followed by the little-endian `u64` counter `i`.
+#[derive(Clone)]
+pub struct SeededRng {
+    seed: [u8; 32],
+    counter: u64,
+    block: [u8; 32],
+    offset: usize,
+}
+
+impl SeededRng {
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self {
+            seed,
+            counter: 0,
+            block: [0u8; 32],
+            offset: 32,
+        }
+    }
+
+    /// Fill `dest` with the next bytes of the stream.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            if self.offset == self.block.len() {
+                self.refill();
+            }
+            *byte = self.block[self.offset];
+            self.offset += 1;
+        }
+    }
+
+    /// Next little-endian word of the stream.
+    pub fn next_word(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn refill(&mut self) {
+        let mut hasher = sha3::Keccak256::new();
+        hasher.update(self.seed);
+        hasher.update(self.counter.to_le_bytes());
+        self.block = hasher.finalize().into();
+        self.counter += 1;
+        self.offset = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_words_roundtrip() {
+        let seed: [u8; 32] = core::array::from_fn(|i| i as u8);
+        let words = seed_to_words(&seed);
+        assert_eq!(words[0], 0x0302_0100);
+        assert_eq!(seed_from_words(&words), seed);
+    }
+
+    #[test]
+    fn stream_is_independent_of_chunking() {
+        let mut whole = [0u8; 80];
+        SeededRng::new([7u8; 32]).fill_bytes(&mut whole);
+
+        let mut rng = SeededRng::new([7u8; 32]);
+        let mut pieces = [0u8; 80];
+        for chunk in pieces.chunks_mut(3) {
+            rng.fill_bytes(chunk);
+        }
+        assert_eq!(whole, pieces);
+        assert_ne!(whole[..32], whole[32..64]);
+
+        let mut other = [0u8; 80];
+        SeededRng::new([8u8; 32]).fill_bytes(&mut other);
+        assert_ne!(whole, other);
+    }
+}
@@ -0,0 +1,73 @@
+//! Input binding for proofs.
+//!
+//! Every word read through [`crate::CsrTransport`] is folded into a running input digest.
+//! Committing with [`commit_with_inputs`] binds the user output to that digest, so a verifier
+//! holding the expected input words can check which inputs the proof consumed. A seed section
+//! read by the runtime's `rng-seed` feature is folded in first, so the seed is bound too.
+//! `std` guests fold their arguments, environment variables and stdin in last, after all
+//! input words, so the program environment is bound as well.
+
+use crate::commit::Commit;
+use airbender_core::binding::bind_output;
+
+#[cfg(target_arch = "riscv32")]
+mod global {
+    use airbender_core::binding::InputHasher;
+    use airbender_rt::cell::GuestCell;
+
+    static INPUT_HASHER: GuestCell<Option<InputHasher>> = GuestCell::new(None);
+
+    pub(super) fn with<R>(f: impl FnOnce(&mut InputHasher) -> R) -> R {
+        INPUT_HASHER.with(|state| f(state.get_or_insert_with(new_hasher)))
+    }
+
+    /// Start the digest with the seed section the runtime read at boot, if any.
+    fn new_hasher() -> InputHasher {
+        let mut hasher = InputHasher::new();
+        if let Some(words) = airbender_rt::getrandom::seed_words() {
+            hasher.update_word(airbender_core::rng::RNG_SEED_MARKER);
+            for word in words {
+                hasher.update_word(word);
+            }
+        }
+        hasher
+    }
+}
+
+/// Fold one input word into the global input digest.
+#[cfg(target_arch = "riscv32")]
+pub(crate) fn record_input_word(word: u32) {
+    global::with(|hasher| hasher.update_word(word));
+}
+
+/// Digest of all input words read so far, followed by the preamble in `std` guests.
+pub fn input_digest() -> [u32; 8] {
+    #[cfg(all(feature = "std", target_arch = "riscv32"))]
+    {
+        // Requesting the preamble reads transport words, so fetch it before taking the hasher.
+        let preamble_words = airbender_rt::preamble_words();
+        global::with(|hasher| {
+            let mut hasher = hasher.clone();
+            hasher.update_preamble(&preamble_words);
+            hasher.digest_words()
+        })
+    }
+    #[cfg(all(not(feature = "std"), target_arch = "riscv32"))]
+    {
+        global::with(|hasher| hasher.digest_words())
+    }
+    #[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+    {
+        crate::native::input_digest()
+    }
+    #[cfg(all(not(feature = "native"), not(target_arch = "riscv32")))]
+    {
+        airbender_core::binding::InputHasher::new().digest_words()
+    }
+}
+
+/// Commit `value` bound to the input digest and exit successfully.
+pub fn commit_with_inputs<T: Commit>(value: T) -> ! {
+    let words = bind_output(&value.commit_words(), &input_digest());
+    crate::commit::exit_success(words)
+}
@@ -1,14 +1,50 @@
 //! Commit helpers for mapping values into output registers.
 
-pub use airbender_core::guest::Commit;
+pub use airbender_core::guest::{Commit, CommitField, FromCommit};
+
+use core::fmt;
 
 /// Commit values to the default output registers and exit successfully.
 pub fn commit<T: Commit>(value: T) -> ! {
-    let words = value.commit_words();
-    airbender_rt::sys::exit_success(&words)
+    exit_success(value.commit_words())
+}
+
+pub(crate) fn exit_success(words: [u32; 8]) -> ! {
+    #[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+    {
+        crate::native::exit_success(words)
+    }
+    #[cfg(not(all(feature = "native", not(target_arch = "riscv32"))))]
+    {
+        airbender_rt::sys::exit_success(&words)
+    }
 }
 
 /// Exit with an error.
 pub fn exit_error() -> ! {
-    airbender_rt::sys::exit_error()
+    #[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+    {
+        crate::native::exit_error(None)
+    }
+    #[cfg(not(all(feature = "native", not(target_arch = "riscv32"))))]
+    {
+        airbender_rt::sys::exit_error()
+    }
+}
+
+/// Report `err` to the host through the UART and exit with an error.
+pub fn exit_with_error<E: fmt::Display>(err: &E) -> ! {
+    // Format up front so the message reaches the host as a single UART frame.
+    let message = alloc::format!("{err}");
+    #[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+    {
+        crate::native::exit_error(Some(message))
+    }
+    #[cfg(not(all(feature = "native", not(target_arch = "riscv32"))))]
+    {
+        use core::fmt::Write as _;
+        airbender_rt::sys::write_word(airbender_core::exit::GUEST_ERROR_MARKER);
+        let _ = airbender_rt::uart::QuasiUart::new().write_str(&message);
+        airbender_rt::sys::exit_error()
+    }
 }
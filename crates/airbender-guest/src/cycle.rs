@@ -1,5 +1,10 @@
 //! Development-only helpers for placing cycle markers in guest code.
 
+use airbender_core::cycle::{RegionEvent, RegionKind, CYCLE_REGION_MARKER};
+use airbender_rt::sys::cycle_markers_emitted;
+
+pub use airbender_core::cycle::RegionLabel;
+
 /// Emit a cycle marker boundary recognized by the transpiler runner.
 ///
 /// Cycle markers are intended for local transpiler profiling. They should not
@@ -17,3 +22,72 @@ pub fn record_cycles<T>(f: impl FnOnce() -> T) -> T {
     marker();
     result
 }
+
+/// Record a region named by `label` around `f`; use [`record_cycles!`](crate::record_cycles!).
+///
+/// Region events are written outside the two markers, so they do not count towards the region.
+/// They refer to markers by their index in the run, which counts every marker the runtime emits.
+#[inline(always)]
+pub fn record_region<T>(label: &'static RegionLabel, f: impl FnOnce() -> T) -> T {
+    region_event(label, RegionKind::Enter, cycle_markers_emitted());
+    marker();
+    let result = f();
+    marker();
+    region_event(label, RegionKind::Exit, cycle_markers_emitted() - 1);
+    result
+}
+
+fn region_event(label: &'static RegionLabel, kind: RegionKind, marker: u32) {
+    // Like cycle markers, region events are only meaningful on real guest targets.
+    if cfg!(not(target_arch = "riscv32")) {
+        return;
+    }
+    let event = RegionEvent {
+        label: label.id(),
+        kind,
+        marker,
+    };
+    airbender_rt::sys::write_word(CYCLE_REGION_MARKER);
+    for word in event.to_words() {
+        airbender_rt::sys::write_word(word);
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __airbender_record_cycles {
+    ($name:literal, $f:expr $(,)?) => {{
+        #[used]
+        #[cfg_attr(target_arch = "riscv32", link_section = ".airbender_regions")]
+        static LABEL: $crate::cycle::RegionLabel = $crate::cycle::RegionLabel::new($name);
+        $crate::cycle::record_region(&LABEL, $f)
+    }};
+}
+
+/// Record the cycles of a named guest code region.
+///
+/// Regions nest, and the host reports them as a tree with inclusive and exclusive cycles and
+/// delegation counts. Names are resolved through the dist `regions.toml` table.
+///
+/// ```ignore
+/// let digest = airbender::guest::record_cycles!("hash", || Keccak256::digest(data));
+/// ```
+#[doc(inline)]
+pub use crate::__airbender_record_cycles as record_cycles;
+
+#[cfg(test)]
+mod tests {
+    use super::{cycle_markers_emitted, record_cycles};
+
+    #[test]
+    fn named_regions_nest_and_return_values() {
+        let before = cycle_markers_emitted();
+        let sum = record_cycles!("outer", || {
+            let inner = record_cycles!("inner", || 2);
+            airbender_rt::sys::emit_cycle_marker();
+            inner + record_cycles(|| 3)
+        });
+        assert_eq!(sum, 5);
+        assert_eq!(cycle_markers_emitted() - before, 7);
+    }
+}
@@ -2,14 +2,17 @@
 
 use crate::transport::Transport;
 use airbender_codec::{AirbenderCodec, AirbenderCodecV0, CodecError};
-use airbender_core::wire::read_framed_bytes_with;
+use airbender_core::wire::{read_framed_bytes_with, WireError};
 use core::fmt;
 
 /// Errors that can occur when decoding inputs on the guest.
 #[derive(Debug)]
 pub enum GuestError {
     Codec(CodecError),
+    Wire(WireError),
     UnsupportedTarget,
+    /// The journal was written inside an `allocator::scope` of the arena allocator.
+    InsideArenaScope,
 }
 
 impl From<CodecError> for GuestError {
@@ -18,25 +21,35 @@ impl From<CodecError> for GuestError {
     }
 }
 
+impl From<WireError> for GuestError {
+    fn from(err: WireError) -> Self {
+        GuestError::Wire(err)
+    }
+}
+
 impl fmt::Display for GuestError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             GuestError::Codec(err) => write!(f, "{err}"),
+            GuestError::Wire(err) => write!(f, "{err}"),
             GuestError::UnsupportedTarget => {
                 f.write_str("csr transport is only available on riscv32")
             }
+            GuestError::InsideArenaScope => {
+                f.write_str("the journal cannot be written inside an arena allocator scope")
+            }
         }
     }
 }
 
 /// Read a single value from the CSR-based transport.
 pub fn read<T: serde::de::DeserializeOwned>() -> Result<T, GuestError> {
-    #[cfg(target_arch = "riscv32")]
+    #[cfg(any(target_arch = "riscv32", feature = "native"))]
     {
         let mut transport = crate::transport::CsrTransport;
         read_with(&mut transport)
     }
-    #[cfg(not(target_arch = "riscv32"))]
+    #[cfg(not(any(target_arch = "riscv32", feature = "native")))]
     {
         Err(GuestError::UnsupportedTarget)
     }
@@ -0,0 +1,139 @@
+//! Public journal for committing arbitrary serde values.
+//!
+//! Each written value is encoded with the Airbender codec, streamed to the host, and folded
+//! into a running digest. Committing the journal publishes that digest in the output registers.
+
+use crate::input::GuestError;
+use crate::transport::Transport;
+use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+use airbender_core::journal::{JournalHasher, JOURNAL_MARKER};
+use airbender_core::wire::frame_words_from_bytes;
+
+/// Journal state tracking the digest of all values written so far.
+#[derive(Clone, Default)]
+pub struct Journal {
+    hasher: JournalHasher,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append one value to the journal using an explicit transport.
+    pub fn write_with<T: serde::Serialize>(
+        &mut self,
+        transport: &mut impl Transport,
+        value: &T,
+    ) -> Result<(), GuestError> {
+        let payload = AirbenderCodecV0::encode(value)?;
+        let words = frame_words_from_bytes(&payload)?;
+        self.hasher.update_entry(&payload);
+        transport.write_word(JOURNAL_MARKER);
+        for word in words {
+            transport.write_word(word);
+        }
+        Ok(())
+    }
+
+    /// Digest of all values written so far, laid out as output words.
+    pub fn digest(&self) -> [u32; 8] {
+        self.hasher.digest_words()
+    }
+}
+
+#[cfg(target_arch = "riscv32")]
+mod global {
+    use super::Journal;
+    use airbender_rt::cell::GuestCell;
+
+    static JOURNAL: GuestCell<Option<Journal>> = GuestCell::new(None);
+
+    pub(super) fn with<R>(f: impl FnOnce(&mut Journal) -> R) -> R {
+        JOURNAL.with(|state| f(state.get_or_insert_with(Journal::new)))
+    }
+}
+
+#[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+mod global {
+    pub(super) use crate::native::with_journal as with;
+}
+
+/// Append one value to the global journal.
+///
+/// With the arena allocator, writes inside `allocator::scope` fail with
+/// [`GuestError::InsideArenaScope`], since the journal outlives the scope.
+pub fn write<T: serde::Serialize>(value: &T) -> Result<(), GuestError> {
+    #[cfg(feature = "allocator-arena")]
+    if airbender_rt::allocator::in_scope() {
+        return Err(GuestError::InsideArenaScope);
+    }
+    #[cfg(any(target_arch = "riscv32", feature = "native"))]
+    {
+        global::with(|journal| journal.write_with(&mut crate::transport::CsrTransport, value))
+    }
+    #[cfg(not(any(target_arch = "riscv32", feature = "native")))]
+    {
+        let _ = value;
+        Err(GuestError::UnsupportedTarget)
+    }
+}
+
+/// Digest of the global journal, suitable for returning from `main`.
+pub fn digest() -> [u32; 8] {
+    #[cfg(any(target_arch = "riscv32", feature = "native"))]
+    {
+        global::with(|journal| journal.digest())
+    }
+    #[cfg(not(any(target_arch = "riscv32", feature = "native")))]
+    {
+        Journal::new().digest()
+    }
+}
+
+/// Commit the global journal digest and exit successfully.
+pub fn commit() -> ! {
+    crate::commit::commit(digest())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+    use airbender_core::journal::{encode_entry, journal_digest_words, split_entries};
+    use airbender_core::wire::read_framed_bytes_with;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn writes_marked_frames_and_tracks_digest() {
+        let mut transport = MockTransport::default();
+        let mut journal = Journal::new();
+        journal
+            .write_with(&mut transport, &7u64)
+            .expect("write u64");
+        journal
+            .write_with(&mut transport, &alloc::string::String::from("balances"))
+            .expect("write string");
+
+        let mut words = transport.into_writes().into_iter();
+        let mut bytes = Vec::new();
+        while let Some(marker) = words.next() {
+            assert_eq!(marker, JOURNAL_MARKER);
+            let payload = read_framed_bytes_with(|| words.next().expect("frame word"));
+            bytes.extend(encode_entry(&payload));
+        }
+
+        let entries = split_entries(&bytes).expect("split entries");
+        assert_eq!(entries.len(), 2);
+        let value: u64 = AirbenderCodecV0::decode(entries[0]).expect("decode u64");
+        assert_eq!(value, 7);
+        assert_eq!(journal.digest(), journal_digest_words(&bytes));
+    }
+
+    #[test]
+    #[cfg(feature = "allocator-arena")]
+    fn rejects_writes_inside_arena_scopes() {
+        let result = unsafe { airbender_rt::allocator::scope(|| write(&1u32)) };
+        assert!(matches!(result, Err(GuestError::InsideArenaScope)));
+    }
+}
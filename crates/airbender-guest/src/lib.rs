@@ -3,15 +3,43 @@
 
 extern crate alloc;
 
-#[cfg(test)]
+#[cfg(any(test, all(feature = "native", not(target_arch = "riscv32"))))]
 extern crate std;
 
+pub mod binding;
 pub mod commit;
 pub mod cycle;
 pub mod input;
+pub mod journal;
+pub mod log;
+#[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+pub mod native;
+pub mod oracle;
+pub mod panic;
 pub mod transport;
 
-pub use commit::{commit, exit_error, Commit};
+pub use binding::{commit_with_inputs, input_digest};
+pub use commit::{commit, exit_error, exit_with_error, Commit, CommitField, FromCommit};
 pub use cycle::{marker as cycle_marker, record_cycles};
 pub use input::{read, read_with, GuestError};
+pub use journal::Journal;
+pub use panic::compact_panic;
 pub use transport::{CsrTransport, MockTransport, Transport};
+
+/// Keeps the `airbender_native_entry` function generated by `#[airbender::main]` only in
+/// builds with the `native` feature.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+macro_rules! __airbender_native_entry {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(all(feature = "native", not(target_arch = "riscv32"))))]
+macro_rules! __airbender_native_entry {
+    ($($item:item)*) => {};
+}
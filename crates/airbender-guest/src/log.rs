@@ -0,0 +1,81 @@
+//! Leveled logging forwarded to the host `tracing` subscriber.
+//!
+//! Records are sent to the host through the UART protocol and emitted there with the `guest`
+//! target, so `RUST_LOG=guest=debug` style filters apply. `debug!` records are compiled out
+//! unless the `log-debug` feature is enabled.
+
+pub use airbender_core::log::Level;
+
+/// Whether `debug!` records are emitted.
+pub const DEBUG_ENABLED: bool = cfg!(feature = "log-debug");
+
+/// Format and send one log record to the host.
+pub fn write_record(level: Level, args: core::fmt::Arguments<'_>) {
+    #[cfg(target_arch = "riscv32")]
+    {
+        // Format up front so the message reaches the host as a single UART frame.
+        let message = alloc::fmt::format(args);
+        airbender_rt::uart::write_log_record(level, &message);
+    }
+    #[cfg(not(target_arch = "riscv32"))]
+    {
+        let _ = (level, args);
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __airbender_log_error {
+    ($($arg:tt)*) => {
+        $crate::log::write_record($crate::log::Level::Error, ::core::format_args!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __airbender_log_warn {
+    ($($arg:tt)*) => {
+        $crate::log::write_record($crate::log::Level::Warn, ::core::format_args!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __airbender_log_info {
+    ($($arg:tt)*) => {
+        $crate::log::write_record($crate::log::Level::Info, ::core::format_args!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __airbender_log_debug {
+    ($($arg:tt)*) => {
+        if $crate::log::DEBUG_ENABLED {
+            $crate::log::write_record($crate::log::Level::Debug, ::core::format_args!($($arg)*))
+        }
+    };
+}
+
+#[doc(inline)]
+pub use crate::__airbender_log_debug as debug;
+#[doc(inline)]
+pub use crate::__airbender_log_error as error;
+#[doc(inline)]
+pub use crate::__airbender_log_info as info;
+#[doc(inline)]
+pub use crate::__airbender_log_warn as warn;
+
+#[cfg(test)]
+mod tests {
+    use super::{debug, error, info, warn};
+
+    #[test]
+    fn macros_accept_format_arguments() {
+        let balance = 7u64;
+        error!("balance {balance} is too low");
+        warn!("retrying {} times", 3);
+        info!("plain message");
+        debug!("state = {:?}", [1u32, 2]);
+    }
+}
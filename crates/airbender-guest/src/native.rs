@@ -0,0 +1,332 @@
+//! Native backend for running guest code on the host while debugging.
+//!
+//! With the `native` feature on non-riscv32 targets, reads are served from a thread-local
+//! transport seeded by [`run`], and `commit` or error exits unwind back to [`run`] instead of
+//! halting the machine. Oracle queries are answered by the closure given to
+//! [`run_with_oracle`]. Output words match `Receipt::output` from host runners.
+
+use crate::journal::Journal;
+use crate::transport::Transport;
+use airbender_core::binding::InputHasher;
+use airbender_core::journal::{encode_entry, JOURNAL_MARKER};
+use airbender_core::wire::{frame_words_from_bytes, read_framed_bytes_with};
+use core::convert::Infallible;
+use core::fmt;
+use std::boxed::Box;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::format;
+use std::panic::{self, AssertUnwindSafe};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// Data produced by a guest that exited successfully.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NativeOutput {
+    /// Committed output words, as found in registers `x10..x17` after a real run.
+    pub output: [u32; 8],
+    /// Raw public journal bytes, in the layout host runners report.
+    pub journal: Vec<u8>,
+    pub input_words_consumed: usize,
+}
+
+/// How a native guest run failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NativeError {
+    /// The guest exited through the error path, with the reported message if any.
+    GuestError(Option<String>),
+    /// The guest panicked.
+    Panic(String),
+    /// An oracle query failed; the guest received an empty answer for it.
+    Oracle(String),
+}
+
+impl fmt::Display for NativeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NativeError::GuestError(Some(message)) => write!(f, "guest error: {message}"),
+            NativeError::GuestError(None) => f.write_str("guest exited with an error"),
+            NativeError::Panic(message) => write!(f, "guest panicked: {message}"),
+            NativeError::Oracle(message) => write!(f, "oracle error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for NativeError {}
+
+/// Answers oracle queries by tag and request bytes.
+pub type NativeOracle = Box<dyn FnMut(u32, &[u8]) -> Result<Vec<u8>, String>>;
+
+struct State {
+    reads: Vec<u32>,
+    cursor: usize,
+    writes: Vec<u32>,
+    input_hasher: InputHasher,
+    journal: Journal,
+    oracle: Option<NativeOracle>,
+    /// First oracle failure.
+    oracle_error: Option<String>,
+}
+
+impl State {
+    /// Answer a query written as `[ORACLE_QUERY_MARKER, tag, framed request]`.
+    fn answer_query(&mut self, query: &[u32]) -> Vec<u32> {
+        let mut words = query.iter().copied().skip(1);
+        let tag = words.next().unwrap_or(0);
+        let request = read_framed_bytes_with(|| words.next().unwrap_or(0));
+        let answer = match &mut self.oracle {
+            Some(oracle) => oracle(tag, &request),
+            None => Err(format!(
+                "guest sent a query with tag {tag}, but no oracle is registered"
+            )),
+        };
+        match answer.and_then(|bytes| frame_words_from_bytes(&bytes).map_err(|err| err.to_string()))
+        {
+            Ok(words) => words,
+            Err(err) => {
+                self.oracle_error.get_or_insert(err);
+                // An empty frame lets the guest fail on decoding, as in host runs.
+                std::vec![0]
+            }
+        }
+    }
+}
+
+/// Unwind payload carrying a guest exit back to [`run`].
+enum Exit {
+    Success([u32; 8]),
+    Error(Option<String>),
+}
+
+std::thread_local! {
+    static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+/// Run `entry` natively with `input_words` as the input stream.
+///
+/// `entry` is usually the `airbender_native_entry` function generated by
+/// `#[airbender::main]`.
+pub fn run(
+    input_words: &[u32],
+    entry: impl FnOnce() -> Infallible,
+) -> Result<NativeOutput, NativeError> {
+    run_inner(input_words, None, entry)
+}
+
+/// Run `entry` natively like [`run`], answering oracle queries with `oracle`.
+pub fn run_with_oracle(
+    input_words: &[u32],
+    oracle: impl FnMut(u32, &[u8]) -> Result<Vec<u8>, String> + 'static,
+    entry: impl FnOnce() -> Infallible,
+) -> Result<NativeOutput, NativeError> {
+    run_inner(input_words, Some(Box::new(oracle)), entry)
+}
+
+fn run_inner(
+    input_words: &[u32],
+    oracle: Option<NativeOracle>,
+    entry: impl FnOnce() -> Infallible,
+) -> Result<NativeOutput, NativeError> {
+    STATE.with(|state| {
+        *state.borrow_mut() = Some(State {
+            reads: input_words.to_vec(),
+            cursor: 0,
+            writes: Vec::new(),
+            input_hasher: InputHasher::new(),
+            journal: Journal::new(),
+            oracle,
+            oracle_error: None,
+        })
+    });
+    let payload = match panic::catch_unwind(AssertUnwindSafe(entry)) {
+        Ok(never) => match never {},
+        Err(payload) => payload,
+    };
+    let state = STATE
+        .with(|state| state.borrow_mut().take())
+        .expect("native state is set for the duration of the run");
+    if let Some(err) = state.oracle_error {
+        return Err(NativeError::Oracle(err));
+    }
+
+    match payload.downcast::<Exit>() {
+        Ok(exit) => match *exit {
+            Exit::Success(output) => Ok(NativeOutput {
+                output,
+                journal: journal_bytes(&state.writes),
+                input_words_consumed: state.cursor,
+            }),
+            Exit::Error(message) => Err(NativeError::GuestError(message)),
+        },
+        Err(payload) => Err(NativeError::Panic(panic_message(payload))),
+    }
+}
+
+fn with_state<R>(f: impl FnOnce(&mut State) -> R) -> R {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let state = state
+            .as_mut()
+            .expect("native guest backend is not running; call `native::run`");
+        f(state)
+    })
+}
+
+pub(crate) fn read_word() -> u32 {
+    let word = with_state(|state| {
+        let word = state.reads.get(state.cursor).copied()?;
+        state.cursor += 1;
+        state.input_hasher.update_word(word);
+        Some(word)
+    });
+    word.expect("guest read past the end of the input words")
+}
+
+pub(crate) fn write_word(word: u32) {
+    with_state(|state| state.writes.push(word));
+}
+
+pub(crate) fn input_digest() -> [u32; 8] {
+    with_state(|state| state.input_hasher.digest_words())
+}
+
+/// Run `f` on the thread-local journal.
+///
+/// The journal is taken out of the state while `f` runs, since writing it goes through
+/// [`write_word`].
+pub(crate) fn with_journal<R>(f: impl FnOnce(&mut Journal) -> R) -> R {
+    let mut journal = with_state(|state| core::mem::take(&mut state.journal));
+    let result = f(&mut journal);
+    with_state(|state| state.journal = journal);
+    result
+}
+
+/// Transport for oracle queries: writes collect the query, and reads serve its answer without
+/// touching the input digest.
+#[derive(Default)]
+pub(crate) struct OracleTransport {
+    query: Vec<u32>,
+    answer: VecDeque<u32>,
+}
+
+impl Transport for OracleTransport {
+    fn read_word(&mut self) -> u32 {
+        if !self.query.is_empty() {
+            let query = core::mem::take(&mut self.query);
+            self.answer = with_state(|state| state.answer_query(&query)).into();
+        }
+        self.answer
+            .pop_front()
+            .expect("guest read past the end of the oracle answer")
+    }
+
+    fn write_word(&mut self, word: u32) {
+        self.query.push(word);
+    }
+}
+
+pub(crate) fn exit_success(words: [u32; 8]) -> ! {
+    panic::resume_unwind(Box::new(Exit::Success(words)))
+}
+
+pub(crate) fn exit_error(message: Option<String>) -> ! {
+    panic::resume_unwind(Box::new(Exit::Error(message)))
+}
+
+/// Collect journal entries from the words the guest wrote.
+fn journal_bytes(writes: &[u32]) -> Vec<u8> {
+    let mut words = writes.iter().copied();
+    let mut journal = Vec::new();
+    while let Some(word) = words.next() {
+        if word == JOURNAL_MARKER {
+            let payload = read_framed_bytes_with(|| words.next().unwrap_or(0));
+            journal.extend(encode_entry(&payload));
+        }
+    }
+    journal
+}
+
+fn panic_message(payload: Box<dyn core::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(_) => "non-string panic payload".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, run_with_oracle, NativeError};
+    use crate::{commit, exit_with_error, journal, oracle, read};
+    use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+    use airbender_core::journal::split_entries;
+    use airbender_core::wire::frame_words_from_bytes;
+    use std::string::ToString;
+    use std::vec::Vec;
+
+    fn input_words(value: u32) -> Vec<u32> {
+        let payload = AirbenderCodecV0::encode(&value).expect("encode input");
+        frame_words_from_bytes(&payload).expect("frame input")
+    }
+
+    #[test]
+    fn commit_returns_output_words() {
+        let words = input_words(41);
+        let output = run(&words, || {
+            let n: u32 = read().expect("read input");
+            journal::write(&n).expect("journal write");
+            commit(n + 1)
+        })
+        .expect("native run");
+
+        assert_eq!(output.output, [42, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(output.input_words_consumed, words.len());
+        let entries = split_entries(&output.journal).expect("split journal");
+        let value: u32 = AirbenderCodecV0::decode(entries[0]).expect("decode entry");
+        assert_eq!(value, 41);
+    }
+
+    #[test]
+    fn reports_guest_errors_and_panics() {
+        let err = run(&[], || exit_with_error(&"balance too low")).expect_err("guest error");
+        assert_eq!(err, NativeError::GuestError(Some("balance too low".into())));
+
+        let err = run(&[], || panic!("boom")).expect_err("panic");
+        assert_eq!(err, NativeError::Panic("boom".into()));
+    }
+
+    #[test]
+    fn answers_oracle_queries() {
+        let words = input_words(20);
+        let output = run_with_oracle(
+            &words,
+            |tag, request| {
+                let n: u32 = AirbenderCodecV0::decode(request).map_err(|err| err.to_string())?;
+                AirbenderCodecV0::encode(&(n * tag)).map_err(|err| err.to_string())
+            },
+            || {
+                let n: u32 = read().expect("read input");
+                let answer: u32 = oracle::query(2, &n).expect("oracle answer");
+                commit(answer + 2)
+            },
+        )
+        .expect("native run");
+
+        assert_eq!(output.output[0], 42);
+        assert_eq!(output.input_words_consumed, words.len());
+
+        let err = run(&[], || {
+            let _ = oracle::query::<u32, u32>(2, &1);
+            commit(0u32)
+        })
+        .expect_err("no oracle");
+        assert_eq!(
+            err,
+            NativeError::Oracle(
+                "guest sent a query with tag 2, but no oracle is registered".into()
+            )
+        );
+    }
+}
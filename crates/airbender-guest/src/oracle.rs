@@ -0,0 +1,91 @@
+//! Oracle queries answered by the host during execution.
+//!
+//! Answers are untrusted advice: they are not folded into the input digest, and the guest
+//! must check them before relying on them. Provers replay the answers recorded during
+//! execution, so proving does not query the host oracle again.
+
+use crate::input::{read_with, GuestError};
+use crate::transport::Transport;
+use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+use airbender_core::oracle::ORACLE_QUERY_MARKER;
+use airbender_core::wire::frame_words_from_bytes;
+
+/// Send a tagged query to the host oracle and decode its answer.
+pub fn query<Req, Resp>(tag: u32, request: &Req) -> Result<Resp, GuestError>
+where
+    Req: serde::Serialize,
+    Resp: serde::de::DeserializeOwned,
+{
+    #[cfg(target_arch = "riscv32")]
+    {
+        query_with(&mut AdviceTransport, tag, request)
+    }
+    #[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+    {
+        query_with(&mut crate::native::OracleTransport::default(), tag, request)
+    }
+    #[cfg(all(not(feature = "native"), not(target_arch = "riscv32")))]
+    {
+        let _ = (tag, request);
+        Err(GuestError::UnsupportedTarget)
+    }
+}
+
+/// Send a tagged query using an explicit transport and decode the answer.
+pub fn query_with<Req, Resp>(
+    transport: &mut impl Transport,
+    tag: u32,
+    request: &Req,
+) -> Result<Resp, GuestError>
+where
+    Req: serde::Serialize,
+    Resp: serde::de::DeserializeOwned,
+{
+    let payload = AirbenderCodecV0::encode(request)?;
+    let words = frame_words_from_bytes(&payload)?;
+    transport.write_word(ORACLE_QUERY_MARKER);
+    transport.write_word(tag);
+    for word in words {
+        transport.write_word(word);
+    }
+    read_with(transport)
+}
+
+/// CSR transport whose reads bypass the input digest.
+#[cfg(target_arch = "riscv32")]
+struct AdviceTransport;
+
+#[cfg(target_arch = "riscv32")]
+impl Transport for AdviceTransport {
+    fn read_word(&mut self) -> u32 {
+        airbender_rt::sys::read_word()
+    }
+
+    fn write_word(&mut self, word: u32) {
+        airbender_rt::sys::write_word(word);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+    use airbender_core::wire::read_framed_bytes_with;
+
+    #[test]
+    fn writes_tagged_request_and_reads_answer() {
+        let answer = AirbenderCodecV0::encode(&99u64).expect("encode answer");
+        let mut transport = MockTransport::new(frame_words_from_bytes(&answer).expect("frame"));
+
+        let value: u64 = query_with(&mut transport, 7, &(3u32, 11u32)).expect("query");
+        assert_eq!(value, 99);
+
+        let mut writes = transport.into_writes().into_iter();
+        assert_eq!(writes.next(), Some(ORACLE_QUERY_MARKER));
+        assert_eq!(writes.next(), Some(7));
+        let request = read_framed_bytes_with(|| writes.next().expect("request word"));
+        let decoded: (u32, u32) = AirbenderCodecV0::decode(&request).expect("decode request");
+        assert_eq!(decoded, (3, 11));
+        assert_eq!(writes.next(), None);
+    }
+}
@@ -0,0 +1,76 @@
+//! Panics whose message stays out of the guest image in compact-panic builds.
+//!
+//! Compact-panic builds report every panic by the site ID of its location; `compact_panic!`
+//! also attaches a literal message to the site.
+
+use airbender_core::exit::GUEST_PANIC_LOCATION_MARKER;
+
+#[doc(hidden)]
+pub use airbender_core::panic_site::{encode_record, record_len, site_id, PanicSiteRecord};
+
+/// Report the panic site `id` to the host and exit with an error.
+#[doc(hidden)]
+pub fn exit_at_site(id: u32) -> ! {
+    airbender_rt::sys::write_word(GUEST_PANIC_LOCATION_MARKER);
+    airbender_rt::sys::write_word(id);
+    airbender_rt::sys::exit_error()
+}
+
+// The section name must match `airbender_core::panic_site::PANIC_SITE_SECTION`.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(airbender_compact_panics)]
+macro_rules! __airbender_compact_panic {
+    () => {
+        $crate::__airbender_compact_panic!("explicit panic")
+    };
+    ($message:literal $(,)?) => {{
+        const FILE: &str = ::core::file!();
+        const LINE: u32 = ::core::line!();
+        const COLUMN: u32 = ::core::column!();
+        const LEN: usize = $crate::panic::record_len(FILE, $message);
+        #[used]
+        #[link_section = ".airbender_panics"]
+        static SITE: $crate::panic::PanicSiteRecord<LEN> = $crate::panic::PanicSiteRecord(
+            $crate::panic::encode_record(FILE, LINE, COLUMN, $message),
+        );
+        $crate::panic::exit_at_site($crate::panic::site_id(FILE, LINE, COLUMN))
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(airbender_compact_panics))]
+macro_rules! __airbender_compact_panic {
+    () => {
+        ::core::panic!()
+    };
+    ($message:literal $(,)?) => {
+        ::core::panic!("{}", $message)
+    };
+}
+
+/// Panic with a literal message that compact-panic builds keep out of the guest image.
+///
+/// With `compact-panics` the guest reports only a site ID, and the location and message are
+/// resolved through the dist `panics.toml` table. Other panics report their location the same
+/// way, without a message. Other builds panic as usual.
+///
+/// ```ignore
+/// if n % 2 != 0 {
+///     airbender::guest::compact_panic!("input must be even");
+/// }
+/// ```
+#[doc(inline)]
+pub use crate::__airbender_compact_panic as compact_panic;
+
+#[cfg(test)]
+mod tests {
+    use super::compact_panic;
+
+    #[test]
+    #[should_panic(expected = "input must be even")]
+    fn panics_with_message_outside_compact_builds() {
+        compact_panic!("input must be even");
+    }
+}
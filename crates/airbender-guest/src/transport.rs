@@ -7,12 +7,17 @@ pub trait Transport {
 }
 
 /// CSR-backed transport for real guest execution.
+///
+/// Every word read is folded into the input digest (see [`crate::binding`]). With the
+/// `native` feature on other targets, it is backed by [`crate::native`] instead.
 pub struct CsrTransport;
 
 #[cfg(target_arch = "riscv32")]
 impl Transport for CsrTransport {
     fn read_word(&mut self) -> u32 {
-        airbender_rt::sys::read_word()
+        let word = airbender_rt::sys::read_word();
+        crate::binding::record_input_word(word);
+        word
     }
 
     fn write_word(&mut self, word: u32) {
@@ -20,7 +25,18 @@ impl Transport for CsrTransport {
     }
 }
 
-#[cfg(not(target_arch = "riscv32"))]
+#[cfg(all(feature = "native", not(target_arch = "riscv32")))]
+impl Transport for CsrTransport {
+    fn read_word(&mut self) -> u32 {
+        crate::native::read_word()
+    }
+
+    fn write_word(&mut self, word: u32) {
+        crate::native::write_word(word);
+    }
+}
+
+#[cfg(all(not(feature = "native"), not(target_arch = "riscv32")))]
 impl Transport for CsrTransport {
     fn read_word(&mut self) -> u32 {
         panic!("csr transport is only available on riscv32")
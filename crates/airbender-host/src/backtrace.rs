@@ -0,0 +1,154 @@
+//! Guest call stacks captured from failed transpiler runs.
+//!
+//! Guests are built with `-C force-frame-pointers`, so every frame saves its return address
+//! at `fp - 4` and the caller's frame pointer at `fp - 8`. Walking that chain from the final
+//! `pc` and `fp` gives the call stack, which is then resolved against `app.elf`: function
+//! names come from the symbol table, and file and line from DWARF line info when the guest
+//! profile keeps it (`debug = "line-tables-only"` or more).
+
+use crate::symbols::Symbolizer;
+use std::fmt;
+use std::path::Path;
+
+/// Deepest call stack collected from a guest.
+const MAX_FRAMES: usize = 128;
+
+/// Guest call stack at the point a run stopped, innermost frame first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Backtrace {
+    pub frames: Vec<BacktraceFrame>,
+}
+
+/// One guest frame; the address of a caller frame points at its call instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BacktraceFrame {
+    pub pc: u32,
+    pub function: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+impl Backtrace {
+    /// Walk the frame-pointer chain from `pc` and `fp`, reading guest memory with `read_word`.
+    pub(crate) fn capture(
+        pc: u32,
+        fp: u32,
+        read_word: impl Fn(u32) -> Option<u32>,
+        elf_path: &Path,
+    ) -> Self {
+        let addresses = frame_addresses(pc, fp, read_word);
+        let symbols = Symbolizer::load(elf_path)
+            .inspect_err(|err| tracing::debug!("cannot symbolize guest backtrace: {err}"))
+            .ok();
+        let frames = addresses
+            .into_iter()
+            .map(|pc| BacktraceFrame::resolve(pc, symbols.as_ref()))
+            .collect();
+        Self { frames }
+    }
+}
+
+impl BacktraceFrame {
+    fn resolve(pc: u32, symbols: Option<&Symbolizer>) -> Self {
+        let function = symbols.and_then(|symbols| symbols.function(pc));
+        let (file, line) = match symbols.and_then(|symbols| symbols.location(pc)) {
+            Some((file, line)) => (Some(file), line),
+            None => (None, None),
+        };
+        Self {
+            pc,
+            function,
+            file,
+            line,
+        }
+    }
+}
+
+/// Frame addresses from the innermost `pc` outward; caller entries are call sites.
+fn frame_addresses(pc: u32, fp: u32, read_word: impl Fn(u32) -> Option<u32>) -> Vec<u32> {
+    let mut addresses = vec![pc];
+    let mut fp = fp;
+    while addresses.len() < MAX_FRAMES && fp >= 8 && fp.is_multiple_of(4) {
+        let (Some(ra), Some(caller_fp)) = (read_word(fp - 4), read_word(fp - 8)) else {
+            break;
+        };
+        if ra < 4 {
+            break;
+        }
+        // `ra` follows the call; report the call instruction itself.
+        addresses.push(ra - 4);
+        // Caller frames live higher up the stack; anything else ends the chain.
+        if caller_fp <= fp {
+            break;
+        }
+        fp = caller_fp;
+    }
+    addresses
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, frame) in self.frames.iter().enumerate() {
+            let function = frame.function.as_deref().unwrap_or("<unknown>");
+            writeln!(f, "{index:>4}: {:#010x} - {function}", frame.pc)?;
+            if let Some(file) = &frame.file {
+                match frame.line {
+                    Some(line) => writeln!(f, "          at {file}:{line}")?,
+                    None => writeln!(f, "          at {file}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{frame_addresses, Backtrace, BacktraceFrame};
+    use std::collections::HashMap;
+
+    #[test]
+    fn walks_frame_pointer_chain_until_it_stops_growing() {
+        // Three frames at 0x1000, 0x1040 and 0x1080; the outermost saved fp points back down.
+        let stack: HashMap<u32, u32> = [
+            (0x0ffc, 0x204),
+            (0x0ff8, 0x1040),
+            (0x103c, 0x308),
+            (0x1038, 0x1080),
+            (0x107c, 0x40c),
+            (0x1078, 0x0800),
+        ]
+        .into_iter()
+        .collect();
+        let addresses = frame_addresses(0x120, 0x1000, |address| stack.get(&address).copied());
+        assert_eq!(addresses, [0x120, 0x200, 0x304, 0x408]);
+
+        assert_eq!(frame_addresses(0x120, 0, |_| Some(0x5000)), [0x120]);
+        assert_eq!(frame_addresses(0x120, 0x1000, |_| None), [0x120]);
+    }
+
+    #[test]
+    fn display_lists_frames_with_locations() {
+        let backtrace = Backtrace {
+            frames: vec![
+                BacktraceFrame {
+                    pc: 0x120,
+                    function: Some("airbender_rt::sys::exit_error".to_string()),
+                    file: None,
+                    line: None,
+                },
+                BacktraceFrame {
+                    pc: 0x200,
+                    function: None,
+                    file: Some("src/main.rs".to_string()),
+                    line: Some(12),
+                },
+            ],
+        };
+        assert_eq!(
+            backtrace.to_string(),
+            "   0: 0x00000120 - airbender_rt::sys::exit_error\n   \
+             1: 0x00000200 - <unknown>\n          at src/main.rs:12\n"
+        );
+    }
+}
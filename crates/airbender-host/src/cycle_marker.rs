@@ -1,5 +1,7 @@
 //! Stable host-side cycle marker snapshots collected from transpiler runs.
 
+use airbender_core::cycle::{RegionEvent, RegionKind};
+use airbender_core::host::regions::RegionTable;
 use std::collections::HashMap;
 
 /// Snapshot of the cumulative cycle and delegation counters at one marker.
@@ -36,6 +38,13 @@ impl Mark {
             delegations,
         }
     }
+
+    fn add(&mut self, other: &Self) {
+        self.cycles += other.cycles;
+        for (id, count) in &other.delegations {
+            *self.delegations.entry(*id).or_default() += count;
+        }
+    }
 }
 
 impl From<riscv_transpiler::cycle::Mark> for Mark {
@@ -52,6 +61,144 @@ impl From<riscv_transpiler::cycle::Mark> for Mark {
 pub struct CycleMarker {
     pub markers: Vec<Mark>,
     pub delegation_counter: HashMap<u32, u64>,
+    /// Named regions recorded with `record_cycles!` or `#[airbender::profile]`, as a tree.
+    #[serde(default)]
+    pub regions: Vec<Region>,
+}
+
+/// Work of one named region, summed over its calls from the same parent region.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Region {
+    /// Region name, or the label ID in hex when the dist `regions.toml` does not have it.
+    pub name: String,
+    pub calls: u64,
+    /// Work between the region markers, nested regions included.
+    pub inclusive: Mark,
+    /// Inclusive work minus that of the nested regions.
+    pub exclusive: Mark,
+    pub children: Vec<Region>,
+}
+
+impl CycleMarker {
+    /// Append the markers of a run resumed from a snapshot to `self`, the markers collected
+    /// before that snapshot was taken.
+    ///
+    /// Marker cycles already count from the start of the run, while the delegations of the
+    /// resumed part start over and are offset by the ones counted before the snapshot.
+    pub(crate) fn followed_by(mut self, resumed: Self) -> Self {
+        for mut mark in resumed.markers {
+            for (id, count) in &self.delegation_counter {
+                *mark.delegations.entry(*id).or_default() += count;
+            }
+            self.markers.push(mark);
+        }
+        for (id, count) in resumed.delegation_counter {
+            *self.delegation_counter.entry(id).or_default() += count;
+        }
+        self
+    }
+
+    /// Build the region tree from guest region `events`, naming labels through `table`.
+    ///
+    /// Events whose region was never closed, or whose markers are missing, are ignored.
+    pub(crate) fn with_regions(
+        mut self,
+        events: &[RegionEvent],
+        table: Option<&RegionTable>,
+    ) -> Self {
+        let mut roots = Vec::new();
+        let mut open: Vec<RegionEvent> = Vec::new();
+        for event in events {
+            match event.kind {
+                RegionKind::Enter => open.push(*event),
+                RegionKind::Exit => {
+                    let Some(depth) = open.iter().rposition(|enter| enter.label == event.label)
+                    else {
+                        continue;
+                    };
+                    let enter = open[depth];
+                    open.truncate(depth);
+                    let marks = (
+                        self.markers.get(enter.marker as usize),
+                        self.markers.get(event.marker as usize),
+                    );
+                    let (Some(before), Some(after)) = marks else {
+                        continue;
+                    };
+                    if enter.marker > event.marker {
+                        continue;
+                    }
+                    let path: Vec<u32> = open
+                        .iter()
+                        .map(|enter| enter.label)
+                        .chain([event.label])
+                        .collect();
+                    let node = RegionNode::find_or_insert(&mut roots, &path);
+                    node.calls += 1;
+                    node.inclusive.add(&after.diff(before));
+                }
+            }
+        }
+        self.regions = roots
+            .into_iter()
+            .map(|node| node.into_region(table))
+            .collect();
+        self
+    }
+}
+
+/// Region tree node keyed by label ID, before names and exclusive work are filled in.
+struct RegionNode {
+    label: u32,
+    calls: u64,
+    inclusive: Mark,
+    children: Vec<RegionNode>,
+}
+
+impl RegionNode {
+    fn find_or_insert<'a>(nodes: &'a mut Vec<RegionNode>, path: &[u32]) -> &'a mut RegionNode {
+        let (label, rest) = path.split_first().expect("region path is never empty");
+        let index = match nodes.iter().position(|node| node.label == *label) {
+            Some(index) => index,
+            None => {
+                nodes.push(RegionNode {
+                    label: *label,
+                    calls: 0,
+                    inclusive: Mark::default(),
+                    children: Vec::new(),
+                });
+                nodes.len() - 1
+            }
+        };
+        let node = &mut nodes[index];
+        if rest.is_empty() {
+            node
+        } else {
+            Self::find_or_insert(&mut node.children, rest)
+        }
+    }
+
+    fn into_region(self, table: Option<&RegionTable>) -> Region {
+        let name = match table.and_then(|table| table.resolve(self.label)) {
+            Some(region) => region.name.clone(),
+            None => format!("{:#010x}", self.label),
+        };
+        let mut nested = Mark::default();
+        for child in &self.children {
+            nested.add(&child.inclusive);
+        }
+        Region {
+            name,
+            calls: self.calls,
+            exclusive: self.inclusive.diff(&nested),
+            inclusive: self.inclusive,
+            children: self
+                .children
+                .into_iter()
+                .map(|child| child.into_region(table))
+                .collect(),
+        }
+    }
 }
 
 impl From<riscv_transpiler::cycle::CycleMarker> for CycleMarker {
@@ -59,13 +206,16 @@ impl From<riscv_transpiler::cycle::CycleMarker> for CycleMarker {
         Self {
             markers: marker.markers.into_iter().map(Mark::from).collect(),
             delegation_counter: marker.delegation_counter,
+            regions: Vec::new(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Mark;
+    use super::{CycleMarker, Mark};
+    use airbender_core::cycle::{RegionEvent, RegionKind};
+    use airbender_core::host::regions::{RegionName, RegionTable};
     use std::collections::HashMap;
 
     #[test]
@@ -86,4 +236,92 @@ mod tests {
         assert_eq!(diff.delegations.get(&0x7cb), Some(&3));
         assert_eq!(diff.delegations.get(&0x7cc), Some(&1));
     }
+
+    #[test]
+    fn resumed_markers_continue_the_delegation_counts() {
+        let before = CycleMarker {
+            markers: vec![Mark {
+                cycles: 4,
+                delegations: HashMap::from([(0x7ca, 1)]),
+            }],
+            delegation_counter: HashMap::from([(0x7ca, 2)]),
+            ..CycleMarker::default()
+        };
+        let resumed = CycleMarker {
+            markers: vec![Mark {
+                cycles: 9,
+                delegations: HashMap::from([(0x7cb, 1)]),
+            }],
+            delegation_counter: HashMap::from([(0x7cb, 1)]),
+            ..CycleMarker::default()
+        };
+
+        let markers = before.followed_by(resumed);
+
+        assert_eq!(markers.markers.len(), 2);
+        assert_eq!(
+            markers.markers[1],
+            Mark {
+                cycles: 9,
+                delegations: HashMap::from([(0x7ca, 2), (0x7cb, 1)]),
+            }
+        );
+        assert_eq!(
+            markers.delegation_counter,
+            HashMap::from([(0x7ca, 2), (0x7cb, 1)])
+        );
+    }
+
+    #[test]
+    fn builds_region_tree_with_inclusive_and_exclusive_work() {
+        let mark = |cycles, keccak| Mark {
+            cycles,
+            delegations: HashMap::from([(0x7cb, keccak)]),
+        };
+        let event = |label, kind, marker| RegionEvent {
+            label,
+            kind,
+            marker,
+        };
+        let (outer, inner) = (0x1000, 0x2000);
+        // outer { inner {} inner {} } and an unclosed outer.
+        let markers = CycleMarker {
+            markers: vec![
+                mark(0, 0),
+                mark(10, 0),
+                mark(30, 2),
+                mark(35, 2),
+                mark(45, 3),
+                mark(50, 3),
+                mark(60, 3),
+            ],
+            ..CycleMarker::default()
+        };
+        let events = [
+            event(outer, RegionKind::Enter, 0),
+            event(inner, RegionKind::Enter, 1),
+            event(inner, RegionKind::Exit, 2),
+            event(inner, RegionKind::Enter, 3),
+            event(inner, RegionKind::Exit, 4),
+            event(outer, RegionKind::Exit, 5),
+            event(outer, RegionKind::Enter, 6),
+        ];
+        let table = RegionTable::new(vec![RegionName {
+            id: outer,
+            name: "outer".to_string(),
+        }]);
+
+        let regions = markers.with_regions(&events, Some(&table)).regions;
+
+        assert_eq!(regions.len(), 1);
+        let outer = &regions[0];
+        assert_eq!((outer.name.as_str(), outer.calls), ("outer", 1));
+        assert_eq!(outer.inclusive, mark(50, 3));
+        assert_eq!(outer.exclusive.cycles, 20);
+        assert!(outer.exclusive.delegations.is_empty());
+        let inner = &outer.children[0];
+        assert_eq!((inner.name.as_str(), inner.calls), ("0x00002000", 2));
+        assert_eq!(inner.inclusive, mark(30, 3));
+        assert_eq!(inner.exclusive, inner.inclusive);
+    }
 }
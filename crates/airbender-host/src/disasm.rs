@@ -0,0 +1,218 @@
+//! RV32IM instruction fields and disassembly for execution traces.
+
+/// ABI names of `x0..x31`.
+pub(crate) const REGISTER_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "fp", "s1", "a0", "a1", "a2", "a3", "a4",
+    "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4",
+    "t5", "t6",
+];
+
+pub(crate) const OPCODE_LOAD: u32 = 0x03;
+pub(crate) const OPCODE_STORE: u32 = 0x23;
+pub(crate) const OPCODE_SYSTEM: u32 = 0x73;
+const OPCODE_LUI: u32 = 0x37;
+const OPCODE_AUIPC: u32 = 0x17;
+const OPCODE_JAL: u32 = 0x6f;
+const OPCODE_JALR: u32 = 0x67;
+const OPCODE_BRANCH: u32 = 0x63;
+const OPCODE_OP_IMM: u32 = 0x13;
+const OPCODE_OP: u32 = 0x33;
+const OPCODE_FENCE: u32 = 0x0f;
+
+pub(crate) fn opcode(word: u32) -> u32 {
+    word & 0x7f
+}
+
+pub(crate) fn rd(word: u32) -> usize {
+    ((word >> 7) & 0x1f) as usize
+}
+
+pub(crate) fn funct3(word: u32) -> u32 {
+    (word >> 12) & 0x7
+}
+
+pub(crate) fn rs1(word: u32) -> usize {
+    ((word >> 15) & 0x1f) as usize
+}
+
+pub(crate) fn rs2(word: u32) -> usize {
+    ((word >> 20) & 0x1f) as usize
+}
+
+fn funct7(word: u32) -> u32 {
+    word >> 25
+}
+
+pub(crate) fn imm_i(word: u32) -> i32 {
+    (word as i32) >> 20
+}
+
+pub(crate) fn imm_s(word: u32) -> i32 {
+    (((word & 0xfe00_0000) as i32) >> 20) | ((word >> 7) & 0x1f) as i32
+}
+
+fn imm_b(word: u32) -> i32 {
+    (((word & 0x8000_0000) as i32) >> 19)
+        | ((word & 0x80) << 4) as i32
+        | ((word >> 20) & 0x7e0) as i32
+        | ((word >> 7) & 0x1e) as i32
+}
+
+fn imm_j(word: u32) -> i32 {
+    (((word & 0x8000_0000) as i32) >> 11)
+        | (word & 0xf_f000) as i32
+        | ((word >> 9) & 0x800) as i32
+        | ((word >> 20) & 0x7fe) as i32
+}
+
+/// Whether `word` writes its `rd` field.
+pub(crate) fn writes_rd(word: u32) -> bool {
+    match opcode(word) {
+        OPCODE_LUI | OPCODE_AUIPC | OPCODE_JAL | OPCODE_JALR | OPCODE_LOAD | OPCODE_OP_IMM
+        | OPCODE_OP => true,
+        OPCODE_SYSTEM => funct3(word) != 0,
+        _ => false,
+    }
+}
+
+/// Bytes accessed by a load or store with `funct3`.
+pub(crate) fn access_width(funct3: u32) -> u8 {
+    1 << (funct3 & 0x3)
+}
+
+/// Assembly text of one instruction; branch and jump offsets are relative to its `pc`.
+pub(crate) fn disassemble(word: u32) -> String {
+    let reg = |index: usize| REGISTER_NAMES[index];
+    let (rd, rs1, rs2) = (reg(rd(word)), reg(rs1(word)), reg(rs2(word)));
+    let unknown = || format!(".word {word:#010x}");
+    match opcode(word) {
+        OPCODE_LUI => format!("lui {rd}, {:#x}", word >> 12),
+        OPCODE_AUIPC => format!("auipc {rd}, {:#x}", word >> 12),
+        OPCODE_JAL => match self::rd(word) {
+            0 => format!("j {}", imm_j(word)),
+            _ => format!("jal {rd}, {}", imm_j(word)),
+        },
+        OPCODE_JALR if word == 0x0000_8067 => "ret".to_string(),
+        OPCODE_JALR => format!("jalr {rd}, {}({rs1})", imm_i(word)),
+        OPCODE_BRANCH => {
+            let name = match funct3(word) {
+                0 => "beq",
+                1 => "bne",
+                4 => "blt",
+                5 => "bge",
+                6 => "bltu",
+                7 => "bgeu",
+                _ => return unknown(),
+            };
+            format!("{name} {rs1}, {rs2}, {}", imm_b(word))
+        }
+        OPCODE_LOAD => {
+            let name = match funct3(word) {
+                0 => "lb",
+                1 => "lh",
+                2 => "lw",
+                4 => "lbu",
+                5 => "lhu",
+                _ => return unknown(),
+            };
+            format!("{name} {rd}, {}({rs1})", imm_i(word))
+        }
+        OPCODE_STORE => {
+            let name = match funct3(word) {
+                0 => "sb",
+                1 => "sh",
+                2 => "sw",
+                _ => return unknown(),
+            };
+            format!("{name} {rs2}, {}({rs1})", imm_s(word))
+        }
+        OPCODE_OP_IMM => {
+            let shamt = self::rs2(word);
+            match (funct3(word), funct7(word)) {
+                (0, _) if word == 0x0000_0013 => "nop".to_string(),
+                (0, _) => format!("addi {rd}, {rs1}, {}", imm_i(word)),
+                (2, _) => format!("slti {rd}, {rs1}, {}", imm_i(word)),
+                (3, _) => format!("sltiu {rd}, {rs1}, {}", imm_i(word)),
+                (4, _) => format!("xori {rd}, {rs1}, {}", imm_i(word)),
+                (6, _) => format!("ori {rd}, {rs1}, {}", imm_i(word)),
+                (7, _) => format!("andi {rd}, {rs1}, {}", imm_i(word)),
+                (1, 0x00) => format!("slli {rd}, {rs1}, {shamt}"),
+                (5, 0x00) => format!("srli {rd}, {rs1}, {shamt}"),
+                (5, 0x20) => format!("srai {rd}, {rs1}, {shamt}"),
+                _ => unknown(),
+            }
+        }
+        OPCODE_OP => {
+            let name = match (funct7(word), funct3(word)) {
+                (0x00, 0) => "add",
+                (0x20, 0) => "sub",
+                (0x00, 1) => "sll",
+                (0x00, 2) => "slt",
+                (0x00, 3) => "sltu",
+                (0x00, 4) => "xor",
+                (0x00, 5) => "srl",
+                (0x20, 5) => "sra",
+                (0x00, 6) => "or",
+                (0x00, 7) => "and",
+                (0x01, 0) => "mul",
+                (0x01, 1) => "mulh",
+                (0x01, 2) => "mulhsu",
+                (0x01, 3) => "mulhu",
+                (0x01, 4) => "div",
+                (0x01, 5) => "divu",
+                (0x01, 6) => "rem",
+                (0x01, 7) => "remu",
+                _ => return unknown(),
+            };
+            format!("{name} {rd}, {rs1}, {rs2}")
+        }
+        OPCODE_FENCE => "fence".to_string(),
+        OPCODE_SYSTEM => {
+            let csr = word >> 20;
+            let zimm = self::rs1(word);
+            match funct3(word) {
+                0 => match word {
+                    0x0000_0073 => "ecall".to_string(),
+                    0x0010_0073 => "ebreak".to_string(),
+                    0x3020_0073 => "mret".to_string(),
+                    0x1050_0073 => "wfi".to_string(),
+                    _ => unknown(),
+                },
+                1 => format!("csrrw {rd}, {csr:#x}, {rs1}"),
+                2 => format!("csrrs {rd}, {csr:#x}, {rs1}"),
+                3 => format!("csrrc {rd}, {csr:#x}, {rs1}"),
+                5 => format!("csrrwi {rd}, {csr:#x}, {zimm}"),
+                6 => format!("csrrsi {rd}, {csr:#x}, {zimm}"),
+                7 => format!("csrrci {rd}, {csr:#x}, {zimm}"),
+                _ => unknown(),
+            }
+        }
+        _ => unknown(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::disassemble;
+
+    #[test]
+    fn disassembles_common_instructions() {
+        let cases = [
+            (0x0000_0013, "nop"),
+            (0x0010_0093, "addi ra, zero, 1"),
+            (0xfff5_0513, "addi a0, a0, -1"),
+            (0x00b5_0533, "add a0, a0, a1"),
+            (0x02b5_0533, "mul a0, a0, a1"),
+            (0x0085_2503, "lw a0, 8(a0)"),
+            (0xfea1_2e23, "sw a0, -4(sp)"),
+            (0xfeb5_0ce3, "beq a0, a1, -8"),
+            (0x0000_006f, "j 0"),
+            (0x0000_8067, "ret"),
+            (0x7ff0_1073, "csrrw zero, 0x7ff, zero"),
+            (0x0000_0000, ".word 0x00000000"),
+        ];
+        for (word, text) in cases {
+            assert_eq!(disassemble(word), text, "{word:#010x}");
+        }
+    }
+}
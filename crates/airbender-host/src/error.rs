@@ -6,6 +6,10 @@ pub enum HostError {
     Codec(airbender_codec::CodecError),
     #[error("wire error: {0}")]
     Wire(airbender_core::wire::WireError),
+    #[error("journal error: {0}")]
+    Journal(String),
+    #[error("oracle error: {0}")]
+    Oracle(String),
     #[error("invalid manifest: {0}")]
     InvalidManifest(String),
     #[error("transpiler error: {0}")]
@@ -0,0 +1,92 @@
+//! Arguments, environment variables and stdin for `std` guests.
+
+use crate::error::Result;
+use airbender_core::preamble::Preamble;
+
+/// Program environment served to `std::env::args`, `std::env::var` and `std::io::stdin`.
+///
+/// The guest requests it lazily over the CSR transport, so it is separate from [`Inputs`].
+/// `bind_inputs` folds it into the input digest after the input words; verifiers pass it with
+/// `VerificationRequest::with_expected_guest_env`.
+///
+/// [`Inputs`]: crate::Inputs
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GuestEnv {
+    args: Vec<String>,
+    vars: Vec<(String, String)>,
+    stdin: Vec<u8>,
+}
+
+impl GuestEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append one argument; the first argument is conventionally the program name.
+    pub fn with_arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Append several arguments in order.
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set an environment variable; setting the same key again overrides it.
+    pub fn with_var(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vars.push((key.into(), value.into()));
+        self
+    }
+
+    /// Bytes the guest reads from stdin.
+    pub fn with_stdin(mut self, stdin: impl Into<Vec<u8>>) -> Self {
+        self.stdin = stdin.into();
+        self
+    }
+
+    /// Preamble words answering the guest's environment request.
+    pub(crate) fn preamble_words(&self) -> Result<Vec<u32>> {
+        let preamble = Preamble {
+            args: self
+                .args
+                .iter()
+                .map(|arg| arg.as_bytes().to_vec())
+                .collect(),
+            vars: self
+                .vars
+                .iter()
+                .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+                .collect(),
+            stdin: self.stdin.clone(),
+        };
+        Ok(preamble.to_words()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GuestEnv;
+    use airbender_core::preamble::Preamble;
+
+    #[test]
+    fn preamble_words_decode_to_configured_env() {
+        let env = GuestEnv::new()
+            .with_args(["grep", "-n"])
+            .with_arg("needle")
+            .with_var("LANG", "C")
+            .with_stdin("hay\nneedle\n");
+        let words = env.preamble_words().expect("encode preamble");
+        let mut cursor = words.iter().copied();
+        let preamble = Preamble::read_with(|| cursor.next().expect("preamble word"));
+
+        assert_eq!(preamble.args, [&b"grep"[..], b"-n", b"needle"]);
+        assert_eq!(preamble.var(b"LANG"), Some(&b"C"[..]));
+        assert_eq!(preamble.stdin, b"hay\nneedle\n");
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::Result;
 use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+use airbender_core::rng::{seed_to_words, RNG_SEED_MARKER, RNG_SEED_WORDS};
 use airbender_core::wire::frame_words_from_bytes;
 use std::fmt::Write as _;
 use std::path::Path;
@@ -8,11 +9,40 @@ use std::path::Path;
 #[derive(Clone, Debug, Default)]
 pub struct Inputs {
     words: Vec<u32>,
+    has_rng_seed: bool,
 }
 
 impl Inputs {
     pub fn new() -> Self {
-        Self { words: Vec::new() }
+        Self {
+            words: Vec::new(),
+            has_rng_seed: false,
+        }
+    }
+
+    /// Supply the seed for guests built with the runtime `rng-seed` feature.
+    ///
+    /// The seed section always leads the input stream, regardless of push order; setting it
+    /// again replaces the previous seed.
+    pub fn with_rng_seed(mut self, seed: [u8; 32]) -> Self {
+        let section = std::iter::once(RNG_SEED_MARKER).chain(seed_to_words(&seed));
+        let replaced = if self.has_rng_seed {
+            1 + RNG_SEED_WORDS
+        } else {
+            0
+        };
+        self.words.splice(..replaced, section);
+        self.has_rng_seed = true;
+        self
+    }
+
+    /// Build inputs from a tuple, pushing each element in order.
+    ///
+    /// This matches a guest entrypoint such as `fn main(a: A, b: B)` for `(a, b)`.
+    pub fn from_tuple<T: InputTuple>(values: &T) -> Result<Self> {
+        let mut inputs = Self::new();
+        values.push_into(&mut inputs)?;
+        Ok(inputs)
     }
 
     /// Serialize and append a typed input value.
@@ -45,9 +75,38 @@ impl Inputs {
     }
 }
 
+/// Tuples whose elements are pushed as separate inputs, in order.
+pub trait InputTuple {
+    fn push_into(&self, inputs: &mut Inputs) -> Result<()>;
+}
+
+macro_rules! impl_input_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: serde::Serialize),*> InputTuple for ($($name,)*) {
+            #[allow(non_snake_case, unused_variables)]
+            fn push_into(&self, inputs: &mut Inputs) -> Result<()> {
+                let ($($name,)*) = self;
+                $(inputs.push($name)?;)*
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_input_tuple!();
+impl_input_tuple!(A);
+impl_input_tuple!(A, B);
+impl_input_tuple!(A, B, C);
+impl_input_tuple!(A, B, C, D);
+impl_input_tuple!(A, B, C, D, E);
+impl_input_tuple!(A, B, C, D, E, F);
+impl_input_tuple!(A, B, C, D, E, F, G);
+impl_input_tuple!(A, B, C, D, E, F, G, H);
+
 #[cfg(test)]
 mod tests {
     use super::Inputs;
+    use airbender_core::rng::{seed_to_words, RNG_SEED_MARKER};
     use std::fs;
     use std::path::PathBuf;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -72,6 +131,33 @@ mod tests {
         fs::remove_file(&file_path).expect("remove input hex file");
     }
 
+    #[test]
+    fn from_tuple_matches_sequential_pushes() {
+        let mut expected = Inputs::new();
+        expected.push(&7u32).expect("push u32");
+        expected.push(&true).expect("push bool");
+        expected.push(&String::from("input")).expect("push string");
+
+        let inputs =
+            Inputs::from_tuple(&(7u32, true, String::from("input"))).expect("inputs from tuple");
+        assert_eq!(inputs.words(), expected.words());
+    }
+
+    #[test]
+    fn rng_seed_section_leads_the_stream() {
+        let mut plain = Inputs::new();
+        plain.push(&7u32).expect("push u32");
+
+        let mut inputs = Inputs::new().with_rng_seed([1u8; 32]);
+        inputs.push(&7u32).expect("push u32");
+        let inputs = inputs.with_rng_seed([2u8; 32]);
+
+        let words = inputs.words();
+        assert_eq!(words[0], RNG_SEED_MARKER);
+        assert_eq!(words[1..9], seed_to_words(&[2u8; 32]));
+        assert_eq!(&words[9..], plain.words());
+    }
+
     fn test_file_path(prefix: &str) -> PathBuf {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
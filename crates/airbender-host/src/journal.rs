@@ -0,0 +1,85 @@
+//! Host-side access to the public journal emitted by guests.
+
+use crate::error::{HostError, Result};
+use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+use airbender_core::journal::{entry_at, journal_digest_words};
+
+/// Compute the digest words a guest commits for the given journal bytes.
+pub fn journal_digest(journal: &[u8]) -> [u32; 8] {
+    journal_digest_words(journal)
+}
+
+/// Sequential typed reader over raw journal bytes.
+pub struct JournalReader<'a> {
+    journal: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> JournalReader<'a> {
+    pub fn new(journal: &'a [u8]) -> Self {
+        Self { journal, offset: 0 }
+    }
+
+    /// Decode the next journal entry as `T`.
+    pub fn read<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        let payload = self.read_raw()?;
+        AirbenderCodecV0::decode(payload).map_err(HostError::from)
+    }
+
+    /// Return the next journal entry payload without decoding it.
+    pub fn read_raw(&mut self) -> Result<&'a [u8]> {
+        if self.is_empty() {
+            return Err(HostError::Journal(
+                "no journal entries left to read".to_string(),
+            ));
+        }
+        let (payload, next) = entry_at(self.journal, self.offset)
+            .map_err(|err| HostError::Journal(err.to_string()))?;
+        self.offset = next;
+        Ok(payload)
+    }
+
+    /// Whether all journal entries have been read.
+    pub fn is_empty(&self) -> bool {
+        self.offset >= self.journal.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{journal_digest, JournalReader};
+    use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+    use airbender_core::journal::{encode_entry, JournalHasher};
+
+    #[test]
+    fn reads_typed_entries_in_order() {
+        let first = AirbenderCodecV0::encode(&42u64).expect("encode u64");
+        let second = AirbenderCodecV0::encode(&vec![1u32, 2, 3]).expect("encode vec");
+        let mut journal = encode_entry(&first);
+        journal.extend(encode_entry(&second));
+
+        let mut reader = JournalReader::new(&journal);
+        assert_eq!(reader.read::<u64>().expect("read u64"), 42);
+        assert_eq!(
+            reader.read::<Vec<u32>>().expect("read vec"),
+            vec![1u32, 2, 3]
+        );
+        assert!(reader.is_empty());
+
+        let err = reader.read::<u64>().expect_err("reader is exhausted");
+        assert_eq!(
+            err.to_string(),
+            "journal error: no journal entries left to read"
+        );
+    }
+
+    #[test]
+    fn digest_matches_guest_hasher() {
+        let mut hasher = JournalHasher::new();
+        hasher.update_entry(b"entry");
+        assert_eq!(
+            journal_digest(&encode_entry(b"entry")),
+            hasher.digest_words()
+        );
+    }
+}
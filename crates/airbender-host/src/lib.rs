@@ -1,20 +1,39 @@
 #![doc = include_str!("../README.md")]
 
+mod backtrace;
 mod cycle_marker;
+mod disasm;
 mod error;
+mod guest_env;
 mod inputs;
+mod journal;
+mod native;
+mod oracle;
 mod program;
 mod proof;
 mod prover;
 mod receipt;
 mod runner;
+mod snapshot;
+mod symbols;
+mod trace;
 mod verifier;
 mod vk;
 
-pub use airbender_core::guest::Commit;
-pub use cycle_marker::{CycleMarker, Mark};
+pub use airbender_core::guest::{Commit, CommitField, FromCommit};
+pub use airbender_core::host::manifest::MemoryLayout;
+pub use airbender_core::host::panics::{PanicLocation, PanicTable};
+pub use airbender_core::host::regions::{RegionName, RegionTable};
+pub use airbender_core::memory::MemoryStats;
+pub use airbender_macros::Commit;
+pub use backtrace::{Backtrace, BacktraceFrame};
+pub use cycle_marker::{CycleMarker, Mark, Region};
 pub use error::{HostError, Result};
-pub use inputs::Inputs;
+pub use guest_env::GuestEnv;
+pub use inputs::{InputTuple, Inputs};
+pub use journal::{journal_digest, JournalReader};
+pub use native::{NativeError, NativeOutput, NativeRunner};
+pub use oracle::{decode_oracle_request, encode_oracle_answer, Oracle};
 pub use program::Program;
 pub use proof::{DevProof, Proof, RealProof};
 pub use prover::{
@@ -24,13 +43,19 @@ pub use prover::{
 pub use prover::{GpuProver, GpuProverBuilder};
 pub use receipt::Receipt;
 pub use runner::{
-    resolve_cycles, ExecutionResult, FlamegraphConfig, Runner, TranspilerRunner,
-    TranspilerRunnerBuilder, DEFAULT_CYCLES,
+    resolve_cycles, ExecutionOutcome, ExecutionResult, FlamegraphConfig, GuestError, GuestMemory,
+    Runner, TranspilerRunner, TranspilerRunnerBuilder, DEFAULT_CYCLES,
+};
+pub use snapshot::{SnapshotConfig, SnapshotPoint};
+pub use symbols::Symbolizer;
+pub use trace::{
+    read_trace, CsrAccess, MemoryAccess, MemoryAccessKind, RegisterWrite, TraceConfig, TraceEntry,
+    TraceFormat, BINARY_TRACE_MAGIC,
 };
 pub use verifier::{
-    verify_real_proof_with_vk, DevVerificationKey, DevVerifier, DevVerifierBuilder,
-    RealUnifiedVerificationKey, RealUnrolledVerificationKey, RealVerifier, RealVerifierBuilder,
-    VerificationKey, VerificationRequest, Verifier,
+    bound_output, bound_output_with_env, verify_real_proof_with_vk, DevVerificationKey,
+    DevVerifier, DevVerifierBuilder, RealUnifiedVerificationKey, RealUnrolledVerificationKey,
+    RealVerifier, RealVerifierBuilder, VerificationKey, VerificationRequest, Verifier,
 };
 pub use vk::{
     compute_unified_vk, compute_unrolled_vk, verify_proof, verify_unrolled_proof, UnifiedVk,
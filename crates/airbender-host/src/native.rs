@@ -0,0 +1,117 @@
+//! Native runs of guest code, for checking it against transpiler execution.
+
+use crate::error::{HostError, Result};
+use crate::inputs::Inputs;
+use crate::oracle::Oracle;
+use crate::receipt::Receipt;
+use core::convert::Infallible;
+use std::sync::Arc;
+
+pub use airbender_guest::native::{NativeError, NativeOutput};
+
+/// Runs a guest entry natively on the host, with the same inputs and oracle as a transpiler run.
+///
+/// `entry` is the `airbender_native_entry` function that `#[airbender::main]` generates for
+/// guests built with the SDK `native` feature.
+#[derive(Clone, Default)]
+pub struct NativeRunner {
+    oracle: Option<Arc<dyn Oracle>>,
+}
+
+impl NativeRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer guest oracle queries with `oracle` during native runs.
+    pub fn with_oracle(mut self, oracle: impl Oracle + 'static) -> Self {
+        self.oracle = Some(Arc::new(oracle));
+        self
+    }
+
+    /// Run `entry` natively over `inputs`.
+    pub fn run(&self, inputs: &Inputs, entry: impl FnOnce() -> Infallible) -> Result<NativeOutput> {
+        let result = match self.oracle.clone() {
+            Some(oracle) => airbender_guest::native::run_with_oracle(
+                inputs.words(),
+                move |tag, request| {
+                    oracle.answer(tag, request).map_err(|err| match err {
+                        HostError::Oracle(message) => message,
+                        err => err.to_string(),
+                    })
+                },
+                entry,
+            ),
+            None => airbender_guest::native::run(inputs.words(), entry),
+        };
+        result.map_err(|err| match err {
+            NativeError::Oracle(message) => HostError::Oracle(message),
+            err => HostError::Runner(format!("native run failed: {err}")),
+        })
+    }
+
+    /// Run `entry` natively and check that its output matches `receipt` from a transpiler run.
+    pub fn run_and_compare(
+        &self,
+        inputs: &Inputs,
+        receipt: &Receipt,
+        entry: impl FnOnce() -> Infallible,
+    ) -> Result<NativeOutput> {
+        let output = self.run(inputs, entry)?;
+        if output.output != receipt.output {
+            return Err(HostError::Runner(format!(
+                "native output {:?} does not match transpiler output {:?}",
+                output.output, receipt.output
+            )));
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NativeRunner;
+    use crate::error::{HostError, Result};
+    use crate::inputs::Inputs;
+    use crate::oracle::{decode_oracle_request, encode_oracle_answer};
+    use crate::receipt::Receipt;
+    use core::convert::Infallible;
+
+    fn entry() -> Infallible {
+        let n: u32 = airbender_guest::read().expect("read input");
+        let doubled: u32 = airbender_guest::oracle::query(1, &n).expect("oracle answer");
+        airbender_guest::commit(doubled + 1)
+    }
+
+    fn receipt_with_output(value: u32) -> Receipt {
+        let mut registers = [0u32; 32];
+        registers[10] = value;
+        Receipt::from_registers(registers)
+    }
+
+    #[test]
+    fn native_output_is_compared_with_receipt() {
+        let runner =
+            NativeRunner::new().with_oracle(|_tag: u32, request: &[u8]| -> Result<Vec<u8>> {
+                let n: u32 = decode_oracle_request(request)?;
+                encode_oracle_answer(&(n * 2))
+            });
+        let mut inputs = Inputs::new();
+        inputs.push(&20u32).expect("push input");
+
+        let output = runner
+            .run_and_compare(&inputs, &receipt_with_output(41), entry)
+            .expect("matching output");
+        assert_eq!(output.input_words_consumed, inputs.words().len());
+
+        let err = runner
+            .run_and_compare(&inputs, &receipt_with_output(42), entry)
+            .expect_err("mismatched output");
+        assert!(matches!(err, HostError::Runner(message) if message.contains("does not match")));
+
+        let err = NativeRunner::new()
+            .run(&inputs, entry)
+            .expect_err("no oracle");
+        assert!(matches!(err, HostError::Oracle(_)));
+    }
+}
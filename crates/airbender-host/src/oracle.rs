@@ -0,0 +1,55 @@
+//! Host-answered oracle queries issued by guests during execution.
+
+use crate::error::{HostError, Result};
+use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+
+/// Answers guest `oracle::query` calls during execution.
+///
+/// `request` and the returned bytes are Airbender codec payloads; `tag` is the value the
+/// guest passed to tell query kinds apart.
+pub trait Oracle: Send + Sync {
+    fn answer(&self, tag: u32, request: &[u8]) -> Result<Vec<u8>>;
+}
+
+impl<F> Oracle for F
+where
+    F: Fn(u32, &[u8]) -> Result<Vec<u8>> + Send + Sync,
+{
+    fn answer(&self, tag: u32, request: &[u8]) -> Result<Vec<u8>> {
+        self(tag, request)
+    }
+}
+
+/// Decode an oracle request payload as `T`.
+pub fn decode_oracle_request<T: serde::de::DeserializeOwned>(request: &[u8]) -> Result<T> {
+    AirbenderCodecV0::decode(request).map_err(HostError::from)
+}
+
+/// Encode `value` as an oracle answer payload.
+pub fn encode_oracle_answer<T: serde::Serialize>(value: &T) -> Result<Vec<u8>> {
+    AirbenderCodecV0::encode(value).map_err(HostError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_oracle_request, encode_oracle_answer, Oracle};
+    use crate::error::{HostError, Result};
+
+    #[test]
+    fn closures_answer_typed_queries() {
+        let oracle = |tag: u32, request: &[u8]| -> Result<Vec<u8>> {
+            if tag != 1 {
+                return Err(HostError::Oracle(format!("unknown tag {tag}")));
+            }
+            let (a, b): (u64, u64) = decode_oracle_request(request)?;
+            encode_oracle_answer(&(a * b))
+        };
+
+        let request = encode_oracle_answer(&(6u64, 7u64)).expect("encode request");
+        let answer = oracle.answer(1, &request).expect("answer");
+        assert_eq!(decode_oracle_request::<u64>(&answer).expect("decode"), 42);
+
+        let err = oracle.answer(2, &request).expect_err("unknown tag");
+        assert_eq!(err.to_string(), "oracle error: unknown tag 2");
+    }
+}
@@ -48,6 +48,23 @@ impl Program {
         verify_manifest_artifact_sha256(&app_bin, "bin.sha256", &manifest.bin.sha256)?;
         verify_manifest_artifact_sha256(&app_elf, "elf.sha256", &manifest.elf.sha256)?;
         verify_manifest_artifact_sha256(&app_text, "text.sha256", &manifest.text.sha256)?;
+        let tables = [
+            (&manifest.panics, "panics.sha256"),
+            (&manifest.regions, "regions.sha256"),
+        ];
+        for (entry, field) in tables {
+            let Some(entry) = entry else {
+                continue;
+            };
+            let path = dist_dir.join(&entry.path);
+            if !path.exists() {
+                return Err(HostError::InvalidManifest(format!(
+                    "missing artifact: {}",
+                    path.display()
+                )));
+            }
+            verify_manifest_artifact_sha256(&path, field, &entry.sha256)?;
+        }
 
         Ok(Self {
             dist_dir,
@@ -80,7 +97,7 @@ impl Program {
 
     /// Create a transpiler runner builder bound to this program.
     pub fn transpiler_runner(&self) -> TranspilerRunnerBuilder {
-        TranspilerRunnerBuilder::new(self.app_bin())
+        TranspilerRunnerBuilder::new(self.app_bin()).maybe_memory_layout(self.manifest.memory)
     }
 
     #[cfg(feature = "gpu-prover")]
@@ -96,7 +113,7 @@ impl Program {
 
     /// Create a CPU prover builder bound to this program.
     pub fn cpu_prover(&self) -> CpuProverBuilder {
-        CpuProverBuilder::new(self.app_bin())
+        CpuProverBuilder::new(self.app_bin()).maybe_memory_layout(self.manifest.memory)
     }
 
     /// Create a development verifier builder bound to this program.
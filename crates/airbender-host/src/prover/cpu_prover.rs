@@ -1,23 +1,34 @@
 use super::{
-    receipt_from_real_proof, resolve_app_bin_path, resolve_text_path, resolve_worker_threads,
-    ProveResult, Prover, DEFAULT_CPU_CYCLE_BOUND, DEFAULT_RAM_BOUND_BYTES,
+    preflight, preflight_runner, receipt_from_real_proof, resolve_app_bin_path, resolve_text_path,
+    resolve_worker_threads, ProveResult, Prover, DEFAULT_CPU_CYCLE_BOUND, DEFAULT_RAM_BOUND_BYTES,
 };
 use crate::error::{HostError, Result};
+use crate::guest_env::GuestEnv;
+use crate::oracle::Oracle;
 use crate::proof::{Proof, RealProof};
-use crate::runner::{Runner, TranspilerRunnerBuilder};
+use crate::runner::check_memory_layout;
+use airbender_core::host::manifest::MemoryLayout;
 use execution_utils::setups;
 use execution_utils::unrolled;
 use riscv_transpiler::abstractions::non_determinism::QuasiUARTSource;
 use riscv_transpiler::common_constants::rom::ROM_BYTE_SIZE;
 use riscv_transpiler::cycle::IMStandardIsaConfigWithUnsignedMulDiv;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Builder for creating a configured cached CPU prover.
+///
+/// Every `prove` call first executes the guest once, with the JIT on x86_64 hosts, to capture
+/// the public journal and the words served to guest reads. Runs that do not succeed are
+/// rejected before proving starts.
 pub struct CpuProverBuilder {
     app_bin_path: PathBuf,
     worker_threads: Option<usize>,
     cycles: Option<usize>,
     ram_bound: Option<usize>,
+    oracle: Option<Arc<dyn Oracle>>,
+    guest_env: GuestEnv,
+    memory_layout: Option<MemoryLayout>,
 }
 
 impl CpuProverBuilder {
@@ -27,6 +38,9 @@ impl CpuProverBuilder {
             worker_threads: None,
             cycles: None,
             ram_bound: None,
+            oracle: None,
+            guest_env: GuestEnv::default(),
+            memory_layout: None,
         }
     }
 
@@ -42,6 +56,8 @@ impl CpuProverBuilder {
         }
     }
 
+    /// Prove exactly `cycles` cycles. Without it, the pre-proving run measures the cycle count,
+    /// bounded by `u32::MAX` cycles.
     pub fn with_cycles(mut self, cycles: usize) -> Self {
         self.cycles = Some(cycles);
         self
@@ -66,12 +82,40 @@ impl CpuProverBuilder {
         }
     }
 
+    /// Answer guest oracle queries with `oracle` during execution.
+    pub fn with_oracle(mut self, oracle: impl Oracle + 'static) -> Self {
+        self.oracle = Some(Arc::new(oracle));
+        self
+    }
+
+    /// Serve `guest_env` to `std` guests reading their args, env vars or stdin.
+    pub fn with_guest_env(mut self, guest_env: GuestEnv) -> Self {
+        self.guest_env = guest_env;
+        self
+    }
+
+    /// Reject guests whose memory layout does not fit within the RAM bound.
+    pub fn with_memory_layout(mut self, memory_layout: MemoryLayout) -> Self {
+        self.memory_layout = Some(memory_layout);
+        self
+    }
+
+    pub fn maybe_memory_layout(self, memory_layout: Option<MemoryLayout>) -> Self {
+        match memory_layout {
+            Some(v) => self.with_memory_layout(v),
+            None => self,
+        }
+    }
+
     pub fn build(self) -> Result<CpuProver> {
         CpuProver::new(
             &self.app_bin_path,
             self.worker_threads,
             self.cycles,
             self.ram_bound,
+            self.oracle,
+            self.guest_env,
+            self.memory_layout,
         )
     }
 }
@@ -85,6 +129,8 @@ pub struct CpuProver {
     cycles: Option<usize>,
     ram_bound: usize,
     worker: execution_utils::prover_examples::prover::worker::Worker,
+    oracle: Option<Arc<dyn Oracle>>,
+    guest_env: GuestEnv,
 }
 
 impl CpuProver {
@@ -93,6 +139,9 @@ impl CpuProver {
         worker_threads: Option<usize>,
         cycles: Option<usize>,
         ram_bound: Option<usize>,
+        oracle: Option<Arc<dyn Oracle>>,
+        guest_env: GuestEnv,
+        memory_layout: Option<MemoryLayout>,
     ) -> Result<Self> {
         if matches!(worker_threads, Some(0)) {
             return Err(HostError::Prover(
@@ -112,6 +161,9 @@ impl CpuProver {
                 ROM_BYTE_SIZE
             )));
         }
+        if let Some(layout) = &memory_layout {
+            check_memory_layout(layout, ram_bound).map_err(HostError::Prover)?;
+        }
 
         let threads = resolve_worker_threads(worker_threads);
         let worker =
@@ -125,36 +177,32 @@ impl CpuProver {
             cycles,
             ram_bound,
             worker,
+            oracle,
+            guest_env,
         })
     }
 }
 
 impl Prover for CpuProver {
     fn prove(&self, input_words: &[u32]) -> Result<ProveResult> {
-        let cycles_bound = match self.cycles {
-            Some(value) => value,
-            None => {
-                let cycle_estimator = TranspilerRunnerBuilder::new(&self.app_bin_path)
-                    .with_cycles(DEFAULT_CPU_CYCLE_BOUND)
-                    .with_text_path(&self.app_text_path)
-                    .build()?;
-                let outcome = cycle_estimator.run(input_words)?;
-                if !outcome.reached_end {
-                    return Err(HostError::Prover(format!(
-                        "automatic cycle estimation did not reach program end after {} cycles; provide explicit cycles to prove a bounded run",
-                        outcome.cycles_executed
-                    )));
-                }
-                outcome.cycles_executed
-            }
-        };
+        // The pre-proving run also measures the cycle bound when none was given.
+        let runner = preflight_runner(
+            &self.app_bin_path,
+            &self.app_text_path,
+            self.cycles.unwrap_or(DEFAULT_CPU_CYCLE_BOUND),
+            self.oracle.clone(),
+            self.guest_env.clone(),
+        )?;
+        let execution = preflight(&runner, input_words)?;
+        let cycles_bound = self.cycles.unwrap_or(execution.cycles_executed);
         if cycles_bound == 0 {
             return Err(HostError::Prover(
                 "cycles bound must be greater than zero".to_string(),
             ));
         }
 
-        let oracle = QuasiUARTSource::new_with_reads(input_words.to_vec());
+        // Replaying the recorded reads serves oracle answers and the preamble in place.
+        let oracle = QuasiUARTSource::new_with_reads(execution.read_words);
         let inner_proof = unrolled::prove_unrolled_for_machine_configuration_into_program_proof::<
             IMStandardIsaConfigWithUnsignedMulDiv,
         >(
@@ -172,6 +220,7 @@ impl Prover for CpuProver {
             proof,
             cycles: cycles_bound as u64,
             receipt,
+            journal: execution.journal,
         })
     }
 }
@@ -1,14 +1,19 @@
 use super::{resolve_app_bin_path, ProveResult, Prover};
 use crate::error::Result;
+use crate::guest_env::GuestEnv;
+use crate::oracle::Oracle;
 use crate::proof::{hash_app_bin, hash_input_words, DevProof, Proof};
 use crate::runner::{Runner, TranspilerRunner, TranspilerRunnerBuilder};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Builder for creating a configured development prover.
 pub struct DevProverBuilder {
     app_bin_path: PathBuf,
     cycles: Option<usize>,
     text_path: Option<PathBuf>,
+    oracle: Option<Arc<dyn Oracle>>,
+    guest_env: GuestEnv,
 }
 
 impl DevProverBuilder {
@@ -17,6 +22,8 @@ impl DevProverBuilder {
             app_bin_path: app_bin_path.as_ref().to_path_buf(),
             cycles: None,
             text_path: None,
+            oracle: None,
+            guest_env: GuestEnv::default(),
         }
     }
 
@@ -44,8 +51,26 @@ impl DevProverBuilder {
         }
     }
 
+    /// Answer guest oracle queries with `oracle` during execution.
+    pub fn with_oracle(mut self, oracle: impl Oracle + 'static) -> Self {
+        self.oracle = Some(Arc::new(oracle));
+        self
+    }
+
+    /// Serve `guest_env` to `std` guests reading their args, env vars or stdin.
+    pub fn with_guest_env(mut self, guest_env: GuestEnv) -> Self {
+        self.guest_env = guest_env;
+        self
+    }
+
     pub fn build(self) -> Result<DevProver> {
-        DevProver::new(&self.app_bin_path, self.cycles, self.text_path.as_deref())
+        DevProver::new(
+            &self.app_bin_path,
+            self.cycles,
+            self.text_path.as_deref(),
+            self.oracle,
+            self.guest_env,
+        )
     }
 }
 
@@ -56,13 +81,21 @@ pub struct DevProver {
 }
 
 impl DevProver {
-    fn new(app_bin_path: &Path, cycles: Option<usize>, text_path: Option<&Path>) -> Result<Self> {
+    fn new(
+        app_bin_path: &Path,
+        cycles: Option<usize>,
+        text_path: Option<&Path>,
+        oracle: Option<Arc<dyn Oracle>>,
+        guest_env: GuestEnv,
+    ) -> Result<Self> {
         let app_bin_path = resolve_app_bin_path(app_bin_path)?;
         let app_bin_hash = hash_app_bin(&app_bin_path)?;
 
         let runner = TranspilerRunnerBuilder::new(&app_bin_path)
             .maybe_cycles(cycles)
             .maybe_text_path(text_path)
+            .maybe_oracle(oracle)
+            .with_guest_env(guest_env)
             .build()?;
 
         Ok(Self {
@@ -89,6 +122,7 @@ impl Prover for DevProver {
             proof,
             cycles,
             receipt,
+            journal: execution.journal,
         })
     }
 }
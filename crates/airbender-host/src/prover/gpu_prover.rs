@@ -1,22 +1,32 @@
 use super::{
-    base_path, receipt_from_real_proof, resolve_app_bin_path, ProveResult, Prover, ProverLevel,
+    base_path, preflight, preflight_runner, receipt_from_real_proof, resolve_app_bin_path,
+    resolve_text_path, ProveResult, Prover, ProverLevel, DEFAULT_CPU_CYCLE_BOUND,
 };
 use crate::error::{HostError, Result};
+use crate::guest_env::GuestEnv;
+use crate::oracle::Oracle;
 use crate::proof::{Proof, RealProof};
+use crate::runner::TranspilerRunner;
 use execution_utils::unrolled_gpu::UnrolledProver;
 use gpu_prover::execution::prover::ExecutionProverConfiguration;
 use riscv_transpiler::abstractions::non_determinism::QuasiUARTSource;
 use std::any::Any;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{mpsc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread::JoinHandle;
 
 /// Builder for creating a configured cached GPU prover.
+///
+/// Every `prove` call first executes the guest once, with the JIT on x86_64 hosts, to capture
+/// the public journal and the words served to guest reads. Runs that do not succeed are
+/// rejected before proving starts.
 pub struct GpuProverBuilder {
     app_bin_path: PathBuf,
     worker_threads: Option<usize>,
     level: ProverLevel,
+    oracle: Option<Arc<dyn Oracle>>,
+    guest_env: GuestEnv,
 }
 
 impl GpuProverBuilder {
@@ -25,6 +35,8 @@ impl GpuProverBuilder {
             app_bin_path: app_bin_path.as_ref().to_path_buf(),
             worker_threads: None,
             level: ProverLevel::RecursionUnified,
+            oracle: None,
+            guest_env: GuestEnv::default(),
         }
     }
 
@@ -45,8 +57,26 @@ impl GpuProverBuilder {
         self
     }
 
+    /// Answer guest oracle queries with `oracle` during execution.
+    pub fn with_oracle(mut self, oracle: impl Oracle + 'static) -> Self {
+        self.oracle = Some(Arc::new(oracle));
+        self
+    }
+
+    /// Serve `guest_env` to `std` guests reading their args, env vars or stdin.
+    pub fn with_guest_env(mut self, guest_env: GuestEnv) -> Self {
+        self.guest_env = guest_env;
+        self
+    }
+
     pub fn build(self) -> Result<GpuProver> {
-        GpuProver::new(&self.app_bin_path, self.worker_threads, self.level)
+        GpuProver::new(
+            &self.app_bin_path,
+            self.worker_threads,
+            self.level,
+            self.oracle,
+            self.guest_env,
+        )
     }
 }
 
@@ -62,6 +92,7 @@ impl GpuProverBuilder {
 ///
 /// After poisioning, you can instantiate a new prover if required.
 pub struct GpuProver {
+    journal_runner: TranspilerRunner,
     command_tx: mpsc::Sender<WorkerCommand>,
     worker_handle: Mutex<Option<JoinHandle<()>>>,
     poisoned: AtomicBool,
@@ -76,7 +107,13 @@ enum WorkerCommand {
 }
 
 impl GpuProver {
-    fn new(app_bin_path: &Path, worker_threads: Option<usize>, level: ProverLevel) -> Result<Self> {
+    fn new(
+        app_bin_path: &Path,
+        worker_threads: Option<usize>,
+        level: ProverLevel,
+        oracle: Option<Arc<dyn Oracle>>,
+        guest_env: GuestEnv,
+    ) -> Result<Self> {
         if matches!(worker_threads, Some(0)) {
             return Err(HostError::Prover(
                 "worker thread count must be greater than zero".to_string(),
@@ -84,9 +121,17 @@ impl GpuProver {
         }
 
         let app_bin_path = resolve_app_bin_path(app_bin_path)?;
+        let journal_runner = preflight_runner(
+            &app_bin_path,
+            &resolve_text_path(&app_bin_path)?,
+            DEFAULT_CPU_CYCLE_BOUND,
+            oracle,
+            guest_env,
+        )?;
         let (command_tx, worker_handle) = spawn_worker(app_bin_path, worker_threads, level)?;
 
         Ok(Self {
+            journal_runner,
             command_tx,
             worker_handle: Mutex::new(Some(worker_handle)),
             poisoned: AtomicBool::new(false),
@@ -136,17 +181,25 @@ impl Prover for GpuProver {
             return Err(Self::poisoned_error());
         }
 
+        // Replaying the reads recorded by the pre-proving run serves oracle answers and the
+        // preamble in place.
+        let execution = preflight(&self.journal_runner, input_words)?;
+
         let (response_tx, response_rx) = mpsc::channel();
         self.command_tx
             .send(WorkerCommand::Prove {
-                input_words: input_words.to_vec(),
+                input_words: execution.read_words,
                 response_tx,
             })
             .map_err(|_| self.handle_worker_failure("submitting a prove request"))?;
 
-        response_rx
+        let result = response_rx
             .recv()
-            .map_err(|_| self.handle_worker_failure("receiving a prove response"))?
+            .map_err(|_| self.handle_worker_failure("receiving a prove response"))??;
+        Ok(ProveResult {
+            journal: execution.journal,
+            ..result
+        })
     }
 }
 
@@ -236,6 +289,7 @@ fn gpu_worker_loop(
                     proof,
                     cycles,
                     receipt,
+                    journal: Vec::new(),
                 });
                 let _ = response_tx.send(result);
             }
@@ -1,6 +1,9 @@
 use crate::error::{HostError, Result};
 use crate::proof::Proof;
 use crate::receipt::Receipt;
+use crate::runner::{
+    ExecutionOutcome, ExecutionResult, Runner, TranspilerRunner, TranspilerRunnerBuilder,
+};
 use execution_utils::unrolled::UnrolledProgramProof;
 use std::path::{Path, PathBuf};
 
@@ -52,6 +55,8 @@ pub struct ProveResult {
     pub proof: Proof,
     pub cycles: u64,
     pub receipt: Receipt,
+    /// Raw public journal bytes written by the guest.
+    pub journal: Vec<u8>,
 }
 
 pub(super) fn resolve_app_bin_path(path: &Path) -> Result<PathBuf> {
@@ -103,6 +108,42 @@ pub(super) fn base_path(app_bin_path: &Path) -> Result<String> {
     }
 }
 
+/// Runner for the guest pass every real prover makes before proving.
+///
+/// The provers do not expose guest writes, so this pass captures the public journal and the
+/// words served to guest reads. It uses the JIT where one is available.
+pub(super) fn preflight_runner(
+    app_bin_path: &Path,
+    app_text_path: &Path,
+    cycles: usize,
+    oracle: Option<std::sync::Arc<dyn crate::oracle::Oracle>>,
+    guest_env: crate::guest_env::GuestEnv,
+) -> Result<TranspilerRunner> {
+    let builder = TranspilerRunnerBuilder::new(app_bin_path)
+        .with_cycles(cycles)
+        .with_text_path(app_text_path)
+        .maybe_oracle(oracle)
+        .with_guest_env(guest_env);
+    let builder = if cfg!(target_arch = "x86_64") {
+        builder.with_jit()
+    } else {
+        builder
+    };
+    builder.build()
+}
+
+/// Run the pre-proving pass and reject runs that did not succeed, which cannot be proven.
+pub(super) fn preflight(runner: &TranspilerRunner, input_words: &[u32]) -> Result<ExecutionResult> {
+    let execution = runner.run(input_words)?;
+    if execution.outcome != ExecutionOutcome::Success {
+        return Err(HostError::Prover(format!(
+            "guest run did not succeed ({}) after {} cycles; refusing to prove it",
+            execution.outcome, execution.cycles_executed
+        )));
+    }
+    Ok(execution)
+}
+
 pub(super) fn resolve_worker_threads(worker_threads: Option<usize>) -> usize {
     worker_threads
         .or_else(|| {
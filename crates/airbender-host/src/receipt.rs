@@ -1,3 +1,5 @@
+use airbender_core::guest::FromCommit;
+
 /// Execution output captured from transpiler or prover results.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Receipt {
@@ -18,4 +20,9 @@ impl Receipt {
             output_extended,
         }
     }
+
+    /// Decode the public output registers as `T`.
+    pub fn output_as<T: FromCommit>(&self) -> T {
+        T::from_commit_words(&self.output)
+    }
 }
@@ -0,0 +1,530 @@
+//! GDB Remote Serial Protocol server for stepping through transpiler runs.
+//!
+//! The server accepts one debugger connection and drives the guest through a [`DebugTarget`]
+//! one instruction at a time. It supports software and hardware breakpoints (both are kept
+//! host-side), single-stepping, continuing with `Ctrl-C` interrupts, and register and memory
+//! reads; writes are refused. The target description names the machine `riscv:rv32`, and
+//! `qXfer:exec-file` points the debugger at `app.elf` so symbols load without a `file` command.
+
+use crate::disasm::REGISTER_NAMES;
+use crate::error::{HostError, Result};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+
+/// Instructions executed between checks for a debugger interrupt.
+const INTERRUPT_POLL_STEPS: usize = 4096;
+
+/// Largest memory read served in one packet.
+const MAX_READ_BYTES: usize = 2048;
+
+const SIGINT: u8 = 2;
+const SIGTRAP: u8 = 5;
+const SIGXCPU: u8 = 24;
+
+/// Byte a debugger sends to interrupt a running target.
+const INTERRUPT: u8 = 0x03;
+
+/// Guest execution as seen by the debugger.
+pub(crate) trait DebugTarget {
+    /// General-purpose registers `x0..x31`.
+    fn registers(&self) -> [u32; 32];
+    fn pc(&self) -> u32;
+    /// One byte of guest memory, or `None` outside of it.
+    fn read_byte(&self, address: u32) -> Option<u8>;
+    /// Executes one instruction.
+    fn step(&mut self) -> StepOutcome;
+}
+
+/// Result of executing one guest instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StepOutcome {
+    Running,
+    /// The guest reached its exit loop.
+    Exited,
+    /// The cycle budget ran out.
+    CycleLimit,
+}
+
+/// How a debugger session ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SessionEnd {
+    /// The guest ran to the end under the debugger.
+    Finished(StepOutcome),
+    /// The debugger detached and leaves the rest of the run to the host.
+    Detached,
+    /// The debugger killed the guest.
+    Killed,
+    /// The connection closed without a detach or kill.
+    Disconnected,
+}
+
+/// Serve one debugger on `addr` until it detaches, kills the guest, or disconnects.
+pub(crate) fn serve(
+    addr: SocketAddr,
+    target: &mut impl DebugTarget,
+    exec_file: &Path,
+) -> Result<SessionEnd> {
+    let listener = TcpListener::bind(addr).map_err(|err| {
+        HostError::Transpiler(format!("failed to bind GDB server to {addr}: {err}"))
+    })?;
+    tracing::info!(
+        "waiting for GDB on {}; symbols are in {}",
+        listener.local_addr()?,
+        exec_file.display()
+    );
+    let (stream, peer) = listener.accept()?;
+    tracing::info!("GDB connected from {peer}");
+
+    let mut connection = Connection::new(stream)?;
+    let mut session = Session::new(target, exec_file);
+    let mut end = SessionEnd::Disconnected;
+    while let Some(packet) = connection.read_packet()? {
+        match session.handle(&packet) {
+            Action::Reply(reply) => connection.write_packet(&reply)?,
+            Action::Resume { step } => {
+                let reply = session.resume(step, || connection.interrupted());
+                connection.write_packet(&reply)?;
+            }
+            Action::Close(close) => {
+                if close == SessionEnd::Detached {
+                    connection.write_packet("OK")?;
+                }
+                end = close;
+                break;
+            }
+        }
+    }
+    tracing::info!("GDB session ended");
+    Ok(session.finished.map_or(end, SessionEnd::Finished))
+}
+
+/// What the server does with a packet.
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Reply(String),
+    Resume { step: bool },
+    Close(SessionEnd),
+}
+
+/// Debugger state for one connection.
+struct Session<'a, T> {
+    target: &'a mut T,
+    exec_file: String,
+    breakpoints: BTreeSet<u32>,
+    finished: Option<StepOutcome>,
+}
+
+impl<'a, T: DebugTarget> Session<'a, T> {
+    fn new(target: &'a mut T, exec_file: &Path) -> Self {
+        Self {
+            target,
+            exec_file: exec_file.display().to_string(),
+            breakpoints: BTreeSet::new(),
+            finished: None,
+        }
+    }
+
+    fn handle(&mut self, packet: &str) -> Action {
+        let reply = match packet {
+            "?" => self.stop_reply(SIGTRAP),
+            "g" => self.read_registers(),
+            "c" => return Action::Resume { step: false },
+            "s" => return Action::Resume { step: true },
+            "k" => return Action::Close(SessionEnd::Killed),
+            "D" => return Action::Close(SessionEnd::Detached),
+            "qAttached" => "1".to_string(),
+            "qC" => "QC1".to_string(),
+            "qfThreadInfo" => "m1".to_string(),
+            "qsThreadInfo" => "l".to_string(),
+            "vCont?" => "vCont;c;C;s;S".to_string(),
+            _ if packet.starts_with("qSupported") => {
+                "PacketSize=1000;qXfer:features:read+;qXfer:exec-file:read+;swbreak+;hwbreak+"
+                    .to_string()
+            }
+            _ if packet.starts_with("vCont;") => {
+                return match packet.as_bytes().get(6) {
+                    Some(b'c' | b'C') => Action::Resume { step: false },
+                    Some(b's' | b'S') => Action::Resume { step: true },
+                    _ => Action::Reply("E01".to_string()),
+                };
+            }
+            _ if packet.starts_with('H') || packet.starts_with('T') => "OK".to_string(),
+            _ => self
+                .handle_with_arguments(packet)
+                .unwrap_or_else(|| "E01".to_string()),
+        };
+        Action::Reply(reply)
+    }
+
+    /// Packets that carry arguments; `None` when they are malformed.
+    fn handle_with_arguments(&mut self, packet: &str) -> Option<String> {
+        if let Some(request) = packet.strip_prefix("qXfer:features:read:target.xml:") {
+            return xfer(target_xml().as_bytes(), request);
+        }
+        if let Some(request) = packet.strip_prefix("qXfer:exec-file:read:") {
+            let (_annex, request) = request.split_once(':')?;
+            return xfer(self.exec_file.as_bytes(), request);
+        }
+        if let Some(register) = packet.strip_prefix('p') {
+            let register = usize::from_str_radix(register, 16).ok()?;
+            let value = match register {
+                0..32 => self.target.registers()[register],
+                32 => self.target.pc(),
+                _ => return None,
+            };
+            return Some(hex_word(value));
+        }
+        if let Some(request) = packet.strip_prefix('m') {
+            let (address, len) = parse_pair(request)?;
+            return Some(self.read_memory(address, len as usize));
+        }
+        if let Some(request) = packet.strip_prefix("Z0,").or(packet.strip_prefix("Z1,")) {
+            let (address, _kind) = parse_pair(request)?;
+            self.breakpoints.insert(address);
+            return Some("OK".to_string());
+        }
+        if let Some(request) = packet.strip_prefix("z0,").or(packet.strip_prefix("z1,")) {
+            let (address, _kind) = parse_pair(request)?;
+            self.breakpoints.remove(&address);
+            return Some("OK".to_string());
+        }
+        // Unsupported packets get an empty reply.
+        Some(String::new())
+    }
+
+    /// Run until the next stop; `interrupted` is polled while continuing.
+    fn resume(&mut self, step: bool, mut interrupted: impl FnMut() -> bool) -> String {
+        if self.finished.is_some() {
+            return self.stop_reply(SIGTRAP);
+        }
+        let mut executed = 0usize;
+        loop {
+            let outcome = self.target.step();
+            executed += 1;
+            if outcome != StepOutcome::Running {
+                self.finished = Some(outcome);
+                return self.stop_reply(SIGTRAP);
+            }
+            if step {
+                return self.stop_reply(SIGTRAP);
+            }
+            if self.breakpoints.contains(&self.target.pc()) {
+                return format!("T{SIGTRAP:02x}swbreak:;");
+            }
+            if executed.is_multiple_of(INTERRUPT_POLL_STEPS) && interrupted() {
+                return self.stop_reply(SIGINT);
+            }
+        }
+    }
+
+    fn stop_reply(&self, signal: u8) -> String {
+        match self.finished {
+            None | Some(StepOutcome::Running) => format!("S{signal:02x}"),
+            Some(StepOutcome::Exited) => "W00".to_string(),
+            Some(StepOutcome::CycleLimit) => format!("X{SIGXCPU:02x}"),
+        }
+    }
+
+    fn read_registers(&self) -> String {
+        let mut reply = String::new();
+        for value in self
+            .target
+            .registers()
+            .into_iter()
+            .chain([self.target.pc()])
+        {
+            reply.push_str(&hex_word(value));
+        }
+        reply
+    }
+
+    fn read_memory(&self, address: u32, len: usize) -> String {
+        let mut reply = String::new();
+        for offset in 0..len.min(MAX_READ_BYTES) as u32 {
+            let Some(byte) = address
+                .checked_add(offset)
+                .and_then(|address| self.target.read_byte(address))
+            else {
+                break;
+            };
+            let _ = write!(reply, "{byte:02x}");
+        }
+        if reply.is_empty() && len > 0 {
+            // EFAULT
+            return "E0e".to_string();
+        }
+        reply
+    }
+}
+
+/// Target description announcing a 32-bit RISC-V CPU.
+fn target_xml() -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\"?><!DOCTYPE target SYSTEM \"gdb-target.dtd\">\
+         <target version=\"1.0\"><architecture>riscv:rv32</architecture>\
+         <feature name=\"org.gnu.gdb.riscv.cpu\">",
+    );
+    for (index, name) in REGISTER_NAMES.iter().enumerate() {
+        let kind = match *name {
+            "sp" | "fp" => "data_ptr",
+            "ra" => "code_ptr",
+            _ => "int",
+        };
+        let _ = write!(
+            xml,
+            "<reg name=\"{name}\" bitsize=\"32\" type=\"{kind}\" regnum=\"{index}\"/>"
+        );
+    }
+    xml.push_str("<reg name=\"pc\" bitsize=\"32\" type=\"code_ptr\" regnum=\"32\"/>");
+    xml.push_str("</feature></target>");
+    xml
+}
+
+/// Serves the `offset,length` window of `data` for a `qXfer` read.
+fn xfer(data: &[u8], request: &str) -> Option<String> {
+    let (offset, len) = parse_pair(request)?;
+    let start = (offset as usize).min(data.len());
+    let end = start.saturating_add(len as usize).min(data.len());
+    let marker = if end < data.len() { 'm' } else { 'l' };
+    let chunk = std::str::from_utf8(&data[start..end]).ok()?;
+    Some(format!("{marker}{chunk}"))
+}
+
+/// Parses `aaaa,bbbb` hex pairs.
+fn parse_pair(request: &str) -> Option<(u32, u32)> {
+    let (first, second) = request.split_once(',')?;
+    Some((
+        u32::from_str_radix(first, 16).ok()?,
+        u32::from_str_radix(second, 16).ok()?,
+    ))
+}
+
+/// Register values go over the wire in target (little-endian) byte order.
+fn hex_word(value: u32) -> String {
+    value
+        .to_le_bytes()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// Escapes the bytes the protocol reserves for framing.
+fn escape(data: &str) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len());
+    for byte in data.bytes() {
+        if matches!(byte, b'$' | b'#' | b'}' | b'*') {
+            escaped.extend([b'}', byte ^ 0x20]);
+        } else {
+            escaped.push(byte);
+        }
+    }
+    escaped
+}
+
+/// Packet framing over the debugger's TCP connection.
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            writer: stream.try_clone()?,
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Next packet payload, acknowledging it; `None` once the debugger disconnects.
+    fn read_packet(&mut self) -> Result<Option<String>> {
+        loop {
+            // Skip acknowledgements and stray interrupts until a packet starts.
+            let mut skipped = Vec::new();
+            if self.reader.read_until(b'$', &mut skipped)? == 0 || skipped.last() != Some(&b'$') {
+                return Ok(None);
+            }
+            let mut payload = Vec::new();
+            self.reader.read_until(b'#', &mut payload)?;
+            if payload.pop() != Some(b'#') {
+                return Ok(None);
+            }
+            let mut sum = [0u8; 2];
+            std::io::Read::read_exact(&mut self.reader, &mut sum)?;
+            let expected = std::str::from_utf8(&sum)
+                .ok()
+                .and_then(|sum| u8::from_str_radix(sum, 16).ok());
+            if expected != Some(checksum(&payload)) {
+                self.writer.write_all(b"-")?;
+                continue;
+            }
+            self.writer.write_all(b"+")?;
+            return Ok(Some(String::from_utf8_lossy(&payload).into_owned()));
+        }
+    }
+
+    fn write_packet(&mut self, reply: &str) -> Result<()> {
+        let payload = escape(reply);
+        let mut packet = Vec::with_capacity(payload.len() + 4);
+        packet.push(b'$');
+        packet.extend_from_slice(&payload);
+        packet.extend_from_slice(format!("#{:02x}", checksum(&payload)).as_bytes());
+        self.writer.write_all(&packet)?;
+        Ok(())
+    }
+
+    /// Whether the debugger sent an interrupt, a packet, or went away, without blocking.
+    fn interrupted(&mut self) -> bool {
+        if self.reader.get_ref().set_nonblocking(true).is_err() {
+            return false;
+        }
+        let interrupted = match self.reader.fill_buf() {
+            Ok([]) => true,
+            Ok(pending) => {
+                let (skipped, interrupted) = scan_pending(pending);
+                self.reader.consume(skipped);
+                interrupted
+            }
+            Err(_) => false,
+        };
+        let _ = self.reader.get_ref().set_nonblocking(false);
+        interrupted
+    }
+}
+
+/// Splits bytes received while the guest runs into the leading bytes `read_packet` would skip
+/// anyway (acknowledgements and interrupts) and whether the run should stop for them.
+///
+/// A packet start also stops the run so the packet gets answered; it is left in the buffer.
+fn scan_pending(pending: &[u8]) -> (usize, bool) {
+    let skipped = pending
+        .iter()
+        .position(|byte| *byte == b'$')
+        .unwrap_or(pending.len());
+    let interrupted = skipped < pending.len() || pending[..skipped].contains(&INTERRUPT);
+    (skipped, interrupted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Straight-line program: every step advances `pc` by 4 until `exit_pc`.
+    struct FakeTarget {
+        registers: [u32; 32],
+        pc: u32,
+        exit_pc: u32,
+        memory: Vec<u8>,
+    }
+
+    impl DebugTarget for FakeTarget {
+        fn registers(&self) -> [u32; 32] {
+            self.registers
+        }
+
+        fn pc(&self) -> u32 {
+            self.pc
+        }
+
+        fn read_byte(&self, address: u32) -> Option<u8> {
+            self.memory.get(address as usize).copied()
+        }
+
+        fn step(&mut self) -> StepOutcome {
+            if self.pc == self.exit_pc {
+                return StepOutcome::Exited;
+            }
+            self.pc += 4;
+            self.registers[10] += 1;
+            StepOutcome::Running
+        }
+    }
+
+    fn target() -> FakeTarget {
+        FakeTarget {
+            registers: std::array::from_fn(|index| index as u32),
+            pc: 0,
+            exit_pc: 0x40,
+            memory: vec![0x13, 0x05, 0x10, 0x00],
+        }
+    }
+
+    fn reply(session: &mut Session<'_, FakeTarget>, packet: &str) -> String {
+        match session.handle(packet) {
+            Action::Reply(reply) => reply,
+            Action::Resume { step } => session.resume(step, || false),
+            other => panic!("unexpected action for {packet}: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reads_registers_and_memory() {
+        let mut target = target();
+        let mut session = Session::new(&mut target, Path::new("dist/app.elf"));
+
+        let registers = reply(&mut session, "g");
+        assert_eq!(registers.len(), 33 * 8);
+        assert_eq!(&registers[8 * 10..8 * 11], "0a000000");
+        assert_eq!(reply(&mut session, "p20"), "00000000");
+        assert_eq!(reply(&mut session, "p21"), "E01");
+        assert_eq!(reply(&mut session, "m1,8"), "051000");
+        assert_eq!(reply(&mut session, "m100,4"), "E0e");
+        assert_eq!(
+            reply(&mut session, "qXfer:exec-file:read::0,fff"),
+            "ldist/app.elf"
+        );
+        assert!(reply(&mut session, "qXfer:features:read:target.xml:0,fff").contains("riscv:rv32"));
+        assert_eq!(reply(&mut session, "qUnknown"), "");
+    }
+
+    #[test]
+    fn steps_stops_at_breakpoints_and_reports_exit() {
+        let mut target = target();
+        let mut session = Session::new(&mut target, Path::new("app.elf"));
+
+        assert_eq!(reply(&mut session, "s"), "S05");
+        assert_eq!(reply(&mut session, "p20"), "04000000");
+        assert_eq!(reply(&mut session, "Z0,10,4"), "OK");
+        assert_eq!(reply(&mut session, "vCont;c"), "T05swbreak:;");
+        assert_eq!(reply(&mut session, "p20"), "10000000");
+        assert_eq!(reply(&mut session, "z0,10,4"), "OK");
+        assert_eq!(reply(&mut session, "c"), "W00");
+        assert_eq!(reply(&mut session, "?"), "W00");
+        assert_eq!(session.finished, Some(StepOutcome::Exited));
+    }
+
+    #[test]
+    fn tells_kill_from_detach() {
+        let mut target = target();
+        let mut session = Session::new(&mut target, Path::new("app.elf"));
+
+        assert_eq!(session.handle("k"), Action::Close(SessionEnd::Killed));
+        assert_eq!(session.handle("D"), Action::Close(SessionEnd::Detached));
+    }
+
+    #[test]
+    fn skips_stray_bytes_while_running() {
+        assert_eq!(scan_pending(b"+"), (1, false));
+        assert_eq!(scan_pending(b"++\x03+"), (4, true));
+        assert_eq!(scan_pending(b"+$qC#b4"), (1, true));
+        assert_eq!(scan_pending(b"-"), (1, false));
+    }
+
+    #[test]
+    fn frames_and_escapes_packets() {
+        assert_eq!(checksum(b"OK"), 0x9a);
+        assert_eq!(escape("a$b}"), b"a}\x04b}]");
+        assert_eq!(xfer(b"abcdef", "2,2").as_deref(), Some("mcd"));
+        assert_eq!(xfer(b"abcdef", "4,10").as_deref(), Some("lef"));
+        assert_eq!(xfer(b"abcdef", "9,10").as_deref(), Some("l"));
+    }
+}
@@ -0,0 +1,773 @@
+//! Non-determinism source that serves guest input words and decodes guest writes.
+
+use crate::error::{HostError, Result};
+use crate::oracle::Oracle;
+use airbender_core::cycle::{RegionEvent, CYCLE_REGION_MARKER};
+use airbender_core::exit::{
+    GUEST_ABORT_MARKER, GUEST_ERROR_MARKER, GUEST_PANIC_LOCATION_MARKER, GUEST_PANIC_MARKER,
+    GUEST_STACK_OVERFLOW_MARKER,
+};
+use airbender_core::host::panics::PanicTable;
+use airbender_core::journal::{encode_entry, JOURNAL_MARKER};
+use airbender_core::log::{Level, GUEST_LOG_MARKER};
+use airbender_core::memory::{MemoryStats, MEMORY_STATS_MARKER};
+use airbender_core::oracle::ORACLE_QUERY_MARKER;
+use airbender_core::preamble::PREAMBLE_REQUEST_MARKER;
+use airbender_core::wire::frame_words_from_bytes;
+use riscv_transpiler::abstractions::memory::MemorySource;
+use riscv_transpiler::abstractions::non_determinism::{NonDeterminismCSRSource, QuasiUARTSource};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+const UART_HELLO_MARKER: u32 = u32::MAX;
+const WORD_BYTES: usize = 4;
+
+/// CSR source used by host runners.
+///
+/// Reads are served from pending answers first, then from the input words, and are recorded
+/// for replay. Writes are split into UART frames, which are decoded and forwarded unchanged to
+/// the wrapped UART source, log records, which are emitted to `tracing` with the `guest`
+/// target, journal frames, memory usage records and region events, which are collected, and oracle queries
+/// and preamble requests, which are answered.
+pub(crate) struct GuestIo {
+    uart: QuasiUARTSource,
+    /// Input words supplied to the run, kept to rebuild the UART source on resume.
+    input_words: Vec<u32>,
+    decoder: WriteDecoder,
+    oracle: Option<Arc<dyn Oracle>>,
+    preamble: Arc<[u32]>,
+    answers: VecDeque<u32>,
+    oracle_error: Option<String>,
+    input_words_read: usize,
+    read_words: Vec<u32>,
+}
+
+/// Serializable state of a [`GuestIo`] between guest write frames.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct GuestIoSnapshot {
+    pub(crate) input_words: Vec<u32>,
+    input_words_read: usize,
+    answers: Vec<u32>,
+    read_words: Vec<u32>,
+    oracle_error: Option<String>,
+    error_pending: bool,
+    journal: Vec<u8>,
+    stdout: Vec<String>,
+    error_message: Option<String>,
+    panic_message: Option<String>,
+    panic_location: Option<u32>,
+    aborted: bool,
+    stack_overflow: bool,
+    memory_stats: Option<[u32; MemoryStats::WORDS]>,
+    region_events: Vec<[u32; RegionEvent::WORDS]>,
+}
+
+/// Data decoded from guest reads and writes during one run.
+#[derive(Debug, Default)]
+pub(crate) struct GuestOutput {
+    pub(crate) journal: Vec<u8>,
+    /// Every UART frame written by the guest, in order.
+    pub(crate) stdout: Vec<String>,
+    pub(crate) error_message: Option<String>,
+    /// Concatenated UART frames written after the runtime announced a panic.
+    pub(crate) panic_message: Option<String>,
+    /// Location ID reported by a compact-panic guest instead of a message.
+    pub(crate) panic_location: Option<u32>,
+    /// Whether the runtime announced an exit through the error path.
+    pub(crate) aborted: bool,
+    /// Whether the runtime found the stack guard region overwritten.
+    pub(crate) stack_overflow: bool,
+    pub(crate) input_words_read: usize,
+    /// Every word served to guest reads, including oracle answers and the preamble.
+    pub(crate) read_words: Vec<u32>,
+    /// First oracle failure; the guest received an empty answer for it.
+    pub(crate) oracle_error: Option<String>,
+    /// Usage record written at exit by guests built with `memory-stats`.
+    pub(crate) memory_stats: Option<MemoryStats>,
+    /// Named cycle-marker region boundaries, in the order the guest wrote them.
+    pub(crate) region_events: Vec<RegionEvent>,
+}
+
+impl GuestIo {
+    pub(crate) fn new(
+        input_words: &[u32],
+        oracle: Option<Arc<dyn Oracle>>,
+        preamble: Arc<[u32]>,
+    ) -> Self {
+        Self {
+            uart: QuasiUARTSource::new_with_reads(input_words.to_vec()),
+            input_words: input_words.to_vec(),
+            decoder: WriteDecoder::default(),
+            oracle,
+            preamble,
+            answers: VecDeque::new(),
+            oracle_error: None,
+            input_words_read: 0,
+            read_words: Vec::new(),
+        }
+    }
+
+    /// Rebuild the source captured in `snapshot`, serving the input words it has not read yet.
+    pub(crate) fn resume(
+        snapshot: GuestIoSnapshot,
+        oracle: Option<Arc<dyn Oracle>>,
+        preamble: Arc<[u32]>,
+    ) -> Self {
+        let unread = snapshot.input_words[snapshot.input_words_read..].to_vec();
+        let output = GuestOutput {
+            journal: snapshot.journal,
+            stdout: snapshot.stdout,
+            error_message: snapshot.error_message,
+            panic_message: snapshot.panic_message,
+            panic_location: snapshot.panic_location,
+            aborted: snapshot.aborted,
+            stack_overflow: snapshot.stack_overflow,
+            memory_stats: snapshot.memory_stats.map(MemoryStats::from_words),
+            region_events: snapshot
+                .region_events
+                .into_iter()
+                .filter_map(RegionEvent::from_words)
+                .collect(),
+            ..GuestOutput::default()
+        };
+        Self {
+            uart: QuasiUARTSource::new_with_reads(unread),
+            input_words: snapshot.input_words,
+            decoder: WriteDecoder {
+                error_pending: snapshot.error_pending,
+                output,
+                ..WriteDecoder::default()
+            },
+            oracle,
+            preamble,
+            answers: snapshot.answers.into(),
+            oracle_error: snapshot.oracle_error,
+            input_words_read: snapshot.input_words_read,
+            read_words: snapshot.read_words,
+        }
+    }
+
+    /// Everything needed to resume this source, or `None` while a guest write is mid-frame.
+    pub(crate) fn snapshot(&self) -> Option<GuestIoSnapshot> {
+        let decoder = &self.decoder;
+        let idle = matches!(decoder.state, WriteState::Idle)
+            && decoder.log_pending.is_none()
+            && decoder.pending_query.is_none()
+            && !decoder.preamble_requested;
+        if !idle {
+            return None;
+        }
+        let output = &decoder.output;
+        Some(GuestIoSnapshot {
+            input_words: self.input_words.clone(),
+            input_words_read: self.input_words_read,
+            answers: self.answers.iter().copied().collect(),
+            read_words: self.read_words.clone(),
+            oracle_error: self.oracle_error.clone(),
+            error_pending: decoder.error_pending,
+            journal: output.journal.clone(),
+            stdout: output.stdout.clone(),
+            error_message: output.error_message.clone(),
+            panic_message: output.panic_message.clone(),
+            panic_location: output.panic_location,
+            aborted: output.aborted,
+            stack_overflow: output.stack_overflow,
+            memory_stats: output.memory_stats.as_ref().map(MemoryStats::to_words),
+            region_events: output
+                .region_events
+                .iter()
+                .map(RegionEvent::to_words)
+                .collect(),
+        })
+    }
+
+    /// Data decoded from guest reads and writes so far.
+    pub(crate) fn into_output(self) -> GuestOutput {
+        GuestOutput {
+            input_words_read: self.input_words_read,
+            read_words: self.read_words,
+            oracle_error: self.oracle_error,
+            ..self.decoder.output
+        }
+    }
+
+    fn answer_query(&mut self, tag: u32, request: &[u8]) {
+        match answer_words(self.oracle.as_deref(), tag, request) {
+            Ok(words) => self.answers.extend(words),
+            Err(err) => {
+                self.oracle_error.get_or_insert_with(|| err.to_string());
+                // An empty frame lets the guest fail on decoding instead of stalling on input.
+                self.answers.push_back(0);
+            }
+        }
+    }
+}
+
+impl GuestOutput {
+    /// Turn a compact panic site ID into a panic message using `panic_table`.
+    pub(crate) fn resolve_panic_location(&mut self, panic_table: Option<&PanicTable>) {
+        let Some(id) = self.panic_location else {
+            return;
+        };
+        let message = match panic_table.and_then(|table| table.resolve(id)) {
+            Some(location) => format!("panicked at {location}"),
+            None => format!("panicked at unknown location {id:#010x}"),
+        };
+        self.panic_message = Some(message);
+    }
+}
+
+impl<M: MemorySource> NonDeterminismCSRSource<M> for GuestIo {
+    fn read(&mut self) -> u32 {
+        let word = match self.answers.pop_front() {
+            Some(word) => word,
+            None => {
+                self.input_words_read += 1;
+                NonDeterminismCSRSource::<M>::read(&mut self.uart)
+            }
+        };
+        self.read_words.push(word);
+        word
+    }
+
+    fn write_with_memory_access(&mut self, memory: &M, value: u32) {
+        if self.decoder.push(value) == WriteRoute::Uart {
+            self.uart.write_with_memory_access(memory, value);
+        }
+        if let Some((tag, request)) = self.decoder.pending_query.take() {
+            self.answer_query(tag, &request);
+        }
+        if std::mem::take(&mut self.decoder.preamble_requested) {
+            self.answers.extend(self.preamble.iter().copied());
+        }
+    }
+}
+
+/// Answer one oracle query as wire-framed words.
+fn answer_words(oracle: Option<&dyn Oracle>, tag: u32, request: &[u8]) -> Result<Vec<u32>> {
+    let oracle = oracle.ok_or_else(|| {
+        HostError::Oracle(format!(
+            "guest sent a query with tag {tag}, but no oracle is registered"
+        ))
+    })?;
+    let answer = oracle.answer(tag, request)?;
+    Ok(frame_words_from_bytes(&answer)?)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum WriteRoute {
+    Uart,
+    Consumed,
+}
+
+#[derive(Debug, Default)]
+enum WriteState {
+    #[default]
+    Idle,
+    UartWordCount,
+    UartByteLength {
+        words: usize,
+    },
+    UartData {
+        len: usize,
+        words: usize,
+        bytes: Vec<u8>,
+    },
+    LogLevel,
+    PanicLocation,
+    MemoryStats {
+        words: Vec<u32>,
+    },
+    CycleRegion {
+        words: Vec<u32>,
+    },
+    OracleTag,
+    WireLength {
+        frame: WireFrame,
+    },
+    WireData {
+        frame: WireFrame,
+        len: usize,
+        payload: Vec<u8>,
+    },
+}
+
+/// Payload kinds sent with the host-to-guest wire framing.
+#[derive(Debug, Clone, Copy)]
+enum WireFrame {
+    Journal,
+    OracleQuery { tag: u32 },
+}
+
+/// Incremental decoder for words written by the guest.
+#[derive(Debug, Default)]
+struct WriteDecoder {
+    state: WriteState,
+    error_pending: bool,
+    /// Level of the log record whose message frame comes next.
+    log_pending: Option<Level>,
+    /// Fully decoded oracle query waiting for an answer.
+    pending_query: Option<(u32, Vec<u8>)>,
+    /// Whether the guest asked for the preamble since the last write.
+    preamble_requested: bool,
+    output: GuestOutput,
+}
+
+impl WriteDecoder {
+    fn push(&mut self, word: u32) -> WriteRoute {
+        match std::mem::take(&mut self.state) {
+            WriteState::Idle => match word {
+                UART_HELLO_MARKER => {
+                    self.state = WriteState::UartWordCount;
+                    self.frame_route()
+                }
+                JOURNAL_MARKER => {
+                    self.state = WriteState::WireLength {
+                        frame: WireFrame::Journal,
+                    };
+                    WriteRoute::Consumed
+                }
+                ORACLE_QUERY_MARKER => {
+                    self.state = WriteState::OracleTag;
+                    WriteRoute::Consumed
+                }
+                PREAMBLE_REQUEST_MARKER => {
+                    self.preamble_requested = true;
+                    WriteRoute::Consumed
+                }
+                GUEST_ERROR_MARKER => {
+                    self.error_pending = true;
+                    WriteRoute::Consumed
+                }
+                GUEST_PANIC_MARKER => {
+                    self.output.panic_message = Some(String::new());
+                    WriteRoute::Consumed
+                }
+                GUEST_PANIC_LOCATION_MARKER => {
+                    self.state = WriteState::PanicLocation;
+                    WriteRoute::Consumed
+                }
+                GUEST_ABORT_MARKER => {
+                    self.output.aborted = true;
+                    WriteRoute::Consumed
+                }
+                GUEST_STACK_OVERFLOW_MARKER => {
+                    self.output.stack_overflow = true;
+                    WriteRoute::Consumed
+                }
+                GUEST_LOG_MARKER => {
+                    self.state = WriteState::LogLevel;
+                    WriteRoute::Consumed
+                }
+                MEMORY_STATS_MARKER => {
+                    self.state = WriteState::MemoryStats { words: Vec::new() };
+                    WriteRoute::Consumed
+                }
+                CYCLE_REGION_MARKER => {
+                    self.state = WriteState::CycleRegion { words: Vec::new() };
+                    WriteRoute::Consumed
+                }
+                _ => WriteRoute::Uart,
+            },
+            // The word count includes the byte length word.
+            WriteState::UartWordCount => {
+                let route = self.frame_route();
+                if word != 0 {
+                    self.state = WriteState::UartByteLength {
+                        words: word as usize - 1,
+                    };
+                }
+                route
+            }
+            WriteState::UartByteLength { words } => {
+                let route = self.frame_route();
+                let len = word as usize;
+                self.uart_data(len, words, Vec::with_capacity(len));
+                route
+            }
+            WriteState::UartData {
+                len,
+                words,
+                mut bytes,
+            } => {
+                let route = self.frame_route();
+                let take = len.saturating_sub(bytes.len()).min(WORD_BYTES);
+                bytes.extend_from_slice(&word.to_le_bytes()[..take]);
+                self.uart_data(len, words - 1, bytes);
+                route
+            }
+            WriteState::LogLevel => {
+                // Unknown levels still carry a message, so keep it visible.
+                self.log_pending = Some(Level::from_word(word).unwrap_or(Level::Info));
+                WriteRoute::Consumed
+            }
+            WriteState::PanicLocation => {
+                self.output.panic_location = Some(word);
+                WriteRoute::Consumed
+            }
+            WriteState::MemoryStats { mut words } => {
+                words.push(word);
+                match <[u32; MemoryStats::WORDS]>::try_from(words) {
+                    Ok(words) => self.output.memory_stats = Some(MemoryStats::from_words(words)),
+                    Err(words) => self.state = WriteState::MemoryStats { words },
+                }
+                WriteRoute::Consumed
+            }
+            WriteState::CycleRegion { mut words } => {
+                words.push(word);
+                match <[u32; RegionEvent::WORDS]>::try_from(words) {
+                    // Malformed events are dropped; they only affect the region report.
+                    Ok(words) => self
+                        .output
+                        .region_events
+                        .extend(RegionEvent::from_words(words)),
+                    Err(words) => self.state = WriteState::CycleRegion { words },
+                }
+                WriteRoute::Consumed
+            }
+            WriteState::OracleTag => {
+                self.state = WriteState::WireLength {
+                    frame: WireFrame::OracleQuery { tag: word },
+                };
+                WriteRoute::Consumed
+            }
+            WriteState::WireLength { frame } => {
+                self.wire_data(frame, word as usize, Vec::with_capacity(word as usize));
+                WriteRoute::Consumed
+            }
+            WriteState::WireData {
+                frame,
+                len,
+                mut payload,
+            } => {
+                let take = (len - payload.len()).min(WORD_BYTES);
+                payload.extend_from_slice(&word.to_be_bytes()[..take]);
+                self.wire_data(frame, len, payload);
+                WriteRoute::Consumed
+            }
+        }
+    }
+
+    fn uart_data(&mut self, len: usize, words: usize, bytes: Vec<u8>) {
+        if words > 0 {
+            self.state = WriteState::UartData { len, words, bytes };
+            return;
+        }
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        if let Some(level) = self.log_pending.take() {
+            emit_log(level, &text);
+            return;
+        }
+        if std::mem::take(&mut self.error_pending) {
+            self.output.error_message = Some(text.clone());
+        } else if let Some(message) = &mut self.output.panic_message {
+            message.push_str(&text);
+        }
+        self.output.stdout.push(text);
+    }
+
+    /// Log message frames are consumed here rather than printed by the UART source.
+    fn frame_route(&self) -> WriteRoute {
+        if self.log_pending.is_some() {
+            WriteRoute::Consumed
+        } else {
+            WriteRoute::Uart
+        }
+    }
+
+    fn wire_data(&mut self, frame: WireFrame, len: usize, payload: Vec<u8>) {
+        if payload.len() < len {
+            self.state = WriteState::WireData {
+                frame,
+                len,
+                payload,
+            };
+            return;
+        }
+        match frame {
+            WireFrame::Journal => self.output.journal.extend(encode_entry(&payload)),
+            WireFrame::OracleQuery { tag } => self.pending_query = Some((tag, payload)),
+        }
+    }
+}
+
+fn emit_log(level: Level, message: &str) {
+    match level {
+        Level::Error => tracing::error!(target: "guest", "{message}"),
+        Level::Warn => tracing::warn!(target: "guest", "{message}"),
+        Level::Info => tracing::info!(target: "guest", "{message}"),
+        Level::Debug => tracing::debug!(target: "guest", "{message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{answer_words, GuestIo, WriteDecoder, WriteRoute, UART_HELLO_MARKER};
+    use crate::error::Result;
+    use airbender_core::cycle::{RegionEvent, RegionKind, CYCLE_REGION_MARKER};
+    use airbender_core::exit::{
+        GUEST_ABORT_MARKER, GUEST_ERROR_MARKER, GUEST_PANIC_LOCATION_MARKER, GUEST_PANIC_MARKER,
+        GUEST_STACK_OVERFLOW_MARKER,
+    };
+    use airbender_core::host::panics::{PanicLocation, PanicTable};
+    use airbender_core::journal::{split_entries, JOURNAL_MARKER};
+    use airbender_core::log::{Level, GUEST_LOG_MARKER};
+    use airbender_core::memory::{MemoryStats, MEMORY_STATS_MARKER};
+    use airbender_core::oracle::ORACLE_QUERY_MARKER;
+    use airbender_core::preamble::PREAMBLE_REQUEST_MARKER;
+    use airbender_core::wire::frame_words_from_bytes;
+    use std::sync::Arc;
+
+    fn push_all(decoder: &mut WriteDecoder, words: &[u32]) -> Vec<WriteRoute> {
+        words.iter().map(|word| decoder.push(*word)).collect()
+    }
+
+    /// Encode `message` the way `QuasiUart::write_str` does.
+    fn uart_frame(message: &str) -> Vec<u32> {
+        let mut words = vec![
+            UART_HELLO_MARKER,
+            message.len().div_ceil(4) as u32 + 1,
+            message.len() as u32,
+        ];
+        for chunk in message.as_bytes().chunks(4) {
+            let mut padded = [0u8; 4];
+            padded[..chunk.len()].copy_from_slice(chunk);
+            words.push(u32::from_le_bytes(padded));
+        }
+        words
+    }
+
+    #[test]
+    fn collects_journal_frames() {
+        let mut decoder = WriteDecoder::default();
+        for payload in [&b"hello"[..], &b""[..], &b"abcd"[..]] {
+            decoder.push(JOURNAL_MARKER);
+            push_all(
+                &mut decoder,
+                &frame_words_from_bytes(payload).expect("frame"),
+            );
+        }
+
+        let entries = split_entries(&decoder.output.journal).expect("split entries");
+        assert_eq!(entries, [&b"hello"[..], &b""[..], &b"abcd"[..]]);
+    }
+
+    #[test]
+    fn forwards_uart_frames_containing_journal_marker() {
+        let mut decoder = WriteDecoder::default();
+        let routes = push_all(&mut decoder, &[UART_HELLO_MARKER, 2, 4, JOURNAL_MARKER]);
+
+        assert!(routes.iter().all(|route| *route == WriteRoute::Uart));
+        assert!(decoder.output.journal.is_empty());
+
+        decoder.push(JOURNAL_MARKER);
+        push_all(&mut decoder, &frame_words_from_bytes(b"x").expect("frame"));
+        assert_eq!(
+            split_entries(&decoder.output.journal).expect("split entries"),
+            [&b"x"[..]]
+        );
+    }
+
+    #[test]
+    fn captures_guest_error_message_from_uart_frame() {
+        let mut decoder = WriteDecoder::default();
+        push_all(&mut decoder, &uart_frame("ordinary output"));
+        assert_eq!(decoder.push(GUEST_ERROR_MARKER), WriteRoute::Consumed);
+        let routes = push_all(&mut decoder, &uart_frame("balance too low"));
+
+        assert!(routes.iter().all(|route| *route == WriteRoute::Uart));
+        assert_eq!(
+            decoder.output.error_message.as_deref(),
+            Some("balance too low")
+        );
+        assert!(!decoder.output.aborted);
+        assert_eq!(decoder.push(GUEST_ABORT_MARKER), WriteRoute::Consumed);
+        assert!(decoder.output.aborted);
+    }
+
+    #[test]
+    fn collects_stdout_and_panic_message_frames() {
+        let mut decoder = WriteDecoder::default();
+        push_all(&mut decoder, &uart_frame("hello\n"));
+        decoder.push(GUEST_PANIC_MARKER);
+        for part in ["PANIC: ", "panicked at src/main.rs:3:5:\n", "boom"] {
+            push_all(&mut decoder, &uart_frame(part));
+        }
+
+        assert_eq!(
+            decoder.output.stdout,
+            [
+                "hello\n",
+                "PANIC: ",
+                "panicked at src/main.rs:3:5:\n",
+                "boom"
+            ]
+        );
+        assert_eq!(
+            decoder.output.panic_message.as_deref(),
+            Some("PANIC: panicked at src/main.rs:3:5:\nboom")
+        );
+        assert!(decoder.output.error_message.is_none());
+    }
+
+    #[test]
+    fn resolves_compact_panic_locations() {
+        let mut decoder = WriteDecoder::default();
+        let routes = push_all(
+            &mut decoder,
+            &[GUEST_PANIC_LOCATION_MARKER, 0x1_2340, GUEST_ABORT_MARKER],
+        );
+        assert!(routes.iter().all(|route| *route == WriteRoute::Consumed));
+        assert!(decoder.output.stdout.is_empty());
+
+        let table = PanicTable::new(vec![PanicLocation {
+            id: 0x1_2340,
+            file: "src/main.rs".to_string(),
+            line: 7,
+            column: 9,
+            message: "input must be even".to_string(),
+        }]);
+        let mut output = std::mem::take(&mut decoder.output);
+        output.resolve_panic_location(Some(&table));
+        assert_eq!(
+            output.panic_message.as_deref(),
+            Some("panicked at src/main.rs:7:9: input must be even")
+        );
+        output.resolve_panic_location(None);
+        assert_eq!(
+            output.panic_message.as_deref(),
+            Some("panicked at unknown location 0x00012340")
+        );
+    }
+
+    #[test]
+    fn flags_stack_overflow_and_keeps_report_in_stdout() {
+        let mut decoder = WriteDecoder::default();
+        assert_eq!(
+            decoder.push(GUEST_STACK_OVERFLOW_MARKER),
+            WriteRoute::Consumed
+        );
+        push_all(&mut decoder, &uart_frame("STACK_OVERFLOW: stack grew"));
+        decoder.push(GUEST_ABORT_MARKER);
+
+        assert!(decoder.output.stack_overflow);
+        assert!(decoder.output.aborted);
+        assert_eq!(decoder.output.stdout, ["STACK_OVERFLOW: stack grew"]);
+    }
+
+    #[test]
+    fn consumes_log_records_without_touching_stdout() {
+        let mut decoder = WriteDecoder::default();
+        let mut words = vec![GUEST_LOG_MARKER, Level::Warn.to_word()];
+        words.extend(uart_frame("low balance"));
+        let routes = push_all(&mut decoder, &words);
+
+        assert!(routes.iter().all(|route| *route == WriteRoute::Consumed));
+        assert!(decoder.output.stdout.is_empty());
+
+        let routes = push_all(&mut decoder, &uart_frame("after"));
+        assert!(routes.iter().all(|route| *route == WriteRoute::Uart));
+        assert_eq!(decoder.output.stdout, ["after"]);
+    }
+
+    #[test]
+    fn decodes_oracle_queries_and_frames_answers() {
+        let mut decoder = WriteDecoder::default();
+        let mut words = vec![ORACLE_QUERY_MARKER, 5];
+        words.extend(frame_words_from_bytes(b"key").expect("frame"));
+        let routes = push_all(&mut decoder, &words);
+
+        assert!(routes.iter().all(|route| *route == WriteRoute::Consumed));
+        let (tag, request) = decoder.pending_query.take().expect("pending query");
+        assert_eq!((tag, request.as_slice()), (5, &b"key"[..]));
+
+        let oracle = |tag: u32, request: &[u8]| -> Result<Vec<u8>> {
+            Ok([request, &tag.to_le_bytes()[..1]].concat())
+        };
+        assert_eq!(
+            answer_words(Some(&oracle), tag, &request).expect("answer"),
+            frame_words_from_bytes(b"key\x05").expect("frame")
+        );
+        let err = answer_words(None, 5, b"key").expect_err("no oracle");
+        assert_eq!(
+            err.to_string(),
+            "oracle error: guest sent a query with tag 5, but no oracle is registered"
+        );
+    }
+
+    #[test]
+    fn decodes_memory_stats_record() {
+        let stats = MemoryStats {
+            heap_bytes: 32,
+            heap_peak_bytes: 2048,
+            allocations: 9,
+            deallocations: 8,
+            stack_peak_bytes: 512,
+        };
+        let mut words = vec![MEMORY_STATS_MARKER];
+        words.extend(stats.to_words());
+        words.push(GUEST_ABORT_MARKER);
+        let mut decoder = WriteDecoder::default();
+        let routes = push_all(&mut decoder, &words);
+
+        assert!(routes.iter().all(|route| *route == WriteRoute::Consumed));
+        assert_eq!(decoder.output.memory_stats, Some(stats));
+        assert!(decoder.output.aborted);
+    }
+
+    #[test]
+    fn collects_region_events() {
+        let event = RegionEvent {
+            label: 0x2000,
+            kind: RegionKind::Enter,
+            marker: 3,
+        };
+        let mut words = vec![CYCLE_REGION_MARKER];
+        words.extend(event.to_words());
+        words.extend([CYCLE_REGION_MARKER, 0x2003, 4]);
+        let mut decoder = WriteDecoder::default();
+        let routes = push_all(&mut decoder, &words);
+
+        assert!(routes.iter().all(|route| *route == WriteRoute::Consumed));
+        assert_eq!(decoder.output.region_events, [event]);
+        assert!(decoder.output.stdout.is_empty());
+    }
+
+    #[test]
+    fn flags_preamble_requests() {
+        let mut decoder = WriteDecoder::default();
+        assert_eq!(decoder.push(PREAMBLE_REQUEST_MARKER), WriteRoute::Consumed);
+        assert!(decoder.preamble_requested);
+
+        let routes = push_all(&mut decoder, &uart_frame("after"));
+        assert!(routes.iter().all(|route| *route == WriteRoute::Uart));
+        assert_eq!(decoder.output.stdout, ["after"]);
+    }
+
+    #[test]
+    fn snapshots_only_between_write_frames() {
+        let mut io = GuestIo::new(&[1, 2, 3], None, Arc::from([]));
+        let event = RegionEvent {
+            label: 0x2000,
+            kind: RegionKind::Enter,
+            marker: 0,
+        };
+        push_all(&mut io.decoder, &[CYCLE_REGION_MARKER, 0x2001, 0]);
+        let frame = uart_frame("before");
+        push_all(&mut io.decoder, &frame[..2]);
+        assert!(io.snapshot().is_none());
+        push_all(&mut io.decoder, &frame[2..]);
+        io.decoder.push(GUEST_ERROR_MARKER);
+        io.input_words_read = 1;
+
+        let snapshot = io.snapshot().expect("idle decoder");
+        let mut resumed = GuestIo::resume(snapshot, None, Arc::from([]));
+        push_all(&mut resumed.decoder, &uart_frame("after"));
+        let output = resumed.into_output();
+        assert_eq!(output.stdout, ["before", "after"]);
+        assert_eq!(output.error_message.as_deref(), Some("after"));
+        assert_eq!(output.input_words_read, 1);
+        assert_eq!(output.region_events, [event]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Guest RAM kept after an interpreter run.
+
+use crate::error::{HostError, Result};
+use crate::symbols;
+use riscv_transpiler::common_constants::rom::ROM_SECOND_WORD_BITS;
+use riscv_transpiler::jit::RAM_SIZE;
+use riscv_transpiler::vm::{RamPeek, RamWithRomRegion};
+use std::ops::Range;
+use std::path::PathBuf;
+
+const WORD_BYTES: u32 = 4;
+
+/// Final memory image of a run, with reads by address or by `app.elf` symbol.
+pub struct GuestMemory {
+    ram: RamWithRomRegion<{ ROM_SECOND_WORD_BITS }>,
+    /// Symbols for lookups by name; a missing file fails those lookups only.
+    elf_path: PathBuf,
+}
+
+impl GuestMemory {
+    pub(crate) fn new(ram: RamWithRomRegion<{ ROM_SECOND_WORD_BITS }>, elf_path: PathBuf) -> Self {
+        Self { ram, elf_path }
+    }
+
+    /// Read the aligned little-endian word at `address`.
+    pub fn word(&self, address: u32) -> Result<u32> {
+        if !address.is_multiple_of(WORD_BYTES) {
+            return Err(HostError::Runner(format!(
+                "guest address {address:#010x} is not word aligned"
+            )));
+        }
+        check_range(address..address.saturating_add(WORD_BYTES))?;
+        Ok(self.ram.peek_word(address))
+    }
+
+    /// Read the bytes in `range`, which need not be aligned.
+    pub fn bytes(&self, range: Range<u32>) -> Result<Vec<u8>> {
+        check_range(range.clone())?;
+        let first_word = range.start & !(WORD_BYTES - 1);
+        let mut bytes = Vec::new();
+        for address in (first_word..range.end).step_by(WORD_BYTES as usize) {
+            bytes.extend(self.ram.peek_word(address).to_le_bytes());
+        }
+        let skip = (range.start - first_word) as usize;
+        Ok(bytes[skip..skip + range.len()].to_vec())
+    }
+
+    /// Address range of the `app.elf` symbol `name`, given mangled or demangled.
+    pub fn symbol(&self, name: &str) -> Result<Range<u32>> {
+        symbols::find_symbol(&self.elf_path, name)
+    }
+
+    /// Bytes of the static `name`, sized by its `app.elf` symbol.
+    pub fn symbol_bytes(&self, name: &str) -> Result<Vec<u8>> {
+        self.bytes(self.symbol(name)?)
+    }
+
+    /// Value of a word-sized static `name`.
+    pub fn symbol_word(&self, name: &str) -> Result<u32> {
+        let range = self.symbol(name)?;
+        if range.len() != WORD_BYTES as usize {
+            return Err(HostError::Runner(format!(
+                "symbol `{name}` is {} bytes, not a word",
+                range.len()
+            )));
+        }
+        self.word(range.start)
+    }
+}
+
+impl std::fmt::Debug for GuestMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuestMemory")
+            .field("elf_path", &self.elf_path)
+            .finish_non_exhaustive()
+    }
+}
+
+fn check_range(range: Range<u32>) -> Result<()> {
+    if range.start > range.end || range.end as usize > RAM_SIZE {
+        return Err(HostError::Runner(format!(
+            "guest range {:#010x}..{:#010x} is outside of RAM",
+            range.start, range.end
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GuestMemory;
+    use riscv_transpiler::common_constants::rom::ROM_SECOND_WORD_BITS;
+    use riscv_transpiler::jit::RAM_SIZE;
+    use riscv_transpiler::vm::RamWithRomRegion;
+    use std::path::PathBuf;
+
+    #[cfg(target_os = "linux")]
+    static NOT_A_WORD: [u32; 3] = [1, 2, 3];
+
+    fn memory(elf_path: PathBuf) -> GuestMemory {
+        let ram = RamWithRomRegion::<{ ROM_SECOND_WORD_BITS }>::from_rom_content(&[], RAM_SIZE);
+        GuestMemory::new(ram, elf_path)
+    }
+
+    #[test]
+    fn rejects_unaligned_words() {
+        let err = memory(PathBuf::new())
+            .word(0x2000_0001)
+            .expect_err("unaligned word");
+        assert!(err.to_string().contains("is not word aligned"));
+    }
+
+    #[test]
+    fn rejects_ranges_outside_of_ram() {
+        let memory = memory(PathBuf::new());
+        let end = RAM_SIZE as u32;
+        for range in [0..u32::MAX, end - 2..end + 2] {
+            let err = memory.bytes(range).expect_err("range outside of RAM");
+            assert!(err.to_string().contains("is outside of RAM"));
+        }
+        assert!(memory.word(end).is_err());
+        assert_eq!(memory.bytes(end - 4..end).expect("last word").len(), 4);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn rejects_symbols_that_are_not_words() {
+        std::hint::black_box(&NOT_A_WORD);
+        let exe = std::env::current_exe().expect("test executable");
+
+        let err = memory(exe)
+            .symbol_word("airbender_host::runner::guest_memory::tests::NOT_A_WORD")
+            .expect_err("12-byte symbol");
+        assert!(err.to_string().contains("is 12 bytes, not a word"));
+    }
+}
@@ -1,10 +1,20 @@
+use crate::backtrace::Backtrace;
 use crate::cycle_marker::CycleMarker;
 use crate::error::{HostError, Result};
 use crate::receipt::Receipt;
+use airbender_core::host::manifest::MemoryLayout;
+use airbender_core::memory::MemoryStats;
+use riscv_transpiler::common_constants::rom::ROM_BYTE_SIZE;
 use std::path::PathBuf;
 
+mod gdb;
+mod guest_io;
+mod guest_memory;
 mod transpiler_runner;
 
+pub(crate) use self::guest_io::{GuestIo, GuestIoSnapshot, GuestOutput};
+
+pub use self::guest_memory::GuestMemory;
 pub use self::transpiler_runner::{TranspilerRunner, TranspilerRunnerBuilder};
 
 /// Flamegraph collection options for execution runners.
@@ -28,8 +38,113 @@ pub trait Runner {
 pub struct ExecutionResult {
     pub receipt: Receipt,
     pub cycles_executed: usize,
-    pub reached_end: bool,
+    pub outcome: ExecutionOutcome,
     pub cycle_markers: Option<CycleMarker>,
+    /// Number of input words the guest read, excluding oracle answers; fewer than supplied
+    /// means leftover inputs.
+    pub input_words_consumed: usize,
+    /// Every word served to guest reads, with oracle answers and the guest environment in
+    /// place; proving with these as input words replays the run without querying the oracle
+    /// again.
+    pub read_words: Vec<u32>,
+    /// UART frames written by the guest (`println!`, panics, errors), decoded in order.
+    pub stdout: Vec<String>,
+    /// Raw public journal bytes written by the guest.
+    pub journal: Vec<u8>,
+    /// Heap and stack usage, reported by guests built with the runtime `memory-stats` feature.
+    pub memory_stats: Option<MemoryStats>,
+    /// Guest call stack where a non-JIT run stopped, captured unless the guest succeeded.
+    pub backtrace: Option<Backtrace>,
+    memory: Option<GuestMemory>,
+}
+
+impl ExecutionResult {
+    /// Guest RAM as the run left it, kept for runners built with
+    /// [`TranspilerRunnerBuilder::keep_memory`].
+    pub fn memory(&self) -> Option<&GuestMemory> {
+        self.memory.as_ref()
+    }
+}
+
+/// How a guest run ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionOutcome {
+    /// The guest committed its output and exited successfully.
+    Success,
+    /// The guest exited through the error path by returning `Err` or panicking.
+    GuestError(GuestError),
+    /// The cycle budget ran out before the guest exited.
+    CycleLimitExceeded,
+    /// The guest stack grew into the heap guard region and the runtime stopped the guest.
+    StackOverflow,
+}
+
+impl ExecutionOutcome {
+    pub(crate) fn from_run(reached_end: bool, output: &GuestOutput) -> Self {
+        if output.stack_overflow {
+            return Self::StackOverflow;
+        }
+        if let Some(message) = &output.error_message {
+            return Self::GuestError(GuestError {
+                message: message.clone(),
+                panicked: false,
+            });
+        }
+        if let Some(message) = &output.panic_message {
+            let message = message.strip_prefix(PANIC_PREFIX).unwrap_or(message);
+            return Self::GuestError(GuestError {
+                message: message.to_string(),
+                panicked: true,
+            });
+        }
+        if output.aborted {
+            return Self::GuestError(GuestError {
+                message: "guest exited with an error".to_string(),
+                panicked: false,
+            });
+        }
+        if reached_end {
+            Self::Success
+        } else {
+            Self::CycleLimitExceeded
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success)
+    }
+}
+
+impl std::fmt::Display for ExecutionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Success => f.write_str("success"),
+            Self::GuestError(err) => err.fmt(f),
+            Self::CycleLimitExceeded => f.write_str("cycle limit exceeded"),
+            Self::StackOverflow => f.write_str("stack overflow"),
+        }
+    }
+}
+
+/// Prefix the guest runtime writes in front of panic messages.
+const PANIC_PREFIX: &str = "PANIC: ";
+
+/// Error reported by the guest before exiting through the error path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuestError {
+    pub message: String,
+    /// Whether the message comes from a panic rather than an `Err` returned from `main`.
+    pub panicked: bool,
+}
+
+impl std::fmt::Display for GuestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.panicked {
+            write!(f, "guest panicked: {}", self.message)
+        } else {
+            write!(f, "guest error: {}", self.message)
+        }
+    }
 }
 
 /// Resolve the cycle budget from an explicit override or default.
@@ -43,9 +158,36 @@ pub fn resolve_cycles(explicit_cycles: Option<usize>) -> Result<usize> {
     Ok(cycles)
 }
 
+/// Check that the stack and heap of `layout` fit in `ram_bytes` of RAM next to the ROM.
+pub(crate) fn check_memory_layout(
+    layout: &MemoryLayout,
+    ram_bytes: usize,
+) -> std::result::Result<(), String> {
+    let available = ram_bytes.saturating_sub(ROM_BYTE_SIZE) as u64;
+    let required = layout.required_bytes();
+    if required > available {
+        return Err(format!(
+            "guest memory layout needs {required} bytes of stack and heap, \
+             but only {available} bytes of RAM are available"
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{resolve_cycles, DEFAULT_CYCLES};
+    use super::{
+        check_memory_layout, resolve_cycles, ExecutionOutcome, GuestError, GuestOutput,
+        MemoryLayout, DEFAULT_CYCLES, ROM_BYTE_SIZE,
+    };
+
+    #[test]
+    fn guest_ram_size_matches_transpiler() {
+        assert_eq!(
+            airbender_core::host::manifest::GUEST_RAM_BYTES,
+            riscv_transpiler::jit::RAM_SIZE as u64
+        );
+    }
 
     #[test]
     fn resolve_cycles_uses_explicit_value() {
@@ -65,4 +207,75 @@ mod tests {
             "runner error: cycle budget must be greater than zero"
         );
     }
+
+    #[test]
+    fn memory_layout_must_fit_next_to_rom() {
+        let layout = MemoryLayout {
+            stack: Some(1 << 16),
+            heap: Some(1 << 20),
+        };
+        let required = (1 << 16) + (1 << 20);
+        assert!(check_memory_layout(&layout, ROM_BYTE_SIZE + required).is_ok());
+        let err = check_memory_layout(&layout, ROM_BYTE_SIZE + required - 1).expect_err("error");
+        assert!(err.contains(&format!("needs {required} bytes")));
+        assert!(check_memory_layout(&MemoryLayout::default(), ROM_BYTE_SIZE).is_ok());
+    }
+
+    #[test]
+    fn outcome_distinguishes_success_errors_overflow_and_cycle_limit() {
+        let clean = GuestOutput::default();
+        assert_eq!(
+            ExecutionOutcome::from_run(true, &clean),
+            ExecutionOutcome::Success
+        );
+        assert_eq!(
+            ExecutionOutcome::from_run(false, &clean),
+            ExecutionOutcome::CycleLimitExceeded
+        );
+
+        let aborted = GuestOutput {
+            aborted: true,
+            ..GuestOutput::default()
+        };
+        assert_eq!(
+            ExecutionOutcome::from_run(true, &aborted),
+            ExecutionOutcome::GuestError(GuestError {
+                message: "guest exited with an error".to_string(),
+                panicked: false,
+            })
+        );
+
+        let reported = GuestOutput {
+            error_message: Some("balance too low".to_string()),
+            aborted: true,
+            ..GuestOutput::default()
+        };
+        assert_eq!(
+            ExecutionOutcome::from_run(true, &reported).to_string(),
+            "guest error: balance too low"
+        );
+
+        let panicked = GuestOutput {
+            panic_message: Some("PANIC: panicked at src/main.rs:3:5:\nboom".to_string()),
+            aborted: true,
+            ..GuestOutput::default()
+        };
+        assert_eq!(
+            ExecutionOutcome::from_run(true, &panicked),
+            ExecutionOutcome::GuestError(GuestError {
+                message: "panicked at src/main.rs:3:5:\nboom".to_string(),
+                panicked: true,
+            })
+        );
+
+        let overflowed = GuestOutput {
+            stack_overflow: true,
+            aborted: true,
+            ..GuestOutput::default()
+        };
+        assert_eq!(
+            ExecutionOutcome::from_run(true, &overflowed),
+            ExecutionOutcome::StackOverflow
+        );
+    }
 }
@@ -1,9 +1,22 @@
-use super::{resolve_cycles, ExecutionResult, FlamegraphConfig, Runner};
+use super::gdb::{self, DebugTarget, SessionEnd, StepOutcome};
+use super::{
+    check_memory_layout, resolve_cycles, ExecutionOutcome, ExecutionResult, FlamegraphConfig,
+    GuestIo, GuestMemory, Runner,
+};
+use crate::backtrace::Backtrace;
+use crate::cycle_marker::CycleMarker;
 use crate::error::{HostError, Result};
+use crate::guest_env::GuestEnv;
+use crate::oracle::Oracle;
 use crate::receipt::Receipt;
-use riscv_transpiler::abstractions::non_determinism::QuasiUARTSource;
+use crate::snapshot::{SnapshotConfig, SnapshotPoint, VmSnapshot};
+use crate::trace::{TraceConfig, TraceEntry, TraceWriter};
+use airbender_core::host::manifest::MemoryLayout;
+use airbender_core::host::panics::{PanicTable, PANIC_TABLE_FILE};
+use airbender_core::host::regions::{RegionTable, REGION_TABLE_FILE};
 use riscv_transpiler::common_constants::{
-    rom::ROM_SECOND_WORD_BITS, INITIAL_TIMESTAMP, TIMESTAMP_STEP,
+    rom::{ROM_BYTE_SIZE, ROM_SECOND_WORD_BITS},
+    INITIAL_TIMESTAMP, TIMESTAMP_STEP,
 };
 use riscv_transpiler::cycle::CycleMarkerHooks;
 use riscv_transpiler::ir::{preprocess_bytecode, FullUnsignedMachineDecoderConfig};
@@ -11,11 +24,13 @@ use riscv_transpiler::ir::{preprocess_bytecode, FullUnsignedMachineDecoderConfig
 use riscv_transpiler::jit::JittedCode;
 use riscv_transpiler::jit::RAM_SIZE;
 use riscv_transpiler::vm::{
-    DelegationsCounters, FlamegraphConfig as VmFlamegraphConfig, RamWithRomRegion, SimpleTape,
-    State, VmFlamegraphProfiler, VM,
+    DelegationsCounters, FlamegraphConfig as VmFlamegraphConfig, RamPeek, RamWithRomRegion,
+    SimpleTape, State, VmFlamegraphProfiler, RAM, VM,
 };
 use std::io::Read;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Builder for creating a configured transpiler runner.
 pub struct TranspilerRunnerBuilder {
@@ -24,6 +39,14 @@ pub struct TranspilerRunnerBuilder {
     text_path: Option<PathBuf>,
     flamegraph: Option<FlamegraphConfig>,
     use_jit: bool,
+    oracle: Option<Arc<dyn Oracle>>,
+    guest_env: GuestEnv,
+    memory_layout: Option<MemoryLayout>,
+    gdb_server: Option<SocketAddr>,
+    trace: Option<TraceConfig>,
+    snapshot: Option<SnapshotConfig>,
+    resume: Option<PathBuf>,
+    keep_memory: bool,
 }
 
 impl TranspilerRunnerBuilder {
@@ -34,6 +57,14 @@ impl TranspilerRunnerBuilder {
             text_path: None,
             flamegraph: None,
             use_jit: false,
+            oracle: None,
+            guest_env: GuestEnv::default(),
+            memory_layout: None,
+            gdb_server: None,
+            trace: None,
+            snapshot: None,
+            resume: None,
+            keep_memory: false,
         }
     }
 
@@ -71,6 +102,74 @@ impl TranspilerRunnerBuilder {
         self
     }
 
+    /// Keep the guest RAM as each run left it, for [`ExecutionResult::memory`].
+    ///
+    /// Every result then holds a copy of the whole RAM.
+    pub fn keep_memory(mut self) -> Self {
+        self.keep_memory = true;
+        self
+    }
+
+    /// Answer guest oracle queries with `oracle` during execution.
+    pub fn with_oracle(self, oracle: impl Oracle + 'static) -> Self {
+        self.maybe_oracle(Some(Arc::new(oracle)))
+    }
+
+    pub(crate) fn maybe_oracle(mut self, oracle: Option<Arc<dyn Oracle>>) -> Self {
+        self.oracle = oracle;
+        self
+    }
+
+    /// Serve `guest_env` to `std` guests reading their args, env vars or stdin.
+    pub fn with_guest_env(mut self, guest_env: GuestEnv) -> Self {
+        self.guest_env = guest_env;
+        self
+    }
+
+    /// Reject guests whose memory layout does not fit in the transpiler's RAM.
+    pub fn with_memory_layout(mut self, memory_layout: MemoryLayout) -> Self {
+        self.memory_layout = Some(memory_layout);
+        self
+    }
+
+    pub fn maybe_memory_layout(self, memory_layout: Option<MemoryLayout>) -> Self {
+        match memory_layout {
+            Some(v) => self.with_memory_layout(v),
+            None => self,
+        }
+    }
+
+    /// Wait for a GDB connection on `addr` before each run and let it drive the guest.
+    pub fn with_gdb_server(mut self, addr: SocketAddr) -> Self {
+        self.gdb_server = Some(addr);
+        self
+    }
+
+    /// Record an instruction-level execution trace of each run.
+    pub fn with_trace(mut self, trace: TraceConfig) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    /// Save the VM state of each run at a cycle count or cycle marker.
+    pub fn with_snapshot(mut self, snapshot: SnapshotConfig) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    /// Start each run from a snapshot file instead of the program entry point.
+    pub fn with_resume(mut self, snapshot_path: impl AsRef<Path>) -> Self {
+        self.resume = Some(snapshot_path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn maybe_resume(self, snapshot_path: Option<impl AsRef<Path>>) -> Self {
+        match snapshot_path {
+            Some(v) => self.with_resume(v),
+            None => self,
+        }
+    }
+
     pub fn build(self) -> Result<TranspilerRunner> {
         if self.use_jit && cfg!(not(target_arch = "x86_64")) {
             return Err(HostError::Transpiler(
@@ -78,6 +177,60 @@ impl TranspilerRunnerBuilder {
             ));
         }
 
+        if self.gdb_server.is_some() && (self.use_jit || self.flamegraph.is_some()) {
+            return Err(HostError::Transpiler(
+                "the GDB server cannot be combined with JIT execution or flamegraphs".to_string(),
+            ));
+        }
+
+        if self.trace.is_some()
+            && (self.use_jit || self.flamegraph.is_some() || self.gdb_server.is_some())
+        {
+            return Err(HostError::Transpiler(
+                "execution traces cannot be combined with JIT execution, flamegraphs, or the GDB server"
+                    .to_string(),
+            ));
+        }
+
+        if self
+            .trace
+            .as_ref()
+            .is_some_and(|trace| trace.pc_range.is_some() && trace.cycles.is_none())
+        {
+            return Err(HostError::Transpiler(
+                "a `pc` range trace also needs a cycle window".to_string(),
+            ));
+        }
+
+        if self.snapshot.is_some()
+            && (self.use_jit
+                || self.flamegraph.is_some()
+                || self.gdb_server.is_some()
+                || self.trace.is_some())
+        {
+            return Err(HostError::Transpiler(
+                "snapshots cannot be combined with JIT execution, flamegraphs, the GDB server, or execution traces"
+                    .to_string(),
+            ));
+        }
+
+        if self.resume.is_some() && (self.use_jit || self.flamegraph.is_some()) {
+            return Err(HostError::Transpiler(
+                "resuming from a snapshot cannot be combined with JIT execution or flamegraphs"
+                    .to_string(),
+            ));
+        }
+
+        if self.keep_memory && self.use_jit {
+            return Err(HostError::Transpiler(
+                "keeping guest memory cannot be combined with JIT execution".to_string(),
+            ));
+        }
+
+        if let Some(layout) = &self.memory_layout {
+            check_memory_layout(layout, RAM_SIZE).map_err(HostError::Transpiler)?;
+        }
+
         let app_bin_path = resolve_app_bin_path(&self.app_bin_path)?;
         let app_text_path = self
             .text_path
@@ -85,13 +238,34 @@ impl TranspilerRunnerBuilder {
             .map(resolve_text_path)
             .unwrap_or_else(|| resolve_text_path(&derive_text_path(&app_bin_path)))?;
         let cycles = resolve_cycles(self.cycles)?;
+        let preamble = self.guest_env.preamble_words()?.into();
+        let panic_table = load_panic_table(&app_bin_path)?;
+        let region_table = load_region_table(&app_bin_path)?;
+        let app_elf_path = derive_elf_path(&app_bin_path);
+        let resume = match &self.resume {
+            Some(path) => Some(VmSnapshot::read_from_file(
+                path,
+                &read_u32_words(&app_bin_path)?,
+            )?),
+            None => None,
+        };
 
         Ok(TranspilerRunner {
             app_bin_path,
             app_text_path,
+            app_elf_path,
             cycles,
             flamegraph: self.flamegraph,
             use_jit: self.use_jit,
+            oracle: self.oracle,
+            preamble,
+            panic_table,
+            region_table,
+            gdb_server: self.gdb_server,
+            trace: self.trace,
+            snapshot: self.snapshot,
+            resume,
+            keep_memory: self.keep_memory,
         })
     }
 }
@@ -100,9 +274,24 @@ impl TranspilerRunnerBuilder {
 pub struct TranspilerRunner {
     app_bin_path: PathBuf,
     app_text_path: PathBuf,
+    /// Symbols for backtraces; a missing file leaves frames unresolved.
+    app_elf_path: PathBuf,
     cycles: usize,
     flamegraph: Option<FlamegraphConfig>,
     use_jit: bool,
+    oracle: Option<Arc<dyn Oracle>>,
+    preamble: Arc<[u32]>,
+    /// Panic locations from the dist `panics.toml`, for compact-panic guests.
+    panic_table: Option<PanicTable>,
+    /// Region names from the dist `regions.toml`, for guests with named regions.
+    region_table: Option<RegionTable>,
+    gdb_server: Option<SocketAddr>,
+    trace: Option<TraceConfig>,
+    snapshot: Option<SnapshotConfig>,
+    /// Snapshot every run starts from instead of the program entry point.
+    resume: Option<VmSnapshot>,
+    /// Whether results hold the final guest RAM.
+    keep_memory: bool,
 }
 
 impl Runner for TranspilerRunner {
@@ -124,7 +313,8 @@ impl TranspilerRunner {
     fn run_with_jit(&self, input_words: &[u32]) -> Result<ExecutionResult> {
         let bin_words = read_u32_words(&self.app_bin_path)?;
         let text_words = read_u32_words(&self.app_text_path)?;
-        let mut non_determinism_source = QuasiUARTSource::new_with_reads(input_words.to_vec());
+        let mut non_determinism_source =
+            GuestIo::new(input_words, self.oracle.clone(), self.preamble.clone());
 
         let cycles_bound = match u32::try_from(self.cycles) {
             Ok(value) => Some(value),
@@ -144,12 +334,27 @@ impl TranspilerRunner {
             cycles_bound,
         );
         let cycles_executed = ((state.timestamp - INITIAL_TIMESTAMP) / TIMESTAMP_STEP) as usize;
+        // The JIT stops either at the guest exit loop or once the cycle bound is spent, which
+        // may happen on the same cycle, so look at where the guest stopped.
+        let reached_end = at_exit_loop(&text_words, state.pc);
+        let mut output = non_determinism_source.into_output();
+        output.resolve_panic_location(self.panic_table.as_ref());
+        if let Some(err) = output.oracle_error {
+            return Err(HostError::Oracle(err));
+        }
 
         Ok(ExecutionResult {
             receipt: Receipt::from_registers(state.registers),
             cycles_executed,
-            reached_end: true,
+            outcome: ExecutionOutcome::from_run(reached_end, &output),
             cycle_markers: None,
+            input_words_consumed: output.input_words_read,
+            stdout: output.stdout,
+            read_words: output.read_words,
+            journal: output.journal,
+            memory_stats: output.memory_stats,
+            backtrace: None,
+            memory: None,
         })
     }
 
@@ -196,50 +401,323 @@ impl TranspilerRunner {
         let mut ram =
             RamWithRomRegion::<{ ROM_SECOND_WORD_BITS }>::from_rom_content(&bin_words, RAM_SIZE);
         let mut state = State::initial_with_counters(DelegationsCounters::default());
-        let mut non_determinism_source = QuasiUARTSource::new_with_reads(input_words.to_vec());
-
-        let (reached_end, cycle_markers) = CycleMarkerHooks::with(|| match profiler {
-            Some(profiler) => {
-                VM::<DelegationsCounters, CycleMarkerHooks>::run_basic_unrolled_with_flamegraph::<
-                    _,
-                    _,
-                    _,
-                >(
-                    &mut state,
-                    &mut ram,
-                    &mut (),
-                    &instruction_tape,
-                    self.cycles,
-                    &mut non_determinism_source,
-                    profiler,
-                )
-                .map_err(|err| {
-                    HostError::Transpiler(format!("failed to generate flamegraph: {err}"))
-                })
+        let mut non_determinism_source = match &self.resume {
+            Some(snapshot) => {
+                restore_vm(snapshot, &mut state, &mut ram);
+                snapshot.guest_io(input_words, self.oracle.clone(), self.preamble.clone())?
             }
-            None => Ok(
-                VM::<DelegationsCounters, CycleMarkerHooks>::run_basic_unrolled::<_, _, _>(
-                    &mut state,
-                    &mut ram,
-                    &mut (),
-                    &instruction_tape,
-                    self.cycles,
-                    &mut non_determinism_source,
-                ),
-            ),
-        });
-        let reached_end = reached_end?;
+            None => GuestIo::new(input_words, self.oracle.clone(), self.preamble.clone()),
+        };
 
-        let cycles_executed = ((state.timestamp - INITIAL_TIMESTAMP) / TIMESTAMP_STEP) as usize;
+        let earlier_markers = self
+            .resume
+            .as_ref()
+            .map(|snapshot| snapshot.cycle_markers.clone())
+            .unwrap_or_default();
+        let run = |state: &mut VmState, ram: &mut VmRam, io: &mut GuestIo, cycles| {
+            Vm::run_basic_unrolled::<_, _, _>(state, ram, &mut (), &instruction_tape, cycles, io)
+        };
+        let (reached_end, cycle_markers) = match (profiler, &self.snapshot) {
+            (None, Some(snapshot)) => self.run_with_snapshot(
+                snapshot,
+                &bin_words,
+                &earlier_markers,
+                &mut state,
+                &mut ram,
+                &mut non_determinism_source,
+                run,
+            )?,
+            (profiler, _) => {
+                let (reached_end, cycle_markers) = CycleMarkerHooks::with(|| match profiler {
+                    Some(profiler) => Vm::run_basic_unrolled_with_flamegraph::<_, _, _>(
+                        &mut state,
+                        &mut ram,
+                        &mut (),
+                        &instruction_tape,
+                        self.cycles,
+                        &mut non_determinism_source,
+                        profiler,
+                    )
+                    .map_err(|err| {
+                        HostError::Transpiler(format!("failed to generate flamegraph: {err}"))
+                    }),
+                    None => {
+                        let io = &mut non_determinism_source;
+                        match (self.gdb_server, &self.trace) {
+                            (Some(addr), _) => self.run_with_gdb_server(
+                                addr,
+                                &mut state,
+                                &mut ram,
+                                |state, ram, cycles| run(state, ram, io, cycles),
+                            ),
+                            (None, Some(trace)) => self.run_with_trace(
+                                trace,
+                                &mut state,
+                                &mut ram,
+                                |state, ram, cycles| run(state, ram, io, cycles),
+                            ),
+                            (None, None) => {
+                                let remaining = self.cycles.saturating_sub(cycles_executed(&state));
+                                Ok(run(&mut state, &mut ram, io, remaining))
+                            }
+                        }
+                    }
+                });
+                (reached_end?, CycleMarker::from(cycle_markers))
+            }
+        };
+
+        let cycles_executed = cycles_executed(&state);
         let registers = state.registers.map(|register| register.value);
+        let mut output = non_determinism_source.into_output();
+        output.resolve_panic_location(self.panic_table.as_ref());
+        if let Some(err) = output.oracle_error {
+            return Err(HostError::Oracle(err));
+        }
+
+        let cycle_markers = earlier_markers
+            .followed_by(cycle_markers)
+            .with_regions(&output.region_events, self.region_table.as_ref());
+
+        let outcome = ExecutionOutcome::from_run(reached_end, &output);
+        let backtrace = (!outcome.is_success()).then(|| {
+            Backtrace::capture(
+                state.pc,
+                registers[FRAME_POINTER],
+                |address| peek_ram_word(&ram, address),
+                &self.app_elf_path,
+            )
+        });
 
         Ok(ExecutionResult {
             receipt: Receipt::from_registers(registers),
             cycles_executed,
-            reached_end,
-            cycle_markers: Some(cycle_markers.into()),
+            outcome,
+            cycle_markers: Some(cycle_markers),
+            input_words_consumed: output.input_words_read,
+            stdout: output.stdout,
+            read_words: output.read_words,
+            journal: output.journal,
+            memory_stats: output.memory_stats,
+            backtrace,
+            memory: self
+                .keep_memory
+                .then(|| GuestMemory::new(ram, self.app_elf_path.clone())),
         })
     }
+
+    /// Hand the guest to a debugger, then finish the run it detaches from.
+    ///
+    /// Killing the guest or dropping the connection from the debugger aborts the run.
+    fn run_with_gdb_server(
+        &self,
+        addr: SocketAddr,
+        state: &mut VmState,
+        ram: &mut VmRam,
+        mut run: impl FnMut(&mut VmState, &mut VmRam, usize) -> bool,
+    ) -> Result<bool> {
+        let mut target = VmDebugTarget {
+            state: &mut *state,
+            ram: &mut *ram,
+            cycles: self.cycles,
+            run: &mut run,
+        };
+        match gdb::serve(addr, &mut target, &self.app_elf_path)? {
+            SessionEnd::Finished(StepOutcome::Exited) => Ok(true),
+            SessionEnd::Finished(StepOutcome::CycleLimit) => Ok(false),
+            SessionEnd::Finished(StepOutcome::Running) | SessionEnd::Detached => {
+                let remaining = self.cycles.saturating_sub(cycles_executed(state));
+                Ok(run(state, ram, remaining))
+            }
+            SessionEnd::Killed => Err(HostError::Runner(
+                "guest run was killed from GDB".to_string(),
+            )),
+            SessionEnd::Disconnected => Err(HostError::Runner(
+                "GDB disconnected without detaching; guest run aborted".to_string(),
+            )),
+        }
+    }
+
+    /// Step through the run instruction by instruction while it can be traced, then finish it.
+    ///
+    /// The trace window counts cycles from the start of the run, so a resumed run lines it up
+    /// with the cycles executed before the snapshot.
+    fn run_with_trace(
+        &self,
+        trace: &TraceConfig,
+        state: &mut VmState,
+        ram: &mut VmRam,
+        mut run: impl FnMut(&mut VmState, &mut VmRam, usize) -> bool,
+    ) -> Result<bool> {
+        let mut writer = TraceWriter::create(trace)?;
+        let (window_start, window_end) = match &trace.cycles {
+            Some(window) => (window.start.min(self.cycles), window.end.min(self.cycles)),
+            None => (0, self.cycles),
+        };
+        let skipped = window_start.saturating_sub(cycles_executed(state));
+        if skipped > 0 && run(state, ram, skipped) {
+            writer.finish()?;
+            return Ok(true);
+        }
+
+        let mut reached_end = false;
+        while !reached_end && cycles_executed(state) < window_end {
+            let cycle = cycles_executed(state);
+            let pc = state.pc;
+            if !writer.records(cycle, pc) {
+                reached_end = run(state, ram, 1);
+                continue;
+            }
+            let before = state.registers.map(|register| register.value);
+            let instruction = peek_ram_word(&*ram, pc).unwrap_or_default();
+            reached_end = run(state, ram, 1);
+            let after = state.registers.map(|register| register.value);
+            let entry = TraceEntry::observe(cycle, pc, instruction, &before, &after, |address| {
+                peek_ram_word(&*ram, address)
+            });
+            writer.write(&entry)?;
+        }
+        writer.finish()?;
+
+        if reached_end {
+            return Ok(true);
+        }
+        let remaining = self.cycles.saturating_sub(cycles_executed(state));
+        Ok(run(state, ram, remaining))
+    }
+
+    /// Run up to the snapshot point, save the VM there, then finish the run.
+    ///
+    /// The two parts collect cycle markers separately, so the snapshot can hold the ones
+    /// before it; `earlier` are the markers of the snapshot this run resumed from, if any. The
+    /// returned markers cover this run only.
+    #[allow(clippy::too_many_arguments)]
+    fn run_with_snapshot(
+        &self,
+        config: &SnapshotConfig,
+        bin_words: &[u32],
+        earlier: &CycleMarker,
+        state: &mut VmState,
+        ram: &mut VmRam,
+        io: &mut GuestIo,
+        run: impl Fn(&mut VmState, &mut VmRam, &mut GuestIo, usize) -> bool,
+    ) -> Result<(bool, CycleMarker)> {
+        let ((reached_end, io_snapshot), before) = CycleMarkerHooks::with(|| {
+            let mut reached_end = match config.at {
+                SnapshotPoint::Cycle(cycle) => {
+                    let (target, done) = (cycle.min(self.cycles), cycles_executed(state));
+                    target > done && run(state, ram, io, target - done)
+                }
+                SnapshotPoint::Marker(index) => {
+                    let mut markers = earlier.markers.len();
+                    loop {
+                        if cycles_executed(state) >= self.cycles {
+                            break false;
+                        }
+                        let marker =
+                            peek_ram_word(&*ram, state.pc) == Some(CYCLE_MARKER_INSTRUCTION);
+                        if run(state, ram, io, 1) {
+                            break true;
+                        }
+                        if marker {
+                            if markers == index {
+                                break false;
+                            }
+                            markers += 1;
+                        }
+                    }
+                }
+            };
+
+            // Guest I/O can only be saved between write frames, so finish any frame in flight.
+            loop {
+                if reached_end || cycles_executed(state) >= self.cycles {
+                    break (reached_end, None);
+                }
+                if let Some(io_snapshot) = io.snapshot() {
+                    break (false, Some(io_snapshot));
+                }
+                reached_end = run(state, ram, io, 1);
+            }
+        });
+        let before = CycleMarker::from(before);
+        let Some(io_snapshot) = io_snapshot else {
+            tracing::warn!("the run stopped before the snapshot point; no snapshot was written");
+            return Ok((reached_end, before));
+        };
+        let snapshot = VmSnapshot::capture(
+            bin_words,
+            state.pc,
+            state.timestamp,
+            state.registers.map(|register| register.value),
+            ROM_BYTE_SIZE..RAM_SIZE,
+            |address| ram.peek_word(address),
+            earlier.clone().followed_by(before.clone()),
+            io_snapshot,
+        );
+        snapshot.write_to_file(&config.output)?;
+
+        let remaining = self.cycles - cycles_executed(state);
+        let (reached_end, after) = CycleMarkerHooks::with(|| run(state, ram, io, remaining));
+        Ok((reached_end, before.followed_by(CycleMarker::from(after))))
+    }
+}
+
+/// Put the registers and RAM saved in `snapshot` into a freshly initialized VM.
+///
+/// The cycle markers and delegation counts saved with it are added to those of the resumed run
+/// once it finishes.
+fn restore_vm(snapshot: &VmSnapshot, state: &mut VmState, ram: &mut VmRam) {
+    state.pc = snapshot.pc;
+    state.timestamp = snapshot.timestamp;
+    for (register, value) in state.registers.iter_mut().zip(snapshot.registers) {
+        register.value = value;
+    }
+    for (address, word) in snapshot.ram_words() {
+        ram.write_word(address, word, INITIAL_TIMESTAMP);
+    }
+}
+
+type Vm = VM<DelegationsCounters, CycleMarkerHooks>;
+type VmState = State<DelegationsCounters>;
+type VmRam = RamWithRomRegion<{ ROM_SECOND_WORD_BITS }>;
+
+/// The interpreter VM, executed one instruction per debugger step.
+struct VmDebugTarget<'a, F> {
+    state: &'a mut VmState,
+    ram: &'a mut VmRam,
+    cycles: usize,
+    run: &'a mut F,
+}
+
+impl<F: FnMut(&mut VmState, &mut VmRam, usize) -> bool> DebugTarget for VmDebugTarget<'_, F> {
+    fn registers(&self) -> [u32; 32] {
+        self.state.registers.map(|register| register.value)
+    }
+
+    fn pc(&self) -> u32 {
+        self.state.pc
+    }
+
+    fn read_byte(&self, address: u32) -> Option<u8> {
+        let word = peek_ram_word(&*self.ram, address & !3)?;
+        Some(word.to_le_bytes()[(address % 4) as usize])
+    }
+
+    fn step(&mut self) -> StepOutcome {
+        if cycles_executed(self.state) >= self.cycles {
+            return StepOutcome::CycleLimit;
+        }
+        if (self.run)(self.state, self.ram, 1) {
+            StepOutcome::Exited
+        } else {
+            StepOutcome::Running
+        }
+    }
+}
+
+fn cycles_executed(state: &VmState) -> usize {
+    ((state.timestamp - INITIAL_TIMESTAMP) / TIMESTAMP_STEP) as usize
 }
 
 fn resolve_app_bin_path(path: &Path) -> Result<PathBuf> {
@@ -274,6 +752,52 @@ fn resolve_text_path(path: &Path) -> Result<PathBuf> {
     })
 }
 
+/// `csrrw x0, 0x7ff, x0`, which the guest executes at each cycle marker.
+const CYCLE_MARKER_INSTRUCTION: u32 = 0x7ff0_1073;
+
+/// Whether `pc` points at the exit loop in `text_words`, where the guest parks once it exits.
+#[cfg(any(target_arch = "x86_64", test))]
+fn at_exit_loop(text_words: &[u32], pc: u32) -> bool {
+    const EXIT_LOOP_INSTRUCTION: u32 = 0x0000_006f; // jal x0, 0
+    text_words.get(pc as usize / 4) == Some(&EXIT_LOOP_INSTRUCTION)
+}
+
+/// Register `s0`/`fp`, which guests keep as the frame pointer.
+const FRAME_POINTER: usize = 8;
+
+/// Read an aligned word of guest memory, or `None` outside of RAM.
+fn peek_ram_word(ram: &impl RamPeek, address: u32) -> Option<u32> {
+    (address.is_multiple_of(4) && (address as usize) < RAM_SIZE).then(|| ram.peek_word(address))
+}
+
+/// Load the panic location table written next to `app.bin` for compact-panic guests.
+fn load_panic_table(bin_path: &Path) -> Result<Option<PanicTable>> {
+    let path = bin_path.with_file_name(PANIC_TABLE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    PanicTable::read_from_file(&path).map(Some).map_err(|err| {
+        HostError::Transpiler(format!(
+            "failed to read panic table {}: {err}",
+            path.display()
+        ))
+    })
+}
+
+/// Load the region name table written next to `app.bin` for guests with named regions.
+fn load_region_table(bin_path: &Path) -> Result<Option<RegionTable>> {
+    let path = bin_path.with_file_name(REGION_TABLE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    RegionTable::read_from_file(&path).map(Some).map_err(|err| {
+        HostError::Transpiler(format!(
+            "failed to read region table {}: {err}",
+            path.display()
+        ))
+    })
+}
+
 fn derive_text_path(bin_path: &Path) -> PathBuf {
     let mut text_path = bin_path.to_path_buf();
     text_path.set_extension("text");
@@ -311,13 +835,18 @@ fn read_u32_words(path: &Path) -> Result<Vec<u32>> {
 
 #[cfg(test)]
 mod tests {
-    use super::TranspilerRunnerBuilder;
-    use crate::runner::Runner;
+    use super::{at_exit_loop, TranspilerRunnerBuilder};
+    use crate::runner::{ExecutionOutcome, Runner};
+    use crate::snapshot::{SnapshotConfig, SnapshotPoint};
+    use crate::trace::{read_trace, TraceConfig};
     use std::path::Path;
 
     const MARKER_OPCODE: u32 = 0x7ff01073; // csrrw x0, 2047, x0
     const ADDI_OPCODE: u32 = 0x00100093; // addi x1, x0, 1
     const LOOP_OPCODE: u32 = 0x0000006f; // jal x0, 0
+    const LUI_OPCODE: u32 = 0x20000137; // lui x2, 0x20000
+    const STORE_OPCODE: u32 = 0x00112023; // sw x1, 0(x2)
+    const LOAD_OPCODE: u32 = 0x00012183; // lw x3, 0(x2)
 
     // TODO: Evaluate how low-level do we want tests to be
     #[test]
@@ -337,7 +866,8 @@ mod tests {
         let execution = runner.run(&[]).expect("run program");
         let markers = execution.cycle_markers.expect("cycle markers");
 
-        assert!(execution.reached_end);
+        assert_eq!(execution.outcome, ExecutionOutcome::Success);
+        assert_eq!(execution.input_words_consumed, 0);
         assert_eq!(execution.receipt.registers[1], 1);
         assert_eq!(markers.markers.len(), 2);
         assert!(markers.delegation_counter.is_empty());
@@ -346,6 +876,40 @@ mod tests {
         assert!(diff.delegations.is_empty());
     }
 
+    #[test]
+    fn exit_loop_is_found_by_pc() {
+        let text = [ADDI_OPCODE, LOOP_OPCODE];
+        assert!(!at_exit_loop(&text, 0));
+        assert!(at_exit_loop(&text, 4));
+        assert!(!at_exit_loop(&text, 8));
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn jit_run_exiting_on_last_budgeted_cycle_succeeds() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let bin_path = dir.path().join("app.bin");
+        let text_path = dir.path().join("app.text");
+        let program = [ADDI_OPCODE, ADDI_OPCODE, LOOP_OPCODE];
+        write_program(&bin_path, &program);
+        write_program(&text_path, &program);
+
+        for (cycles, outcome) in [
+            (1, ExecutionOutcome::CycleLimitExceeded),
+            (2, ExecutionOutcome::Success),
+        ] {
+            let runner = TranspilerRunnerBuilder::new(&bin_path)
+                .with_text_path(&text_path)
+                .with_cycles(cycles)
+                .with_jit()
+                .build()
+                .expect("build runner");
+            let execution = runner.run(&[]).expect("run program");
+            assert_eq!(execution.cycles_executed, cycles);
+            assert_eq!(execution.outcome, outcome, "budget of {cycles} cycles");
+        }
+    }
+
     #[cfg(target_arch = "x86_64")]
     #[test]
     fn jit_runs_do_not_collect_cycle_markers() {
@@ -366,6 +930,143 @@ mod tests {
 
         assert_eq!(execution.receipt.registers[1], 1);
         assert!(execution.cycle_markers.is_none());
+        assert!(execution.memory().is_none());
+    }
+
+    #[test]
+    fn resumed_snapshot_matches_straight_run() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let bin_path = dir.path().join("app.bin");
+        let snapshot_path = dir.path().join("app.snapshot");
+        let program = [
+            LUI_OPCODE,
+            ADDI_OPCODE,
+            STORE_OPCODE,
+            MARKER_OPCODE,
+            LOAD_OPCODE,
+            MARKER_OPCODE,
+            LOOP_OPCODE,
+        ];
+        write_program(&bin_path, &program);
+        write_program(&dir.path().join("app.text"), &program);
+
+        let straight = TranspilerRunnerBuilder::new(&bin_path)
+            .with_cycles(program.len())
+            .build()
+            .expect("build runner")
+            .run(&[])
+            .expect("run program");
+        let snapshotted = TranspilerRunnerBuilder::new(&bin_path)
+            .with_cycles(program.len())
+            .with_snapshot(SnapshotConfig::new(
+                &snapshot_path,
+                SnapshotPoint::Marker(0),
+            ))
+            .build()
+            .expect("build runner")
+            .run(&[])
+            .expect("run program");
+        let resumed = TranspilerRunnerBuilder::new(&bin_path)
+            .with_cycles(program.len())
+            .with_resume(&snapshot_path)
+            .build()
+            .expect("build runner")
+            .run(&[])
+            .expect("resume program");
+
+        assert_eq!(straight.outcome, ExecutionOutcome::Success);
+        assert_eq!(straight.receipt.registers[3], 1);
+        for execution in [&snapshotted, &resumed] {
+            assert_eq!(execution.outcome, straight.outcome);
+            assert_eq!(execution.cycles_executed, straight.cycles_executed);
+            assert_eq!(execution.receipt.registers, straight.receipt.registers);
+            assert_eq!(execution.receipt.output, straight.receipt.output);
+            assert_eq!(execution.cycle_markers, straight.cycle_markers);
+        }
+        let markers = resumed.cycle_markers.as_ref().expect("cycle markers");
+        assert_eq!(markers.markers.len(), 2);
+
+        let err = TranspilerRunnerBuilder::new(&bin_path)
+            .with_resume(&snapshot_path)
+            .build()
+            .expect("build runner")
+            .run(&[1])
+            .expect_err("different input words");
+        assert!(err.to_string().contains("input words differ"));
+    }
+
+    #[test]
+    fn traces_resumed_runs_in_run_cycles() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let bin_path = dir.path().join("app.bin");
+        let snapshot_path = dir.path().join("app.snapshot");
+        let trace_path = dir.path().join("trace.jsonl");
+        let program = [
+            ADDI_OPCODE,
+            MARKER_OPCODE,
+            ADDI_OPCODE,
+            ADDI_OPCODE,
+            ADDI_OPCODE,
+            LOOP_OPCODE,
+        ];
+        write_program(&bin_path, &program);
+        write_program(&dir.path().join("app.text"), &program);
+
+        TranspilerRunnerBuilder::new(&bin_path)
+            .with_cycles(program.len())
+            .with_snapshot(SnapshotConfig::new(
+                &snapshot_path,
+                SnapshotPoint::Marker(0),
+            ))
+            .build()
+            .expect("build runner")
+            .run(&[])
+            .expect("run program");
+        let mut trace = TraceConfig::new(&trace_path);
+        trace.cycles = Some(3..4);
+        let resumed = TranspilerRunnerBuilder::new(&bin_path)
+            .with_cycles(program.len())
+            .with_resume(&snapshot_path)
+            .with_trace(trace)
+            .build()
+            .expect("build runner")
+            .run(&[])
+            .expect("resume program");
+
+        assert_eq!(resumed.outcome, ExecutionOutcome::Success);
+        let entries = read_trace(&trace_path).expect("read trace");
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].cycle, entries[0].pc), (3, 12));
+    }
+
+    #[test]
+    fn keeps_final_memory_of_interpreter_runs() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let bin_path = dir.path().join("app.bin");
+        let program = [LUI_OPCODE, ADDI_OPCODE, STORE_OPCODE, LOOP_OPCODE];
+        write_program(&bin_path, &program);
+        write_program(&dir.path().join("app.text"), &program);
+
+        let runner = TranspilerRunnerBuilder::new(&bin_path).with_cycles(program.len());
+        let execution = runner.build().expect("build runner").run(&[]).expect("run");
+        assert!(execution.memory().is_none());
+
+        let execution = TranspilerRunnerBuilder::new(&bin_path)
+            .with_cycles(program.len())
+            .keep_memory()
+            .build()
+            .expect("build runner")
+            .run(&[])
+            .expect("run program");
+        let memory = execution.memory().expect("final memory");
+
+        assert_eq!(memory.word(0x2000_0000).expect("read word"), 1);
+        assert_eq!(memory.word(0).expect("read rom"), LUI_OPCODE);
+        assert_eq!(
+            memory.bytes(0x1fff_fffe..0x2000_0002).expect("read bytes"),
+            [0, 0, 1, 0]
+        );
+        assert!(memory.symbol_word("COUNTER").is_err());
     }
 
     fn write_program(path: &Path, program: &[u32]) {
@@ -0,0 +1,235 @@
+//! Snapshots of interpreter runs, taken at a cycle count or a cycle marker.
+//!
+//! A snapshot holds the guest `pc` and registers, every non-zero RAM page above the ROM region,
+//! the cycle markers and delegation counts collected so far, and the host side of guest I/O: the
+//! input words, how many of them the guest has read, pending oracle answers and everything
+//! decoded from guest writes so far. Snapshot files are encoded
+//! with [`AirbenderCodecV0`] and only resume the `app.bin` they were taken from.
+
+use crate::cycle_marker::CycleMarker;
+use crate::error::{HostError, Result};
+use crate::oracle::Oracle;
+use crate::runner::{GuestIo, GuestIoSnapshot};
+use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+use serde::{Deserialize, Serialize};
+use sha3::Digest;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const SNAPSHOT_VERSION: u32 = 1;
+const PAGE_WORDS: usize = 1024;
+const WORD_BYTES: usize = 4;
+
+/// Snapshot options for the transpiler runner.
+#[derive(Clone, Debug)]
+pub struct SnapshotConfig {
+    pub output: PathBuf,
+    pub at: SnapshotPoint,
+}
+
+impl SnapshotConfig {
+    /// Writes the snapshot taken at `at` to `output`.
+    pub fn new(output: impl AsRef<Path>, at: SnapshotPoint) -> Self {
+        Self {
+            output: output.as_ref().to_path_buf(),
+            at,
+        }
+    }
+}
+
+/// Where in a run the snapshot is taken.
+///
+/// A guest write that is still mid-frame at this point is finished first, so the snapshot may
+/// land a few cycles later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotPoint {
+    /// After this many cycles.
+    Cycle(usize),
+    /// Right after the run executes its cycle marker with this index, counting from zero.
+    Marker(usize),
+}
+
+/// Saved state of an interpreter run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct VmSnapshot {
+    version: u32,
+    /// Keccak256 of the `app.bin` words the run executed.
+    program_digest: [u8; 32],
+    pub(crate) pc: u32,
+    pub(crate) timestamp: u64,
+    pub(crate) registers: [u32; 32],
+    pages: Vec<RamPage>,
+    /// Markers and delegation counts of the run up to the snapshot point.
+    pub(crate) cycle_markers: CycleMarker,
+    io: GuestIoSnapshot,
+}
+
+/// Words of one RAM page with at least one non-zero word.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RamPage {
+    address: u32,
+    words: Vec<u32>,
+}
+
+impl VmSnapshot {
+    /// Capture a run of `bin_words`, reading the RAM in `memory` through `peek`.
+    pub(crate) fn capture(
+        bin_words: &[u32],
+        pc: u32,
+        timestamp: u64,
+        registers: [u32; 32],
+        memory: Range<usize>,
+        peek: impl Fn(u32) -> u32,
+        cycle_markers: CycleMarker,
+        io: GuestIoSnapshot,
+    ) -> Self {
+        let mut pages = Vec::new();
+        for page_start in memory.clone().step_by(PAGE_WORDS * WORD_BYTES) {
+            let page_end = (page_start + PAGE_WORDS * WORD_BYTES).min(memory.end);
+            let words: Vec<u32> = (page_start..page_end)
+                .step_by(WORD_BYTES)
+                .map(|address| peek(address as u32))
+                .collect();
+            if words.iter().any(|word| *word != 0) {
+                pages.push(RamPage {
+                    address: page_start as u32,
+                    words,
+                });
+            }
+        }
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            program_digest: program_digest(bin_words),
+            pc,
+            timestamp,
+            registers,
+            pages,
+            cycle_markers,
+            io,
+        }
+    }
+
+    /// Non-zero RAM words as `(address, word)` pairs.
+    pub(crate) fn ram_words(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.pages.iter().flat_map(|page| {
+            page.words
+                .iter()
+                .enumerate()
+                .filter(|(_, word)| **word != 0)
+                .map(|(index, word)| (page.address + (index * WORD_BYTES) as u32, *word))
+        })
+    }
+
+    /// Guest I/O for resuming, which must be given the same input words as the snapshotted run.
+    pub(crate) fn guest_io(
+        &self,
+        input_words: &[u32],
+        oracle: Option<Arc<dyn Oracle>>,
+        preamble: Arc<[u32]>,
+    ) -> Result<GuestIo> {
+        if self.io.input_words != input_words {
+            return Err(HostError::Transpiler(
+                "input words differ from the run the snapshot was taken from".to_string(),
+            ));
+        }
+        Ok(GuestIo::resume(self.io.clone(), oracle, preamble))
+    }
+
+    pub(crate) fn write_to_file(&self, path: &Path) -> Result<()> {
+        let bytes = AirbenderCodecV0::encode(self)?;
+        std::fs::write(path, bytes).map_err(|err| {
+            HostError::Transpiler(format!(
+                "failed to write snapshot {}: {err}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Load a snapshot and check that it was taken from a run of `bin_words`.
+    pub(crate) fn read_from_file(path: &Path, bin_words: &[u32]) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(|err| {
+            HostError::Transpiler(format!("failed to read snapshot {}: {err}", path.display()))
+        })?;
+        let snapshot: Self = AirbenderCodecV0::decode(&bytes)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(HostError::Transpiler(format!(
+                "snapshot {} has unsupported version {}",
+                path.display(),
+                snapshot.version
+            )));
+        }
+        if snapshot.program_digest != program_digest(bin_words) {
+            return Err(HostError::Transpiler(format!(
+                "snapshot {} was taken from a different app.bin",
+                path.display()
+            )));
+        }
+        Ok(snapshot)
+    }
+}
+
+fn program_digest(bin_words: &[u32]) -> [u8; 32] {
+    let mut hasher = sha3::Keccak256::new();
+    for word in bin_words {
+        hasher.update(word.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VmSnapshot;
+    use crate::cycle_marker::{CycleMarker, Mark};
+    use crate::runner::GuestIo;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn keeps_non_zero_pages_and_rejects_other_programs() {
+        let io = GuestIo::new(&[7, 8], None, Arc::from([]))
+            .snapshot()
+            .expect("idle guest io");
+        let peek = |address: u32| match address {
+            0x1000 => 5,
+            0x3ffc => 6,
+            _ => 0,
+        };
+        let markers = CycleMarker {
+            markers: vec![Mark {
+                cycles: 12,
+                delegations: HashMap::from([(0x7ca, 1)]),
+            }],
+            delegation_counter: HashMap::from([(0x7ca, 1)]),
+            ..CycleMarker::default()
+        };
+        let snapshot = VmSnapshot::capture(
+            &[1, 2],
+            0x40,
+            100,
+            [3; 32],
+            0..0x4000,
+            peek,
+            markers.clone(),
+            io,
+        );
+        assert_eq!(snapshot.pages.len(), 2);
+        assert_eq!(
+            snapshot.ram_words().collect::<Vec<_>>(),
+            [(0x1000, 5), (0x3ffc, 6)]
+        );
+
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("run.snapshot");
+        snapshot.write_to_file(&path).expect("write snapshot");
+        let loaded = VmSnapshot::read_from_file(&path, &[1, 2]).expect("read snapshot");
+        assert_eq!((loaded.pc, loaded.timestamp), (0x40, 100));
+        assert_eq!(loaded.cycle_markers, markers);
+        assert!(loaded.guest_io(&[7, 8], None, Arc::from([])).is_ok());
+        assert!(loaded.guest_io(&[7], None, Arc::from([])).is_err());
+
+        let err = VmSnapshot::read_from_file(&path, &[1, 3]).expect_err("different program");
+        assert!(err.to_string().contains("different app.bin"));
+    }
+}
@@ -0,0 +1,86 @@
+//! Guest symbol lookups against `app.elf`.
+
+use crate::error::{HostError, Result};
+use object::{Object, ObjectSymbol};
+use std::ops::Range;
+use std::path::Path;
+
+/// Function names and source lines of a guest image.
+///
+/// Function names come from the ELF symbol table; file and line need DWARF line info, which
+/// guests keep with `debug = "line-tables-only"` or more in their build profile.
+pub struct Symbolizer {
+    loader: addr2line::Loader,
+}
+
+impl Symbolizer {
+    /// Load symbols and line info from a guest ELF file.
+    pub fn load(elf_path: &Path) -> Result<Self> {
+        let loader = addr2line::Loader::new(elf_path).map_err(|err| {
+            HostError::Transpiler(format!(
+                "failed to load symbols from {}: {err}",
+                elf_path.display()
+            ))
+        })?;
+        Ok(Self { loader })
+    }
+
+    /// Demangled name of the function containing `pc`.
+    pub fn function(&self, pc: u32) -> Option<String> {
+        self.loader
+            .find_symbol(u64::from(pc))
+            .map(|name| addr2line::demangle_auto(name.into(), None).into_owned())
+    }
+
+    /// Source file and line of `pc`, when the image has line info for it.
+    pub fn location(&self, pc: u32) -> Option<(String, Option<u32>)> {
+        let location = self.loader.find_location(u64::from(pc)).ok()??;
+        Some((location.file?.to_string(), location.line))
+    }
+}
+
+/// Address range of the symbol `name` in a guest ELF file, matched mangled or demangled.
+pub(crate) fn find_symbol(elf_path: &Path, name: &str) -> Result<Range<u32>> {
+    let data = std::fs::read(elf_path).map_err(|err| {
+        HostError::Transpiler(format!("failed to read {}: {err}", elf_path.display()))
+    })?;
+    let file = object::File::parse(&*data).map_err(|err| {
+        HostError::Transpiler(format!("failed to parse {}: {err}", elf_path.display()))
+    })?;
+    let symbol = file
+        .symbols()
+        .find(|symbol| {
+            symbol.name().is_ok_and(|symbol_name| {
+                symbol_name == name || addr2line::demangle_auto(symbol_name.into(), None) == name
+            })
+        })
+        .ok_or_else(|| {
+            HostError::Transpiler(format!(
+                "symbol `{name}` not found in {}",
+                elf_path.display()
+            ))
+        })?;
+    let start = symbol.address() as u32;
+    Ok(start..start + symbol.size() as u32)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::find_symbol;
+
+    static SYMBOL_UNDER_TEST: [u32; 3] = [1, 2, 3];
+
+    #[test]
+    fn finds_demangled_symbols_with_their_size() {
+        std::hint::black_box(&SYMBOL_UNDER_TEST);
+        let exe = std::env::current_exe().expect("test executable");
+
+        let range = find_symbol(&exe, "airbender_host::symbols::tests::SYMBOL_UNDER_TEST")
+            .expect("find symbol");
+        assert_eq!(range.len(), 12);
+        let err = find_symbol(&exe, "no_such_symbol").expect_err("missing symbol");
+        assert!(err
+            .to_string()
+            .contains("symbol `no_such_symbol` not found"));
+    }
+}
@@ -0,0 +1,546 @@
+//! Instruction-level execution traces of transpiler runs.
+//!
+//! Each traced instruction is recorded with its `pc`, raw encoding, the register it wrote, and
+//! the memory or CSR access it made. Traces are written as JSON lines, one object per
+//! instruction, or in a compact binary form that starts with [`BINARY_TRACE_MAGIC`]:
+//!
+//! ```text
+//! cycle: u64, pc: u32, instruction: u32, flags: u8          (little-endian)
+//! flags & 0x01: register: u8, value: u32                    register write
+//! flags & 0x06: address: u32, width: u8, value: u32         memory read (0x02) or write (0x04)
+//! flags & 0x08: csr: u16, written: u32                      CSR access
+//! flags & 0x10: read: u32                                   value the CSR access returned
+//! ```
+
+use crate::disasm::{self, REGISTER_NAMES};
+use crate::error::{HostError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// First bytes of a binary trace file.
+pub const BINARY_TRACE_MAGIC: &[u8; 8] = b"ABTRACE\x01";
+
+const FLAG_REGISTER: u8 = 0x01;
+const FLAG_MEMORY_READ: u8 = 0x02;
+const FLAG_MEMORY_WRITE: u8 = 0x04;
+const FLAG_CSR: u8 = 0x08;
+const FLAG_CSR_READ: u8 = 0x10;
+
+/// Execution trace options for the transpiler runner.
+#[derive(Clone, Debug)]
+pub struct TraceConfig {
+    pub output: PathBuf,
+    pub format: TraceFormat,
+    /// Only instructions executed in this cycle window are recorded.
+    ///
+    /// Cycles count from the start of the run, including those before a resumed snapshot.
+    pub cycles: Option<Range<usize>>,
+    /// Only instructions at these addresses are recorded.
+    ///
+    /// Whether the `pc` is in range is only known one instruction at a time, so the runner
+    /// requires a cycle window with it to bound that stepping.
+    pub pc_range: Option<Range<u32>>,
+}
+
+impl TraceConfig {
+    /// Records every instruction to `output` as JSON lines.
+    pub fn new(output: impl AsRef<Path>) -> Self {
+        Self {
+            output: output.as_ref().to_path_buf(),
+            format: TraceFormat::Jsonl,
+            cycles: None,
+            pc_range: None,
+        }
+    }
+
+    fn records(&self, cycle: usize, pc: u32) -> bool {
+        self.cycles
+            .as_ref()
+            .is_none_or(|cycles| cycles.contains(&cycle))
+            && self
+                .pc_range
+                .as_ref()
+                .is_none_or(|range| range.contains(&pc))
+    }
+}
+
+/// On-disk encoding of a trace.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TraceFormat {
+    #[default]
+    Jsonl,
+    Binary,
+}
+
+/// One executed instruction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// Cycles executed before this instruction.
+    pub cycle: u64,
+    pub pc: u32,
+    pub instruction: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub register_write: Option<RegisterWrite>,
+    /// Load or store the instruction made. Memory that delegation circuits read or write
+    /// through CSR calls is not recorded here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryAccess>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csr: Option<CsrAccess>,
+}
+
+/// Value written to a general-purpose register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterWrite {
+    pub register: u8,
+    pub value: u32,
+}
+
+/// Load or store made by an instruction; `value` is the raw, zero-extended data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAccess {
+    pub kind: MemoryAccessKind,
+    pub address: u32,
+    pub width: u8,
+    pub value: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryAccessKind {
+    Read,
+    Write,
+}
+
+/// CSR access; `read` is missing when the result was discarded into `zero`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsrAccess {
+    pub csr: u16,
+    pub written: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read: Option<u32>,
+}
+
+impl TraceEntry {
+    /// Describes the instruction at `pc` from the registers around it and memory after it ran.
+    pub(crate) fn observe(
+        cycle: usize,
+        pc: u32,
+        instruction: u32,
+        before: &[u32; 32],
+        after: &[u32; 32],
+        read_word: impl Fn(u32) -> Option<u32>,
+    ) -> Self {
+        let rd = disasm::rd(instruction);
+        let register_write = (disasm::writes_rd(instruction) && rd != 0).then(|| RegisterWrite {
+            register: rd as u8,
+            value: after[rd],
+        });
+
+        let funct3 = disasm::funct3(instruction);
+        let base = before[disasm::rs1(instruction)];
+        let memory = match disasm::opcode(instruction) {
+            disasm::OPCODE_LOAD => {
+                let address = base.wrapping_add_signed(disasm::imm_i(instruction));
+                let width = disasm::access_width(funct3);
+                Some(MemoryAccess {
+                    kind: MemoryAccessKind::Read,
+                    address,
+                    width,
+                    value: read_value(&read_word, address, width),
+                })
+            }
+            disasm::OPCODE_STORE => {
+                let width = disasm::access_width(funct3);
+                Some(MemoryAccess {
+                    kind: MemoryAccessKind::Write,
+                    address: base.wrapping_add_signed(disasm::imm_s(instruction)),
+                    width,
+                    value: before[disasm::rs2(instruction)] & width_mask(width),
+                })
+            }
+            _ => None,
+        };
+
+        let csr =
+            (disasm::opcode(instruction) == disasm::OPCODE_SYSTEM && funct3 != 0).then(|| {
+                CsrAccess {
+                    csr: (instruction >> 20) as u16,
+                    // `csrr*i` forms carry the operand in the `rs1` field.
+                    written: if funct3 & 0x4 == 0 {
+                        base
+                    } else {
+                        disasm::rs1(instruction) as u32
+                    },
+                    read: (rd != 0).then(|| after[rd]),
+                }
+            });
+
+        Self {
+            cycle: cycle as u64,
+            pc,
+            instruction,
+            register_write,
+            memory,
+            csr,
+        }
+    }
+
+    /// Assembly text of the instruction.
+    pub fn disassembly(&self) -> String {
+        disasm::disassemble(self.instruction)
+    }
+}
+
+fn width_mask(width: u8) -> u32 {
+    u32::MAX >> (32 - 8 * u32::from(width))
+}
+
+fn read_value(read_word: &impl Fn(u32) -> Option<u32>, address: u32, width: u8) -> u32 {
+    let shift = 8 * (address % 4);
+    let low = read_word(address & !3).unwrap_or_default() >> shift;
+    // Misaligned accesses may spill into the next word.
+    let high = if shift + 8 * u32::from(width) > 32 {
+        read_word((address & !3).wrapping_add(4)).unwrap_or_default() << (32 - shift)
+    } else {
+        0
+    };
+    (low | high) & width_mask(width)
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>10}  {:#010x}  {:<28}",
+            self.cycle,
+            self.pc,
+            self.disassembly()
+        )?;
+        if let Some(write) = &self.register_write {
+            let name = REGISTER_NAMES[usize::from(write.register)];
+            write!(f, "  {name} = {:#010x}", write.value)?;
+        }
+        if let Some(access) = &self.memory {
+            let arrow = match access.kind {
+                MemoryAccessKind::Read => "->",
+                MemoryAccessKind::Write => "<-",
+            };
+            write!(
+                f,
+                "  [{:#010x}] {arrow} {:#x}",
+                access.address, access.value
+            )?;
+        }
+        if let Some(access) = &self.csr {
+            write!(f, "  csr {:#x} <- {:#x}", access.csr, access.written)?;
+            if let Some(read) = access.read {
+                write!(f, " -> {read:#x}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Streams trace entries to the configured file.
+pub(crate) struct TraceWriter<'a> {
+    config: &'a TraceConfig,
+    out: BufWriter<File>,
+}
+
+impl<'a> TraceWriter<'a> {
+    pub(crate) fn create(config: &'a TraceConfig) -> Result<Self> {
+        let file = File::create(&config.output).map_err(|err| {
+            HostError::Transpiler(format!(
+                "failed to create trace {}: {err}",
+                config.output.display()
+            ))
+        })?;
+        let mut out = BufWriter::new(file);
+        if config.format == TraceFormat::Binary {
+            out.write_all(BINARY_TRACE_MAGIC)?;
+        }
+        Ok(Self { config, out })
+    }
+
+    /// Whether the instruction at `pc` in `cycle` should be observed at all.
+    pub(crate) fn records(&self, cycle: usize, pc: u32) -> bool {
+        self.config.records(cycle, pc)
+    }
+
+    pub(crate) fn write(&mut self, entry: &TraceEntry) -> Result<()> {
+        match self.config.format {
+            TraceFormat::Jsonl => {
+                let line = serde_json::to_string(&JsonEntry {
+                    entry,
+                    asm: entry.disassembly(),
+                })
+                .map_err(|err| HostError::Transpiler(format!("failed to encode trace: {err}")))?;
+                writeln!(self.out, "{line}")?;
+            }
+            TraceFormat::Binary => self.out.write_all(&encode(entry))?,
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// JSON line of one entry; the disassembly is for readers and ignored when loading.
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    #[serde(flatten)]
+    entry: &'a TraceEntry,
+    asm: String,
+}
+
+fn encode(entry: &TraceEntry) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(32);
+    let mut flags = 0;
+    bytes.extend_from_slice(&entry.cycle.to_le_bytes());
+    bytes.extend_from_slice(&entry.pc.to_le_bytes());
+    bytes.extend_from_slice(&entry.instruction.to_le_bytes());
+    bytes.push(0);
+    if let Some(write) = &entry.register_write {
+        flags |= FLAG_REGISTER;
+        bytes.push(write.register);
+        bytes.extend_from_slice(&write.value.to_le_bytes());
+    }
+    if let Some(access) = &entry.memory {
+        flags |= match access.kind {
+            MemoryAccessKind::Read => FLAG_MEMORY_READ,
+            MemoryAccessKind::Write => FLAG_MEMORY_WRITE,
+        };
+        bytes.extend_from_slice(&access.address.to_le_bytes());
+        bytes.push(access.width);
+        bytes.extend_from_slice(&access.value.to_le_bytes());
+    }
+    if let Some(access) = &entry.csr {
+        flags |= FLAG_CSR;
+        bytes.extend_from_slice(&access.csr.to_le_bytes());
+        bytes.extend_from_slice(&access.written.to_le_bytes());
+        if let Some(read) = access.read {
+            flags |= FLAG_CSR_READ;
+            bytes.extend_from_slice(&read.to_le_bytes());
+        }
+    }
+    bytes[16] = flags;
+    bytes
+}
+
+/// Cursor over the records of a binary trace.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl Decoder<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, rest) = self.bytes.split_first_chunk::<N>()?;
+        self.bytes = rest;
+        Some(*head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn entry(&mut self) -> Option<TraceEntry> {
+        let cycle = u64::from_le_bytes(self.take()?);
+        let (pc, instruction, flags) = (self.u32()?, self.u32()?, self.u8()?);
+        let register_write = match flags & FLAG_REGISTER {
+            0 => None,
+            _ => Some(RegisterWrite {
+                register: self.u8()?,
+                value: self.u32()?,
+            }),
+        };
+        let kind = match flags & (FLAG_MEMORY_READ | FLAG_MEMORY_WRITE) {
+            0 => None,
+            FLAG_MEMORY_READ => Some(MemoryAccessKind::Read),
+            _ => Some(MemoryAccessKind::Write),
+        };
+        let memory = match kind {
+            None => None,
+            Some(kind) => Some(MemoryAccess {
+                kind,
+                address: self.u32()?,
+                width: self.u8()?,
+                value: self.u32()?,
+            }),
+        };
+        let csr = match flags & FLAG_CSR {
+            0 => None,
+            _ => Some(CsrAccess {
+                csr: self.u16()?,
+                written: self.u32()?,
+                read: match flags & FLAG_CSR_READ {
+                    0 => None,
+                    _ => Some(self.u32()?),
+                },
+            }),
+        };
+        Some(TraceEntry {
+            cycle,
+            pc,
+            instruction,
+            register_write,
+            memory,
+            csr,
+        })
+    }
+}
+
+/// Read a trace written by the transpiler runner, in either format.
+pub fn read_trace(path: &Path) -> Result<Vec<TraceEntry>> {
+    let invalid = |reason: String| {
+        HostError::Transpiler(format!("invalid trace {}: {reason}", path.display()))
+    };
+    let mut reader = BufReader::new(File::open(path).map_err(|err| invalid(err.to_string()))?);
+    if reader.fill_buf()?.starts_with(BINARY_TRACE_MAGIC) {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let mut decoder = Decoder {
+            bytes: &bytes[BINARY_TRACE_MAGIC.len()..],
+        };
+        let mut entries = Vec::new();
+        while !decoder.bytes.is_empty() {
+            let entry = decoder
+                .entry()
+                .ok_or_else(|| invalid("truncated record".to_string()))?;
+            entries.push(entry);
+        }
+        return Ok(entries);
+    }
+
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .map_err(|err| invalid(format!("line {}: {err}", index + 1)))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registers(values: &[(usize, u32)]) -> [u32; 32] {
+        let mut registers = [0; 32];
+        for &(index, value) in values {
+            registers[index] = value;
+        }
+        registers
+    }
+
+    #[test]
+    fn observes_register_memory_and_csr_effects() {
+        let memory = |address: u32| (address == 0x1008).then_some(0xdead_beef);
+
+        // lw a0, 8(a0)
+        let before = registers(&[(10, 0x1000)]);
+        let after = registers(&[(10, 0xdead_beef)]);
+        let load = TraceEntry::observe(7, 0x40, 0x0085_2503, &before, &after, memory);
+        assert_eq!(
+            load.register_write,
+            Some(RegisterWrite {
+                register: 10,
+                value: 0xdead_beef
+            })
+        );
+        assert_eq!(
+            load.memory,
+            Some(MemoryAccess {
+                kind: MemoryAccessKind::Read,
+                address: 0x1008,
+                width: 4,
+                value: 0xdead_beef
+            })
+        );
+
+        // sw a0, -4(sp)
+        let before = registers(&[(2, 0x2000), (10, 5)]);
+        let store = TraceEntry::observe(8, 0x44, 0xfea1_2e23, &before, &before, memory);
+        assert_eq!(store.register_write, None);
+        assert_eq!(store.memory.map(|access| access.address), Some(0x1ffc));
+        assert_eq!(store.memory.map(|access| access.value), Some(5));
+
+        // csrrw a0, 0x7c0, zero
+        let after = registers(&[(10, 42)]);
+        let csr = TraceEntry::observe(9, 0x48, 0x7c00_1573, &before, &after, memory);
+        assert_eq!(
+            csr.csr,
+            Some(CsrAccess {
+                csr: 0x7c0,
+                written: 0,
+                read: Some(42)
+            })
+        );
+        assert_eq!(
+            csr.to_string(),
+            "         9  0x00000048  csrrw a0, 0x7c0, zero         a0 = 0x0000002a  csr 0x7c0 <- 0x0 -> 0x2a"
+        );
+    }
+
+    #[test]
+    fn roundtrips_both_formats() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let memory = |_| Some(0x1234_5678);
+        let before = registers(&[(1, 0x1000), (2, 0x2000), (10, 9)]);
+        let after = registers(&[(1, 0x1000), (2, 0x2000), (10, 0x78)]);
+        let entries = vec![
+            TraceEntry::observe(0, 0x0, 0x0000_0013, &before, &before, memory),
+            // lbu a0, 0(ra)
+            TraceEntry::observe(1, 0x4, 0x0000_c503, &before, &after, memory),
+            // sb a0, 1(sp)
+            TraceEntry::observe(2, 0x8, 0x00a1_00a3, &after, &after, memory),
+            // csrrw zero, 0x7ff, zero
+            TraceEntry::observe(3, 0xc, 0x7ff0_1073, &after, &after, memory),
+        ];
+        assert_eq!(entries[1].memory.map(|access| access.value), Some(0x78));
+
+        for format in [TraceFormat::Jsonl, TraceFormat::Binary] {
+            let config = TraceConfig {
+                format,
+                ..TraceConfig::new(dir.path().join("trace"))
+            };
+            let mut writer = TraceWriter::create(&config).expect("create trace");
+            for entry in &entries {
+                writer.write(entry).expect("write entry");
+            }
+            writer.finish().expect("finish trace");
+            assert_eq!(read_trace(&config.output).expect("read trace"), entries);
+        }
+    }
+
+    #[test]
+    fn filters_by_cycle_window_and_pc_range() {
+        let config = TraceConfig {
+            cycles: Some(10..20),
+            pc_range: Some(0x100..0x200),
+            ..TraceConfig::new("trace.jsonl")
+        };
+        assert!(config.records(10, 0x100));
+        assert!(!config.records(20, 0x100));
+        assert!(!config.records(15, 0x200));
+    }
+}
@@ -1,10 +1,13 @@
 use crate::error::{HostError, Result};
+use crate::guest_env::GuestEnv;
+use crate::journal::journal_digest;
 use crate::proof::{hash_app_bin, hash_input_words, Proof, RealProof};
 use crate::prover::ProverLevel;
 use crate::vk::{
-    compute_unified_vk, compute_unrolled_vk, verify_proof, verify_unrolled_proof, UnifiedVk,
-    UnrolledVk,
+    compute_unified_vk, compute_unrolled_vk, output_mismatch, verify_proof, verify_unrolled_proof,
+    UnifiedVk, UnrolledVk,
 };
+use airbender_core::binding::{bind_output, input_digest_words, input_digest_words_with_preamble};
 use airbender_core::guest::Commit;
 use std::path::{Path, PathBuf};
 
@@ -40,6 +43,9 @@ pub struct RealUnrolledVerificationKey {
 pub struct VerificationRequest<'a> {
     expected_output: Option<&'a dyn Commit>,
     expected_input_words: Option<&'a [u32]>,
+    expected_journal: Option<&'a [u8]>,
+    expected_guest_env: Option<&'a GuestEnv>,
+    bind_inputs: bool,
 }
 
 impl<'a> VerificationRequest<'a> {
@@ -57,6 +63,27 @@ impl<'a> VerificationRequest<'a> {
         self
     }
 
+    /// Require the committed output to be the digest of `expected_journal`.
+    pub fn with_expected_journal(mut self, expected_journal: &'a [u8]) -> Self {
+        self.expected_journal = Some(expected_journal);
+        self
+    }
+
+    /// Expect a `std` guest run with `guest_env`, whose input digest also binds its arguments,
+    /// environment variables and stdin. Use `GuestEnv::default()` for a `std` guest run
+    /// without one.
+    pub fn with_expected_guest_env(mut self, guest_env: &'a GuestEnv) -> Self {
+        self.expected_guest_env = Some(guest_env);
+        self
+    }
+
+    /// Expect the output committed by a guest using `#[airbender::main(bind_inputs)]`, bound to
+    /// the expected input words.
+    pub fn with_input_binding(mut self) -> Self {
+        self.bind_inputs = true;
+        self
+    }
+
     pub fn real(expected_output: &'a dyn Commit) -> Self {
         Self::empty().with_expected_output(expected_output)
     }
@@ -67,13 +94,71 @@ impl<'a> VerificationRequest<'a> {
             .with_expected_output(expected_output)
     }
 
-    fn expected_output(self) -> Option<&'a dyn Commit> {
-        self.expected_output
+    /// Resolve the expected output words from the expected output and journal.
+    fn expected_output_words(self) -> Result<Option<[u32; 8]>> {
+        let output_words = self.expected_output.map(|output| output.commit_words());
+        let journal_words = self.expected_journal.map(journal_digest);
+        match (output_words, journal_words) {
+            (Some(output), Some(journal)) if output != journal => {
+                Err(HostError::Verification(format!(
+                    "expected output {output:?} does not match expected journal digest {journal:?}"
+                )))
+            }
+            (output, journal) => Ok(output.or(journal)),
+        }
     }
 
     fn expected_input_words(self) -> Option<&'a [u32]> {
         self.expected_input_words
     }
+
+    /// Expected output words bound to the expected input words, if input binding is requested.
+    fn expected_bound_output_words(self) -> Result<Option<[u32; 8]>> {
+        if !self.bind_inputs {
+            return Ok(None);
+        }
+        let input_words = self.expected_input_words.ok_or_else(|| {
+            HostError::Verification("input binding requires expected input words".to_string())
+        })?;
+        let output_words = self.expected_output_words()?.ok_or_else(|| {
+            HostError::Verification("input binding requires expected output or journal".to_string())
+        })?;
+        let input_digest = match self.expected_guest_env {
+            Some(guest_env) => {
+                input_digest_words_with_preamble(input_words, &guest_env.preamble_words()?)
+            }
+            None => input_digest_words(input_words),
+        };
+        Ok(Some(bind_output(&output_words, &input_digest)))
+    }
+
+    /// Words the guest is expected to commit: the bound output with input binding, otherwise
+    /// the plain expected output.
+    fn expected_committed_words(self) -> Result<Option<[u32; 8]>> {
+        match self.expected_bound_output_words()? {
+            Some(words) => Ok(Some(words)),
+            None => self.expected_output_words(),
+        }
+    }
+}
+
+/// Compute the output committed by a guest using `#[airbender::main(bind_inputs)]`.
+pub fn bound_output(expected_output: &dyn Commit, input_words: &[u32]) -> [u32; 8] {
+    bind_output(
+        &expected_output.commit_words(),
+        &input_digest_words(input_words),
+    )
+}
+
+/// Compute the output committed by a `std` guest using `#[airbender::main(bind_inputs)]`
+/// that ran with `guest_env`.
+pub fn bound_output_with_env(
+    expected_output: &dyn Commit,
+    input_words: &[u32],
+    guest_env: &GuestEnv,
+) -> Result<[u32; 8]> {
+    let input_digest = input_digest_words_with_preamble(input_words, &guest_env.preamble_words()?);
+    Ok(bind_output(&expected_output.commit_words(), &input_digest))
 }
 
 /// Verifier interface shared by dev and real verifiers.
@@ -189,15 +274,23 @@ impl Verifier for DevVerifier {
             ));
         }
 
-        let expected_output = request.expected_output().ok_or_else(|| {
-            HostError::Verification("dev verification requires expected output".to_string())
+        let expected_words = request.expected_committed_words()?.ok_or_else(|| {
+            HostError::Verification(
+                "dev verification requires expected output or journal".to_string(),
+            )
         })?;
-        let expected_words = expected_output.commit_words();
         if proof.receipt.output != expected_words {
-            return Err(HostError::Verification(format!(
-                "public output mismatch: expected {expected_words:?}, got {:?}",
-                proof.receipt.output
-            )));
+            // A bound output is a digest, which the expected output type cannot describe.
+            let expected_output: &dyn Commit = if request.bind_inputs {
+                &expected_words
+            } else {
+                request.expected_output.unwrap_or(&expected_words)
+            };
+            return Err(output_mismatch(
+                expected_output,
+                &expected_words,
+                &proof.receipt.output,
+            ));
         }
 
         Ok(())
@@ -248,11 +341,20 @@ impl Verifier for RealVerifier {
         vk: &VerificationKey,
         request: VerificationRequest<'_>,
     ) -> Result<()> {
-        if request.expected_input_words().is_some() {
+        if request.expected_input_words.is_some() && !request.bind_inputs {
             return Err(HostError::Verification(
-                "real verifier cannot validate input words".to_string(),
+                "real verifier can only check input words bound by the guest; use `with_input_binding`"
+                    .to_string(),
             ));
         }
+        let expected_words = request.expected_output_words()?;
+        let bound_words = request.expected_bound_output_words()?;
+        let expected_output = match &bound_words {
+            Some(words) => Some(words as &dyn Commit),
+            None => request
+                .expected_output
+                .or(expected_words.as_ref().map(|words| words as &dyn Commit)),
+        };
 
         let proof = match proof {
             Proof::Real(proof) => proof,
@@ -267,12 +369,7 @@ impl Verifier for RealVerifier {
             (
                 ProverLevel::RecursionUnified,
                 VerificationKey::RealUnified(RealUnifiedVerificationKey { vk }),
-            ) => verify_proof(
-                proof.inner(),
-                vk,
-                Some(self.app_bin_hash),
-                request.expected_output(),
-            ),
+            ) => verify_proof(proof.inner(), vk, Some(self.app_bin_hash), expected_output),
             (
                 ProverLevel::Base | ProverLevel::RecursionUnrolled,
                 VerificationKey::RealUnrolled(RealUnrolledVerificationKey { level, vk }),
@@ -290,7 +387,7 @@ impl Verifier for RealVerifier {
                     vk,
                     proof.level(),
                     Some(self.app_bin_hash),
-                    request.expected_output(),
+                    expected_output,
                 )
             }
             (_, VerificationKey::Dev(_)) => Err(HostError::Verification(
@@ -381,3 +478,137 @@ fn resolve_app_bin_path(path: &Path) -> Result<PathBuf> {
         path.display()
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        bound_output, bound_output_with_env, DevVerifierBuilder, VerificationRequest, Verifier,
+    };
+    use crate::guest_env::GuestEnv;
+    use crate::journal::journal_digest;
+    use crate::proof::{hash_app_bin, hash_input_words, DevProof, Proof};
+    use crate::receipt::Receipt;
+    use airbender_core::guest::Commit;
+    use airbender_core::journal::encode_entry;
+
+    #[test]
+    fn expected_journal_resolves_to_digest_words() {
+        let journal = encode_entry(b"entry");
+        let request = VerificationRequest::empty().with_expected_journal(&journal);
+        assert_eq!(
+            request.expected_output_words().expect("output words"),
+            Some(journal_digest(&journal))
+        );
+    }
+
+    #[test]
+    fn expected_journal_must_match_expected_output() {
+        let journal = encode_entry(b"entry");
+        let output = 7u32;
+        let err = VerificationRequest::empty()
+            .with_expected_output(&output)
+            .with_expected_journal(&journal)
+            .expected_output_words()
+            .expect_err("conflicting expectations");
+        assert!(err
+            .to_string()
+            .contains("does not match expected journal digest"));
+    }
+
+    #[test]
+    fn expected_input_words_bind_expected_output() {
+        let input_words = [1u32, 2, 3];
+        let request = VerificationRequest::dev(&input_words, &42u32);
+        assert_eq!(
+            request
+                .expected_bound_output_words()
+                .expect("unbound request"),
+            None
+        );
+        let request = request.with_input_binding();
+        assert_eq!(
+            request
+                .expected_bound_output_words()
+                .expect("bound output words"),
+            Some(bound_output(&42u32, &input_words))
+        );
+        assert_ne!(
+            bound_output(&42u32, &input_words),
+            bound_output(&42u32, &[1])
+        );
+    }
+
+    #[test]
+    fn guest_env_stdin_is_bound() {
+        let input_words = [1u32, 2, 3];
+        let env = GuestEnv::new().with_stdin("first");
+        let request = VerificationRequest::dev(&input_words, &42u32)
+            .with_input_binding()
+            .with_expected_guest_env(&env);
+        let bound = request
+            .expected_bound_output_words()
+            .expect("bound output words")
+            .expect("input binding requested");
+
+        assert_eq!(
+            bound,
+            bound_output_with_env(&42u32, &input_words, &env).expect("bound output")
+        );
+        assert_ne!(bound, bound_output(&42u32, &input_words));
+        let other_env = GuestEnv::new().with_stdin("second");
+        assert_ne!(
+            bound,
+            bound_output_with_env(&42u32, &input_words, &other_env).expect("bound output")
+        );
+    }
+
+    #[test]
+    fn input_binding_requires_expected_output() {
+        let input_words = [1u32];
+        let err = VerificationRequest::empty()
+            .with_expected_input_words(&input_words)
+            .with_input_binding()
+            .expected_bound_output_words()
+            .expect_err("missing expected output");
+        assert_eq!(
+            err.to_string(),
+            "verification error: input binding requires expected output or journal"
+        );
+    }
+
+    #[test]
+    fn dev_verifier_compares_against_one_output_form() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let app_bin = dir.path().join("app.bin");
+        std::fs::write(&app_bin, [0x13, 0, 0, 0]).expect("write app.bin");
+        let verifier = DevVerifierBuilder::new(&app_bin)
+            .build()
+            .expect("build verifier");
+        let vk = verifier.generate_vk().expect("generate vk");
+
+        let input_words = [1u32, 2, 3];
+        let plain = 42u32.commit_words();
+        let bound = bound_output(&42u32, &input_words);
+        let proof = |output| {
+            Proof::Dev(DevProof {
+                app_bin_hash: hash_app_bin(&app_bin).expect("hash app.bin"),
+                input_words_hash: hash_input_words(&input_words),
+                receipt: Receipt {
+                    registers: [0; 32],
+                    output,
+                    output_extended: [0; 16],
+                },
+                cycles: 1,
+            })
+        };
+        let unbound_request = VerificationRequest::dev(&input_words, &42u32);
+        let bound_request = unbound_request.with_input_binding();
+
+        assert!(verifier.verify(&proof(plain), &vk, unbound_request).is_ok());
+        assert!(verifier.verify(&proof(plain), &vk, bound_request).is_err());
+        assert!(verifier.verify(&proof(bound), &vk, bound_request).is_ok());
+        assert!(verifier
+            .verify(&proof(bound), &vk, unbound_request)
+            .is_err());
+    }
+}
@@ -199,14 +199,36 @@ fn verify_expected_output(
     actual_words.copy_from_slice(&verifier_output[..8]);
 
     if expected_words != actual_words {
-        return Err(HostError::Verification(format!(
-            "public output mismatch: expected {expected_words:?}, got {actual_words:?}"
-        )));
+        return Err(output_mismatch(
+            expected_output,
+            &expected_words,
+            &actual_words,
+        ));
     }
 
     Ok(())
 }
 
+/// Build an output mismatch error, describing both sides with the expected value's layout.
+pub(crate) fn output_mismatch(
+    expected_output: &dyn Commit,
+    expected_words: &[u32; 8],
+    actual_words: &[u32; 8],
+) -> HostError {
+    let describe = |words: &[u32; 8]| {
+        let mut description = String::new();
+        match expected_output.fmt_words(words, &mut description) {
+            Ok(()) => description,
+            Err(_) => format!("{words:?}"),
+        }
+    };
+    HostError::Verification(format!(
+        "public output mismatch: expected {}, got {}",
+        describe(expected_words),
+        describe(actual_words)
+    ))
+}
+
 fn verify_app_bin_hash(
     expected_app_bin_hash: Option<[u8; 32]>,
     actual_app_bin_hash: [u8; 32],
@@ -285,4 +307,28 @@ mod tests {
             .expect_err("mismatching output must fail verification");
         assert!(err.to_string().contains("public output mismatch"));
     }
+
+    #[test]
+    fn verify_expected_output_mismatch_names_fields() {
+        #[derive(crate::Commit)]
+        #[commit(crate = crate)]
+        struct Output {
+            count: u32,
+            done: bool,
+        }
+
+        let mut verifier_output = [0u32; 16];
+        verifier_output[0] = 3;
+        let expected = Output {
+            count: 2,
+            done: true,
+        };
+
+        let err = verify_expected_output(Some(&expected), verifier_output)
+            .expect_err("mismatching output must fail verification");
+        assert_eq!(
+            err.to_string(),
+            "verification error: public output mismatch: expected Output { count: 2, done: true }, got Output { count: 3, done: false }"
+        );
+    }
 }
@@ -0,0 +1,166 @@
+//! `#[derive(Commit)]` implementation.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Fields, LitStr};
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "derive(Commit) does not support generic types",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.ident.span(),
+            "derive(Commit) is only supported for structs",
+        ));
+    };
+
+    let krate = crate_path(&input)?;
+    let name = &input.ident;
+    let field = quote!(#krate::CommitField);
+
+    let types: Vec<_> = data.fields.iter().map(|f| &f.ty).collect();
+    let accessors: Vec<TokenStream> = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                let ident = f.ident.as_ref().expect("named field");
+                quote!(#ident)
+            })
+            .collect(),
+        Fields::Unnamed(fields) => (0..fields.unnamed.len())
+            .map(|idx| {
+                let idx = syn::Index::from(idx);
+                quote!(#idx)
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    // Field `i` occupies `starts[i]..starts[i] + WORDS` in the packed layout.
+    let starts: Vec<TokenStream> = (0..types.len())
+        .map(|idx| {
+            let before = &types[..idx];
+            quote!(0usize #(+ <#before as #field>::WORDS)*)
+        })
+        .collect();
+    let bindings: Vec<_> = (0..types.len())
+        .map(|idx| format_ident!("__field{idx}"))
+        .collect();
+
+    let construct = match &data.fields {
+        Fields::Named(_) => quote!(Self { #(#accessors: #bindings),* }),
+        Fields::Unnamed(_) => quote!(Self(#(#bindings),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let name_str = name.to_string();
+    let fmt_fields = match &data.fields {
+        Fields::Named(_) if types.is_empty() => quote!(out.write_str(#name_str)),
+        Fields::Named(_) => {
+            let labels = accessors.iter().enumerate().map(|(idx, accessor)| {
+                let sep = if idx == 0 { " { " } else { ", " };
+                format!("{sep}{accessor}: ")
+            });
+            quote! {
+                out.write_str(#name_str)?;
+                #(
+                    out.write_str(#labels)?;
+                    <#types as #field>::fmt_field(&words[#starts..#starts + <#types as #field>::WORDS], out)?;
+                )*
+                out.write_str(" }")
+            }
+        }
+        Fields::Unnamed(_) => {
+            let seps = (0..types.len()).map(|idx| if idx == 0 { "" } else { ", " });
+            quote! {
+                out.write_str(#name_str)?;
+                out.write_str("(")?;
+                #(
+                    out.write_str(#seps)?;
+                    <#types as #field>::fmt_field(&words[#starts..#starts + <#types as #field>::WORDS], out)?;
+                )*
+                out.write_str(")")
+            }
+        }
+        Fields::Unit => quote!(out.write_str(#name_str)),
+    };
+
+    let overflow_message = LitStr::new(
+        &format!("`{name_str}` does not fit into 8 output words"),
+        name.span(),
+    );
+
+    Ok(quote! {
+        impl #field for #name {
+            const WORDS: usize = 0usize #(+ <#types as #field>::WORDS)*;
+
+            fn write_words(&self, words: &mut [u32]) {
+                #(
+                    <#types as #field>::write_words(
+                        &self.#accessors,
+                        &mut words[#starts..#starts + <#types as #field>::WORDS],
+                    );
+                )*
+            }
+
+            fn read_words(words: &[u32]) -> Self {
+                #(
+                    let #bindings = <#types as #field>::read_words(
+                        &words[#starts..#starts + <#types as #field>::WORDS],
+                    );
+                )*
+                #construct
+            }
+
+            fn fmt_field(
+                words: &[u32],
+                out: &mut dyn ::core::fmt::Write,
+            ) -> ::core::fmt::Result {
+                #fmt_fields
+            }
+        }
+
+        impl #krate::Commit for #name {
+            fn commit_words(&self) -> [u32; 8] {
+                let mut words = [0u32; 8];
+                <Self as #field>::write_words(self, &mut words[..<Self as #field>::WORDS]);
+                words
+            }
+
+            fn fmt_words(
+                &self,
+                words: &[u32; 8],
+                out: &mut dyn ::core::fmt::Write,
+            ) -> ::core::fmt::Result {
+                <Self as #field>::fmt_field(&words[..<Self as #field>::WORDS], out)
+            }
+        }
+
+        const _: () = assert!(<#name as #field>::WORDS <= 8, #overflow_message);
+    })
+}
+
+/// Resolve the path exporting `Commit` and `CommitField` (`#[commit(crate = <path>)]`).
+fn crate_path(input: &DeriveInput) -> syn::Result<syn::Path> {
+    let mut krate = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("commit"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                krate = Some(meta.value()?.parse::<syn::Path>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported argument; expected `crate = <path>`"))
+            }
+        })?;
+    }
+    Ok(krate.unwrap_or_else(|| syn::parse_quote!(::airbender::guest)))
+}
@@ -1,46 +1,63 @@
 #![doc = include_str!("../README.md")]
 
+mod commit;
+
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input,
     spanned::Spanned,
-    Error, ItemFn, ReturnType, Token, Type,
+    DeriveInput, Error, FnArg, ItemFn, LitStr, Pat, ReturnType, Token, Type,
 };
 
 struct MainArgs {
     allocator_init: Option<syn::Path>,
+    bind_inputs: bool,
 }
 
 impl Parse for MainArgs {
     fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
-        if input.is_empty() {
-            return Ok(Self {
-                allocator_init: None,
-            });
-        }
+        let mut args = Self {
+            allocator_init: None,
+            bind_inputs: false,
+        };
 
-        let key: syn::Ident = input.parse()?;
-        if key != "allocator_init" {
-            return Err(Error::new(
-                key.span(),
-                "unsupported argument; expected `allocator_init = <path>`",
-            ));
-        }
-        input.parse::<Token![=]>()?;
-        let allocator_init = input.parse::<syn::Path>()?;
-
-        if !input.is_empty() {
-            return Err(Error::new(
-                input.span(),
-                "unexpected trailing tokens in `airbender::main` arguments",
-            ));
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            if key == "allocator_init" {
+                if args.allocator_init.is_some() {
+                    return Err(Error::new(
+                        key.span(),
+                        "duplicate `allocator_init` argument",
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                args.allocator_init = Some(input.parse::<syn::Path>()?);
+            } else if key == "bind_inputs" {
+                if args.bind_inputs {
+                    return Err(Error::new(key.span(), "duplicate `bind_inputs` argument"));
+                }
+                args.bind_inputs = true;
+            } else {
+                return Err(Error::new(
+                    key.span(),
+                    "unsupported argument; expected `allocator_init = <path>` or `bind_inputs`",
+                ));
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            if input.parse::<Token![,]>().is_err() {
+                return Err(Error::new(
+                    input.span(),
+                    "unexpected trailing tokens in `airbender::main` arguments",
+                ));
+            }
         }
 
-        Ok(Self {
-            allocator_init: Some(allocator_init),
-        })
+        Ok(args)
     }
 }
 
@@ -48,14 +65,6 @@ impl Parse for MainArgs {
 pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as MainArgs);
     let input = parse_macro_input!(item as ItemFn);
-    if !input.sig.inputs.is_empty() {
-        return syn::Error::new(
-            input.sig.inputs.span(),
-            "airbender::main does not accept arguments",
-        )
-        .to_compile_error()
-        .into();
-    }
     if input.sig.asyncness.is_some() {
         return syn::Error::new(
             input.sig.asyncness.span(),
@@ -75,12 +84,52 @@ pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     }
 
+    // Each argument is read from the input stream in declaration order.
+    let mut arg_names = Vec::new();
+    let mut arg_reads = Vec::new();
+    for (idx, arg) in input.sig.inputs.iter().enumerate() {
+        let FnArg::Typed(arg) = arg else {
+            return syn::Error::new(arg.span(), "airbender::main cannot take `self`")
+                .to_compile_error()
+                .into();
+        };
+        let ty = &arg.ty;
+        let binding = format_ident!("__airbender_arg{idx}");
+        let label = match &*arg.pat {
+            Pat::Ident(pat) => pat.ident.to_string(),
+            pat => quote!(#pat).to_string(),
+        };
+        arg_reads.push(quote! {
+            let #binding: #ty = match ::airbender::guest::read::<#ty>() {
+                Ok(value) => value,
+                Err(err) => panic!("failed to read argument `{}`: {}", #label, err),
+            };
+        });
+        arg_names.push(binding);
+    }
+
     let fn_name = &input.sig.ident;
     let wrapper_name = syn::Ident::new(&format!("__airbender_start_{fn_name}"), fn_name.span());
 
+    let commit_fn = if args.bind_inputs {
+        quote!(::airbender::guest::commit_with_inputs)
+    } else {
+        quote!(::airbender::guest::commit)
+    };
+    let commit_output = if returns_result(&input.sig.output) {
+        quote! {
+            match output {
+                Ok(value) => #commit_fn(value),
+                Err(err) => ::airbender::guest::exit_with_error(&err),
+            }
+        }
+    } else {
+        quote!(#commit_fn(output))
+    };
     let guest_entry = quote! {
-        let output = #fn_name();
-        ::airbender::guest::commit(output)
+        #(#arg_reads)*
+        let output = #fn_name(#(#arg_names),*);
+        #commit_output
     };
 
     let start_call = if let Some(allocator_init) = args.allocator_init {
@@ -106,7 +155,73 @@ pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
         pub extern "C" fn #wrapper_name() -> ! {
             #start_call
         }
+
+        ::airbender::guest::__airbender_native_entry! {
+            /// Guest entry point for `airbender::guest::native::run`.
+            #[allow(dead_code)]
+            pub fn airbender_native_entry() -> ::core::convert::Infallible {
+                #guest_entry
+            }
+        }
     };
 
     expanded.into()
 }
+
+/// Record the cycles of every call to the annotated function as a named region.
+///
+/// The region is named after the function unless a name is given, as in
+/// `#[airbender::profile("verify")]`. Expands to `airbender::guest::record_cycles!`.
+#[proc_macro_attribute]
+pub fn profile(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let name = if attr.is_empty() {
+        None
+    } else {
+        Some(parse_macro_input!(attr as LitStr))
+    };
+    let mut input = parse_macro_input!(item as ItemFn);
+    if input.sig.asyncness.is_some() || input.sig.constness.is_some() {
+        return syn::Error::new(
+            input.sig.span(),
+            "airbender::profile cannot be used on async or const functions",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let name =
+        name.unwrap_or_else(|| LitStr::new(&input.sig.ident.to_string(), input.sig.ident.span()));
+    let block = &input.block;
+    *input.block = syn::parse_quote!({ ::airbender::guest::record_cycles!(#name, || #block) });
+
+    quote!(#input).into()
+}
+
+/// Whether the entrypoint returns a `Result`, which is unwrapped before committing.
+fn returns_result(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, ty) = output else {
+        return false;
+    };
+    let Type::Path(path) = &**ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Result")
+}
+
+/// Derive `Commit` and `CommitField` by packing struct fields into output words in order.
+///
+/// Supported field types are `u32`, `u64`, `i64`, `bool`, `[u32; 8]`, `[u8; 32]`, and other
+/// `#[derive(Commit)]` types. The trait path defaults to `::airbender::guest` and can be
+/// overridden with `#[commit(crate = <path>)]`, e.g. `#[commit(crate = airbender_host)]`.
+#[proc_macro_derive(Commit, attributes(commit))]
+pub fn derive_commit(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    commit::expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,66 @@
+use airbender_core::guest::{Commit, CommitField, FromCommit};
+use airbender_macros::Commit;
+
+#[derive(Commit, Debug, PartialEq, Eq)]
+#[commit(crate = airbender_core::guest)]
+struct Header {
+    number: u64,
+    finalized: bool,
+}
+
+#[derive(Commit, Debug, PartialEq, Eq)]
+#[commit(crate = airbender_core::guest)]
+struct Output {
+    header: Header,
+    count: u32,
+    tag: i64,
+}
+
+#[derive(Commit, Debug, PartialEq, Eq)]
+#[commit(crate = airbender_core::guest)]
+struct Digest([u8; 32]);
+
+#[test]
+fn packs_fields_in_declaration_order() {
+    let output = Output {
+        header: Header {
+            number: 0x11223344_55667788,
+            finalized: true,
+        },
+        count: 9,
+        tag: -1,
+    };
+
+    assert_eq!(<Output as CommitField>::WORDS, 6);
+    assert_eq!(
+        output.commit_words(),
+        [0x55667788, 0x11223344, 1, 9, u32::MAX, u32::MAX, 0, 0]
+    );
+    assert_eq!(Output::from_commit_words(&output.commit_words()), output);
+}
+
+#[test]
+fn roundtrips_tuple_structs() {
+    let digest = Digest([7u8; 32]);
+    assert_eq!(Digest::from_commit_words(&digest.commit_words()), digest);
+}
+
+#[test]
+fn describes_words_with_field_names() {
+    let output = Output {
+        header: Header {
+            number: 3,
+            finalized: false,
+        },
+        count: 1,
+        tag: 2,
+    };
+    let mut description = String::new();
+    output
+        .fmt_words(&output.commit_words(), &mut description)
+        .expect("format words");
+    assert_eq!(
+        description,
+        "Output { header: Header { number: 3, finalized: false }, count: 1, tag: 2 }"
+    );
+}
@@ -0,0 +1,201 @@
+use crate::cell::GuestCell;
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr::null_mut;
+
+/// Number of [`scope`] calls currently running.
+static SCOPE_DEPTH: GuestCell<u32> = GuestCell::new(0);
+
+/// Bump allocator whose allocations can be released in bulk with [`scope`].
+pub struct ArenaAllocator {
+    state: UnsafeCell<ArenaState>,
+}
+
+#[derive(Clone, Copy)]
+struct ArenaState {
+    start: usize,
+    end: usize,
+    current: usize,
+    initialized: bool,
+}
+
+unsafe impl Sync for ArenaAllocator {}
+
+impl ArenaAllocator {
+    pub const fn uninit() -> Self {
+        Self {
+            state: UnsafeCell::new(ArenaState {
+                start: 0,
+                end: 0,
+                current: 0,
+                initialized: false,
+            }),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// Caller must ensure `start` and `end` define a writable heap range.
+    pub unsafe fn init(&self, start: *mut usize, end: *mut usize) {
+        let state = &mut *self.state.get();
+        state.start = start as usize;
+        state.end = end as usize;
+        state.current = state.start;
+        state.initialized = true;
+    }
+
+    /// Current arena position, to be passed to [`ArenaAllocator::reset`].
+    pub fn mark(&self) -> usize {
+        unsafe { (*self.state.get()).current }
+    }
+
+    /// Release everything allocated since `mark` was taken.
+    ///
+    /// With `memory-stats`, allocations count the bytes they advance the arena by, alignment
+    /// padding included, so releasing them gives back exactly what they recorded.
+    ///
+    /// # Safety
+    ///
+    /// `mark` must come from [`ArenaAllocator::mark`] on this allocator, and no allocation made
+    /// after it may be used again.
+    pub unsafe fn reset(&self, mark: usize) {
+        let state = &mut *self.state.get();
+        if mark < state.current {
+            #[cfg(feature = "memory-stats")]
+            crate::memory::record_dealloc(state.current - mark);
+            state.current = mark;
+        }
+    }
+
+    unsafe fn alloc_inner(&self, layout: Layout) -> *mut u8 {
+        crate::stack_guard::check_fast();
+        let state = &mut *self.state.get();
+        if !state.initialized {
+            return null_mut();
+        }
+
+        let align = layout.align();
+        let size = layout.size();
+        let aligned = (state.current + align - 1) & !(align - 1);
+        let next = aligned.saturating_add(size);
+        if next > state.end {
+            return null_mut();
+        }
+
+        #[cfg(feature = "memory-stats")]
+        crate::memory::record_alloc(next - state.current);
+        state.current = next;
+        aligned as *mut u8
+    }
+}
+
+unsafe impl GlobalAlloc for ArenaAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.alloc_inner(layout)
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {
+        // no-op: memory is released when the enclosing scope ends
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, old_layout: Layout, new_size: usize) -> *mut u8 {
+        if ptr.is_null() {
+            return self.alloc_inner(Layout::from_size_align_unchecked(
+                new_size,
+                old_layout.align(),
+            ));
+        }
+
+        let new_layout = Layout::from_size_align_unchecked(new_size, old_layout.align());
+        let new_ptr = self.alloc_inner(new_layout);
+        if !new_ptr.is_null() {
+            let copy_len = core::cmp::min(old_layout.size(), new_size);
+            core::ptr::copy_nonoverlapping(ptr, new_ptr, copy_len);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(target_arch = "riscv32")]
+#[global_allocator]
+static GLOBAL_ALLOCATOR: ArenaAllocator = ArenaAllocator::uninit();
+
+/// # Safety
+///
+/// Caller must ensure `start` and `end` define a valid, exclusively-owned heap region.
+#[cfg(target_arch = "riscv32")]
+pub unsafe fn init(start: *mut usize, end: *mut usize) {
+    GLOBAL_ALLOCATOR.init(start, end);
+}
+
+/// # Safety
+///
+/// No-op on non-riscv32 targets; kept for API compatibility.
+#[cfg(not(target_arch = "riscv32"))]
+pub unsafe fn init(_start: *mut usize, _end: *mut usize) {}
+
+/// Run `f` and release everything it allocated once it returns.
+///
+/// Scopes can be nested; each one releases only its own allocations. Runtime state that
+/// would outlive the scope, such as the public journal, refuses to be used inside one (see
+/// [`in_scope`]).
+///
+/// # Safety
+///
+/// Nothing allocated inside `f` may be used after `scope` returns. That includes the return
+/// value and any global state of the guest first initialized or grown inside `f`.
+pub unsafe fn scope<R>(f: impl FnOnce() -> R) -> R {
+    SCOPE_DEPTH.with(|depth| *depth += 1);
+    #[cfg(target_arch = "riscv32")]
+    let result = {
+        let mark = GLOBAL_ALLOCATOR.mark();
+        let result = f();
+        GLOBAL_ALLOCATOR.reset(mark);
+        result
+    };
+    #[cfg(not(target_arch = "riscv32"))]
+    let result = f();
+    SCOPE_DEPTH.with(|depth| *depth -= 1);
+    result
+}
+
+/// Whether a [`scope`] is running, so that allocations would be released when it returns.
+pub fn in_scope() -> bool {
+    SCOPE_DEPTH.get() > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{in_scope, scope, ArenaAllocator};
+    use core::alloc::{GlobalAlloc, Layout};
+
+    #[test]
+    fn reset_reuses_memory_allocated_after_mark() {
+        let mut heap = [0usize; 64];
+        let range = heap.as_mut_ptr_range();
+        let arena = ArenaAllocator::uninit();
+        let layout = Layout::from_size_align(16, 4).expect("layout");
+
+        unsafe {
+            arena.init(range.start, range.end);
+            let kept = arena.alloc(layout);
+            let mark = arena.mark();
+            let first = arena.alloc(layout);
+            arena.alloc(layout);
+            arena.reset(mark);
+            let reused = arena.alloc(layout);
+
+            assert!(!kept.is_null());
+            assert_eq!(first, reused);
+            assert_eq!(kept.add(16), first);
+        }
+    }
+
+    #[test]
+    fn scopes_report_while_running() {
+        assert!(!in_scope());
+        let nested = unsafe { scope(|| scope(in_scope) && in_scope()) };
+        assert!(nested);
+        assert!(!in_scope());
+    }
+}
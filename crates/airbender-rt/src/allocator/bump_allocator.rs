@@ -41,6 +41,7 @@ impl BumpAllocator {
     }
 
     unsafe fn alloc_inner(&self, layout: Layout) -> *mut u8 {
+        crate::stack_guard::check_fast();
         let state = &mut *self.state.get();
         if !state.initialized {
             return null_mut();
@@ -55,6 +56,8 @@ impl BumpAllocator {
         }
 
         state.current = next;
+        #[cfg(feature = "memory-stats")]
+        crate::memory::record_alloc(size);
         aligned as *mut u8
     }
 }
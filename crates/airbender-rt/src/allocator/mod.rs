@@ -1,21 +1,29 @@
 //! Guest allocator backends.
 
-#[cfg(not(any(
-    feature = "allocator-bump",
-    feature = "allocator-talc",
-    feature = "allocator-custom"
-)))]
+// Host builds, such as native runs through `airbender-guest`, may leave the allocator unset.
+#[cfg(all(
+    target_arch = "riscv32",
+    not(any(
+        feature = "allocator-bump",
+        feature = "allocator-talc",
+        feature = "allocator-arena",
+        feature = "allocator-custom"
+    ))
+))]
 compile_error!(
-    "enable one allocator feature: `allocator-bump`, `allocator-talc`, or `allocator-custom`"
+    "enable one allocator feature: `allocator-bump`, `allocator-talc`, `allocator-arena`, or `allocator-custom`"
 );
 
 #[cfg(any(
     all(feature = "allocator-bump", feature = "allocator-talc"),
+    all(feature = "allocator-bump", feature = "allocator-arena"),
     all(feature = "allocator-bump", feature = "allocator-custom"),
+    all(feature = "allocator-talc", feature = "allocator-arena"),
     all(feature = "allocator-talc", feature = "allocator-custom"),
+    all(feature = "allocator-arena", feature = "allocator-custom"),
 ))]
 compile_error!(
-    "allocator features are mutually exclusive; enable only one of `allocator-bump`, `allocator-talc`, `allocator-custom`"
+    "allocator features are mutually exclusive; enable only one of `allocator-bump`, `allocator-talc`, `allocator-arena`, `allocator-custom`"
 );
 
 #[cfg(feature = "allocator-bump")]
@@ -27,3 +35,8 @@ pub use bump_allocator::{init, BumpAllocator};
 mod talc_allocator;
 #[cfg(feature = "allocator-talc")]
 pub use talc_allocator::{init, TalcAllocator};
+
+#[cfg(feature = "allocator-arena")]
+mod arena_allocator;
+#[cfg(feature = "allocator-arena")]
+pub use arena_allocator::{in_scope, init, scope, ArenaAllocator};
@@ -38,16 +38,21 @@ impl TalcAllocator {
     }
 
     unsafe fn alloc_inner(&self, layout: Layout) -> *mut u8 {
+        crate::stack_guard::check_fast();
         let state = &mut *self.state.get();
         let Some(allocator) = state.allocator.as_mut() else {
             return null_mut();
         };
-        allocator
-            .malloc(layout)
-            .map_or(null_mut(), |nn| nn.as_ptr())
+        let Ok(ptr) = allocator.malloc(layout) else {
+            return null_mut();
+        };
+        #[cfg(feature = "memory-stats")]
+        crate::memory::record_alloc(layout.size());
+        ptr.as_ptr()
     }
 
     unsafe fn dealloc_inner(&self, ptr: *mut u8, layout: Layout) {
+        crate::stack_guard::check_fast();
         if ptr.is_null() || layout.size() == 0 {
             return;
         }
@@ -55,6 +60,8 @@ impl TalcAllocator {
         let state = &mut *self.state.get();
         if let Some(allocator) = state.allocator.as_mut() {
             allocator.free(NonNull::new_unchecked(ptr), layout);
+            #[cfg(feature = "memory-stats")]
+            crate::memory::record_dealloc(layout.size());
         }
     }
 }
@@ -5,7 +5,11 @@
 /// This helper is available only for built-in allocator backends. When
 /// `allocator-custom` is enabled, use `start_with_allocator_init` so the
 /// runtime receives an explicit allocator init hook.
-#[cfg(not(feature = "allocator-custom"))]
+#[cfg(any(
+    feature = "allocator-bump",
+    feature = "allocator-talc",
+    feature = "allocator-arena"
+))]
 pub fn start<F>(entry: F) -> !
 where
     F: FnOnce() -> core::convert::Infallible,
@@ -27,14 +31,29 @@ where
     #[cfg(target_arch = "riscv32")]
     {
         riscv_common::boot_sequence::init();
+        let heap_start = riscv_common::boot_sequence::heap_start();
+        #[cfg(feature = "mem-delegation")]
+        crate::mem::init(heap_start as usize);
         // SAFETY: The boot sequence guarantees that heap_start and heap_end
-        // point to a valid, exclusively-owned memory region.
-        unsafe {
+        // point to a valid, exclusively-owned memory region right below the stack.
+        let heap_end = unsafe {
+            let heap_end =
+                crate::layout::heap_end(heap_start, riscv_common::boot_sequence::heap_end());
             allocator_init(
-                riscv_common::boot_sequence::heap_start(),
-                riscv_common::boot_sequence::heap_end(),
+                heap_start,
+                crate::stack_guard::install(heap_start, heap_end),
             );
+            heap_end
+        };
+        // SAFETY: The stack sits directly above the heap and grows down toward it.
+        #[cfg(feature = "memory-stats")]
+        unsafe {
+            crate::memory::paint_stack(heap_end);
         }
+        #[cfg(not(feature = "memory-stats"))]
+        let _ = heap_end;
+        #[cfg(feature = "rng-seed")]
+        crate::getrandom::init_seed();
     }
 
     match entry() {}
@@ -0,0 +1,107 @@
+//! Mutable globals for the runtime and guest crates.
+
+use core::cell::UnsafeCell;
+
+/// A global value that guest code reads and updates in place.
+///
+/// Guest programs run on a single hart without threads or interrupts, so a global is never
+/// touched from two places at once; the only way to alias it is to re-enter
+/// [`GuestCell::with`] from inside its own closure, which panics instead. Host builds of the
+/// runtime, used by tests and native runs, may share globals between threads, so there the
+/// cell is a spin lock.
+pub struct GuestCell<T> {
+    value: UnsafeCell<T>,
+    #[cfg(target_arch = "riscv32")]
+    borrowed: core::cell::Cell<bool>,
+    #[cfg(not(target_arch = "riscv32"))]
+    locked: core::sync::atomic::AtomicBool,
+}
+
+// SAFETY: See the type documentation; `with` hands out at most one reference at a time.
+unsafe impl<T> Sync for GuestCell<T> {}
+
+impl<T> GuestCell<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            #[cfg(target_arch = "riscv32")]
+            borrowed: core::cell::Cell::new(false),
+            #[cfg(not(target_arch = "riscv32"))]
+            locked: core::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Run `f` with exclusive access to the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` calls `with` on the same cell again.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let _guard = self.acquire();
+        // SAFETY: The guard makes this the only reference to the value.
+        f(unsafe { &mut *self.value.get() })
+    }
+
+    #[cfg(target_arch = "riscv32")]
+    fn acquire(&self) -> Release<'_> {
+        assert!(!self.borrowed.replace(true), "GuestCell re-entered");
+        Release(&self.borrowed)
+    }
+
+    #[cfg(not(target_arch = "riscv32"))]
+    fn acquire(&self) -> Release<'_> {
+        use core::sync::atomic::Ordering;
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        Release(&self.locked)
+    }
+}
+
+impl<T: Copy> GuestCell<T> {
+    pub fn get(&self) -> T {
+        self.with(|value| *value)
+    }
+
+    pub fn set(&self, value: T) {
+        self.with(|slot| *slot = value);
+    }
+}
+
+/// Gives the cell back when `with` returns or unwinds.
+#[cfg(target_arch = "riscv32")]
+struct Release<'a>(&'a core::cell::Cell<bool>);
+
+#[cfg(not(target_arch = "riscv32"))]
+struct Release<'a>(&'a core::sync::atomic::AtomicBool);
+
+impl Drop for Release<'_> {
+    fn drop(&mut self) {
+        #[cfg(target_arch = "riscv32")]
+        self.0.set(false);
+        #[cfg(not(target_arch = "riscv32"))]
+        self.0.store(false, core::sync::atomic::Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GuestCell;
+
+    #[test]
+    fn updates_in_place_from_several_threads() {
+        static COUNTER: GuestCell<u32> = GuestCell::new(0);
+        COUNTER.set(2);
+        let threads: std::vec::Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(|| (0..100).for_each(|_| COUNTER.with(|n| *n += 1))))
+            .collect();
+        for thread in threads {
+            thread.join().expect("thread");
+        }
+        assert_eq!(COUNTER.get(), 402);
+    }
+}
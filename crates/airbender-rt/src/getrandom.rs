@@ -1,24 +1,103 @@
 //! Custom `getrandom` backend for the Airbender guest environment.
+//!
+//! Airbender guests do not have access to system randomness. With the `rng-seed` feature the
+//! runtime reads a host-supplied seed section from the start of the input stream at boot and
+//! expands it with [`airbender_core::rng::SeededRng`], which then backs `getrandom` and the
+//! `std` `sys_rand` hook. Without the feature, randomness requests are rejected, and `sys_rand`
+//! exits the guest with an error.
 
-/// Stub implementation to avoid linker errors when dependencies pull in `getrandom`.
+#[cfg(feature = "rng-seed")]
+mod seeded {
+    use crate::cell::GuestCell;
+    use airbender_core::rng::{SeededRng, RNG_SEED_WORDS};
+
+    /// Seed words read at boot and the generator expanded from them.
+    pub(super) static GLOBAL_RNG: GuestCell<Option<([u32; RNG_SEED_WORDS], SeededRng)>> =
+        GuestCell::new(None);
+}
+
+/// Read the seed section from the input stream and initialize the guest RNG.
+///
+/// Called by the boot sequence before the entry point runs, so the seed section must be the
+/// first thing in the input stream.
+#[cfg(all(feature = "rng-seed", target_arch = "riscv32"))]
+pub(crate) fn init_seed() {
+    use airbender_core::rng::{seed_from_words, SeededRng, RNG_SEED_MARKER};
+
+    if crate::sys::read_word() != RNG_SEED_MARKER {
+        panic!("`rng-seed` is enabled but the inputs carry no seed; use `Inputs::with_rng_seed`");
+    }
+    let words = core::array::from_fn(|_| crate::sys::read_word());
+    seeded::GLOBAL_RNG.with(|rng| *rng = Some((words, SeededRng::new(seed_from_words(&words)))));
+}
+
+/// Seed words read at boot, if the `rng-seed` feature is enabled.
+///
+/// The guest input digest folds these words (after `RNG_SEED_MARKER`) first, so committing
+/// with the input binding also binds the seed.
+pub fn seed_words() -> Option<[u32; airbender_core::rng::RNG_SEED_WORDS]> {
+    #[cfg(feature = "rng-seed")]
+    {
+        seeded::GLOBAL_RNG.with(|rng| rng.as_ref().map(|(words, _)| *words))
+    }
+    #[cfg(not(feature = "rng-seed"))]
+    {
+        None
+    }
+}
+
+/// Fill `dest` from the seeded guest RNG.
+///
+/// Returns `false` when no seed is available.
+pub fn fill_bytes(dest: &mut [u8]) -> bool {
+    #[cfg(feature = "rng-seed")]
+    {
+        seeded::GLOBAL_RNG.with(|rng| match rng {
+            Some((_, rng)) => {
+                rng.fill_bytes(dest);
+                true
+            }
+            None => false,
+        })
+    }
+    #[cfg(not(feature = "rng-seed"))]
+    {
+        let _ = dest;
+        false
+    }
+}
+
+/// `getrandom` v0.2 backend.
 ///
-/// Airbender guests do not have access to system randomness, so calls are
-/// rejected with `UNSUPPORTED`.
+/// Fills `dest` from the seeded guest RNG, or fails with `UNSUPPORTED` without a seed.
 #[cfg(target_arch = "riscv32")]
 #[no_mangle]
 pub unsafe extern "Rust" fn __getrandom_v02_custom(
-    _dest: *mut u8,
-    _len: usize,
+    dest: *mut u8,
+    len: usize,
 ) -> Result<(), getrandom::Error> {
-    Err(getrandom::Error::UNSUPPORTED)
+    fill_raw(dest, len)
 }
 
-/// Stub implementation for `getrandom` v0.3 symbols.
+/// `getrandom` v0.3 backend, sharing the v0.2 behavior.
 #[cfg(target_arch = "riscv32")]
 #[no_mangle]
 pub unsafe extern "Rust" fn __getrandom_v03_custom(
-    _dest: *mut u8,
-    _len: usize,
+    dest: *mut u8,
+    len: usize,
 ) -> Result<(), getrandom::Error> {
-    Err(getrandom::Error::UNSUPPORTED)
+    fill_raw(dest, len)
+}
+
+#[cfg(target_arch = "riscv32")]
+unsafe fn fill_raw(dest: *mut u8, len: usize) -> Result<(), getrandom::Error> {
+    if len == 0 {
+        return Ok(());
+    }
+    let dest = core::slice::from_raw_parts_mut(dest, len);
+    if fill_bytes(dest) {
+        Ok(())
+    } else {
+        Err(getrandom::Error::UNSUPPORTED)
+    }
 }
@@ -1,6 +1,62 @@
+use crate::cell::GuestCell;
 use crate::uart::QuasiUart;
+use airbender_core::preamble::{Preamble, PREAMBLE_REQUEST_MARKER};
 use std::alloc::{Allocator, Layout};
 use std::fmt::Write as _;
+use std::vec::Vec;
+
+/// State of the preamble once requested from the host.
+struct PreambleState {
+    preamble: Preamble,
+    /// Words the preamble was decoded from, for the input digest.
+    words: Vec<u32>,
+    stdin_cursor: usize,
+}
+
+/// Preamble requested from the host on first use.
+static PREAMBLE: GuestCell<Option<PreambleState>> = GuestCell::new(None);
+
+fn with_preamble_state<R>(f: impl FnOnce(&mut PreambleState) -> R) -> R {
+    PREAMBLE.with(|state| {
+        f(state.get_or_insert_with(|| {
+            crate::sys::write_word(PREAMBLE_REQUEST_MARKER);
+            let mut words = Vec::new();
+            let preamble = Preamble::read_with(|| {
+                let word = crate::sys::read_word();
+                words.push(word);
+                word
+            });
+            PreambleState {
+                preamble,
+                words,
+                stdin_cursor: 0,
+            }
+        }))
+    })
+}
+
+fn with_preamble<R>(f: impl FnOnce(&Preamble, &mut usize) -> R) -> R {
+    with_preamble_state(|state| f(&state.preamble, &mut state.stdin_cursor))
+}
+
+/// Words of the program environment preamble, requesting it from the host if the guest has
+/// not used it yet.
+pub fn preamble_words() -> Vec<u32> {
+    with_preamble_state(|state| state.words.clone())
+}
+
+/// Copy as much of `bytes` as fits into `nwords` words at `out` and return the full length.
+///
+/// # Safety
+///
+/// `out` must be valid for `nwords` word writes when `nwords` is non-zero.
+unsafe fn copy_to_words(bytes: &[u8], out: *mut u32, nwords: usize) -> usize {
+    let len = bytes.len().min(nwords * WORD_SIZE);
+    if len > 0 {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), out.cast::<u8>(), len);
+    }
+    bytes.len()
+}
 
 #[inline(never)]
 #[unsafe(no_mangle)]
@@ -8,9 +64,23 @@ pub extern "C" fn sys_halt() {
     crate::sys::exit_error();
 }
 
+/// # Safety
+///
+/// This function is called by the standard library with a buffer of at least `words` words.
+/// Without a seed the guest exits with an error instead of handing out predictable bytes.
 #[inline(never)]
 #[unsafe(no_mangle)]
-pub extern "C" fn sys_rand(_recv_buf: *mut u32, _words: usize) {}
+pub unsafe extern "C" fn sys_rand(recv_buf: *mut u32, words: usize) {
+    if words == 0 {
+        return;
+    }
+    let buf = core::slice::from_raw_parts_mut(recv_buf.cast::<u8>(), words * 4);
+    if !crate::getrandom::fill_bytes(buf) {
+        let msg = "randomness requested without a seed; enable the `rng-seed` feature and \
+                   provide a seed with `Inputs::with_rng_seed`";
+        sys_panic(msg.as_ptr(), msg.len());
+    }
+}
 
 /// # Safety
 ///
@@ -19,6 +89,7 @@ pub extern "C" fn sys_rand(_recv_buf: *mut u32, _words: usize) {}
 #[unsafe(no_mangle)]
 pub unsafe fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
     let msg = core::str::from_raw_parts(msg_ptr, len);
+    crate::sys::write_word(airbender_core::exit::GUEST_PANIC_MARKER);
     let mut uart = QuasiUart::new();
     let _ = uart.write_str("PANIC: ");
     let _ = uart.write_str(msg);
@@ -26,14 +97,36 @@ pub unsafe fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
     core::hint::unreachable_unchecked()
 }
 
+/// # Safety
+///
+/// This function is called by the standard library with trusted pointers.
 #[inline(never)]
 #[unsafe(no_mangle)]
-pub fn sys_log(_msg_ptr: *const u8, _len: usize) {}
+pub unsafe fn sys_log(msg_ptr: *const u8, len: usize) {
+    let msg = core::str::from_raw_parts(msg_ptr, len);
+    crate::uart::write_log_record(airbender_core::log::Level::Info, msg);
+}
 
+/// Reads from stdin (fd 0) are served from the host preamble; other descriptors are empty.
+///
+/// # Safety
+///
+/// This function is called by the standard library with trusted pointers.
 #[inline(never)]
 #[unsafe(no_mangle)]
-pub fn sys_read(_fd: u32, _recv_buf: *mut u8, _nrequested: usize) -> usize {
-    0
+pub unsafe fn sys_read(fd: u32, recv_buf: *mut u8, nrequested: usize) -> usize {
+    if fd != 0 {
+        return 0;
+    }
+    with_preamble(|preamble, cursor| {
+        let remaining = &preamble.stdin[*cursor..];
+        let len = remaining.len().min(nrequested);
+        if len > 0 {
+            core::ptr::copy_nonoverlapping(remaining.as_ptr(), recv_buf, len);
+        }
+        *cursor += len;
+        len
+    })
 }
 
 /// # Safety
@@ -50,30 +143,44 @@ pub unsafe fn sys_write(fd: u32, write_buf: *const u8, nbytes: usize) {
     let _ = uart.write_str(msg);
 }
 
+/// Returns the value length in bytes, or `usize::MAX` when the variable is not set.
+///
 /// # Safety
 ///
 /// This function is called by the standard library with trusted pointers.
 #[inline(never)]
 #[unsafe(no_mangle)]
 pub unsafe fn sys_getenv(
-    _recv_buf: *mut u32,
-    _words: usize,
-    _varname: *const u8,
-    _varname_len: usize,
+    recv_buf: *mut u32,
+    words: usize,
+    varname: *const u8,
+    varname_len: usize,
 ) -> usize {
-    0
+    let name = core::slice::from_raw_parts(varname, varname_len);
+    with_preamble(|preamble, _| match preamble.var(name) {
+        Some(value) => copy_to_words(value, recv_buf, words),
+        None => usize::MAX,
+    })
 }
 
 #[inline(never)]
 #[unsafe(no_mangle)]
 pub fn sys_argc() -> usize {
-    0
+    with_preamble(|preamble, _| preamble.args.len())
 }
 
+/// Returns the argument length in bytes, copying as much as fits into `out_words`.
+///
+/// # Safety
+///
+/// This function is called by the standard library with trusted pointers.
 #[inline(never)]
 #[unsafe(no_mangle)]
-pub fn sys_argv(_out_words: *mut u32, _out_nwords: usize, _arg_index: usize) -> usize {
-    0
+pub unsafe fn sys_argv(out_words: *mut u32, out_nwords: usize, arg_index: usize) -> usize {
+    with_preamble(|preamble, _| match preamble.args.get(arg_index) {
+        Some(arg) => copy_to_words(arg, out_words, out_nwords),
+        None => 0,
+    })
 }
 
 const WORD_SIZE: usize = core::mem::size_of::<u32>();
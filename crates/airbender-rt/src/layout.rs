@@ -0,0 +1,88 @@
+//! Heap bounds from the memory layout configured in `[package.metadata.airbender]`.
+//!
+//! `airbender-build` links such guests with a script defining `_airbender_stack_size` and
+//! `_airbender_heap_size`, and builds them with `--cfg airbender_memory_layout`. At boot the
+//! runtime shrinks the heap so the stack keeps at least the requested size and the heap stays
+//! within its cap. A zero size leaves that bound unconstrained.
+
+/// End of the heap starting at `heap_start` once the layout is applied, or `None` if the
+/// requested stack does not fit above the heap start.
+///
+/// `heap_end` is the end reported by the boot sequence and `stack_top` the stack pointer at
+/// boot.
+#[cfg(any(test, airbender_memory_layout))]
+fn clamp_heap_end(
+    heap_start: usize,
+    heap_end: usize,
+    stack_top: usize,
+    stack_size: usize,
+    heap_size: usize,
+) -> Option<usize> {
+    let mut end = heap_end;
+    if stack_size != 0 {
+        end = end.min(stack_top.checked_sub(stack_size)? & !3);
+    }
+    if heap_size != 0 {
+        end = end.min(heap_start.saturating_add(heap_size));
+    }
+    (end >= heap_start).then_some(end)
+}
+
+/// Apply the configured layout to the heap bounds reported by the boot sequence.
+///
+/// # Safety
+///
+/// Must be called once at boot, before anything is allocated.
+#[cfg(all(airbender_memory_layout, target_arch = "riscv32"))]
+pub(crate) unsafe fn heap_end(heap_start: *mut usize, heap_end: *mut usize) -> *mut usize {
+    extern "C" {
+        static _airbender_stack_size: u8;
+        static _airbender_heap_size: u8;
+    }
+
+    // The linker script defines the sizes as absolute symbols, so their addresses are the values.
+    let stack_size = core::ptr::addr_of!(_airbender_stack_size) as usize;
+    let heap_size = core::ptr::addr_of!(_airbender_heap_size) as usize;
+    let sp: usize;
+    core::arch::asm!("mv {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags));
+    match clamp_heap_end(
+        heap_start as usize,
+        heap_end as usize,
+        sp,
+        stack_size,
+        heap_size,
+    ) {
+        Some(end) => end as *mut usize,
+        None => panic!("memory layout: a {stack_size}-byte stack does not fit in guest RAM"),
+    }
+}
+
+/// Without a configured layout the boot sequence bounds are used as is.
+///
+/// # Safety
+///
+/// Kept `unsafe` to match the configured-layout variant.
+#[cfg(all(not(airbender_memory_layout), target_arch = "riscv32"))]
+pub(crate) unsafe fn heap_end(_heap_start: *mut usize, heap_end: *mut usize) -> *mut usize {
+    heap_end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clamp_heap_end;
+
+    #[test]
+    fn applies_stack_reservation_and_heap_cap() {
+        // Heap 0x1000..0x9000, stack pointer at 0xa000.
+        assert_eq!(clamp_heap_end(0x1000, 0x9000, 0xa000, 0, 0), Some(0x9000));
+        assert_eq!(
+            clamp_heap_end(0x1000, 0x9000, 0xa000, 0x4000, 0),
+            Some(0x6000)
+        );
+        assert_eq!(
+            clamp_heap_end(0x1000, 0x9000, 0xa000, 0x4000, 0x2000),
+            Some(0x3000)
+        );
+        assert_eq!(clamp_heap_end(0x1000, 0x9000, 0xa000, 0xa000, 0), None);
+    }
+}
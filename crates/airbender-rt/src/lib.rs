@@ -15,31 +15,70 @@ extern crate std;
 
 pub mod allocator;
 pub mod boot;
+pub mod cell;
 pub mod getrandom;
+mod layout;
+#[cfg(all(feature = "mem-delegation", any(target_arch = "riscv32", test)))]
+mod mem;
+#[cfg(feature = "memory-stats")]
+pub mod memory;
+#[cfg(feature = "critical-section")]
+mod single_core;
+pub mod stack_guard;
 pub mod sys;
 pub mod uart;
 
 #[cfg(all(feature = "std", target_arch = "riscv32"))]
 mod glue;
 
-#[cfg(not(feature = "allocator-custom"))]
+#[cfg(all(feature = "std", target_arch = "riscv32"))]
+pub use glue::preamble_words;
+
+#[cfg(any(
+    feature = "allocator-bump",
+    feature = "allocator-talc",
+    feature = "allocator-arena"
+))]
 pub use boot::start;
 pub use boot::start_with_allocator_init;
 
-#[cfg(all(not(feature = "std"), target_arch = "riscv32"))]
+#[cfg(all(
+    not(feature = "std"),
+    target_arch = "riscv32",
+    not(airbender_compact_panics)
+))]
 #[panic_handler]
 fn panic(info: &core::panic::PanicInfo) -> ! {
     use core::fmt::Write as _;
+    sys::write_word(airbender_core::exit::GUEST_PANIC_MARKER);
     let mut uart = uart::QuasiUart::new();
     let _ = uart.write_str("PANIC: ");
     let _ = write!(uart, "{info}");
     sys::exit_error();
 }
 
+/// Reports only the panic location, as a site ID that the host resolves through the dist
+/// `panics.toml` table, without the formatting machinery for the message.
+#[cfg(all(
+    not(feature = "std"),
+    target_arch = "riscv32",
+    airbender_compact_panics
+))]
+#[panic_handler]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    let id = info.location().map_or(0, |location| {
+        airbender_core::panic_site::site_id(location.file(), location.line(), location.column())
+    });
+    sys::write_word(airbender_core::exit::GUEST_PANIC_LOCATION_MARKER);
+    sys::write_word(id);
+    sys::exit_error();
+}
+
 #[cfg(all(not(feature = "std"), target_arch = "riscv32"))]
 #[alloc_error_handler]
 fn alloc_error(layout: core::alloc::Layout) -> ! {
     use core::fmt::Write as _;
+    sys::write_word(airbender_core::exit::GUEST_PANIC_MARKER);
     let mut uart = uart::QuasiUart::new();
     let _ = write!(
         uart,
@@ -0,0 +1,331 @@
+//! Word-aligned `memcpy`, `memmove`, `memset`, `memcmp` and `bcmp` for the `mem-delegation`
+//! feature.
+//!
+//! compiler-builtins only ships byte loops for the guest target. These versions move whole
+//! words whenever both pointers share the word alignment, and hand 32-byte blocks that are
+//! 32-byte aligned on both sides to the bigint delegation circuit, which copies or compares a
+//! block in a single call. Delegation is only used for addresses at or above the heap start,
+//! so reads from the program image always take the plain word loops.
+//!
+//! Loops use volatile accesses so LLVM cannot turn them back into calls to these symbols.
+
+use crate::cell::GuestCell;
+use core::ptr::{read_volatile, write_volatile};
+
+const WORD: usize = 4;
+const BLOCK: usize = 32;
+
+/// Delegation op bits, matching `airbender_crypto::BigIntOps`.
+const OP_EQ: u32 = 1 << 5;
+const OP_MEMCPY: u32 = 1 << 7;
+
+/// Lowest address the delegation may touch; `usize::MAX` until boot sets it.
+static RAM_FLOOR: GuestCell<usize> = GuestCell::new(if cfg!(test) { 0 } else { usize::MAX });
+
+/// Allow delegated block operations on memory from `heap_start` upward.
+#[cfg(target_arch = "riscv32")]
+pub(crate) fn init(heap_start: usize) {
+    RAM_FLOOR.set(heap_start);
+}
+
+fn delegable(addr: usize) -> bool {
+    addr >= RAM_FLOOR.get()
+}
+
+#[cfg(target_arch = "riscv32")]
+#[inline(always)]
+unsafe fn delegate(op: u32, a: usize, b: usize) -> u32 {
+    let mut mask = op;
+    core::arch::asm!(
+        "csrrw x0, 0x7ca, x0",
+        in("x10") a,
+        in("x11") b,
+        inlateout("x12") mask,
+        options(nostack, preserves_flags)
+    );
+    mask
+}
+
+/// Software stand-in for the circuit so the block paths run in host tests.
+#[cfg(not(target_arch = "riscv32"))]
+unsafe fn delegate(op: u32, a: usize, b: usize) -> u32 {
+    let (a, b) = (a as *mut u8, b as *const u8);
+    match op {
+        OP_MEMCPY => {
+            core::ptr::copy_nonoverlapping(b, a, BLOCK);
+            0
+        }
+        OP_EQ => {
+            (core::slice::from_raw_parts(a, BLOCK) == core::slice::from_raw_parts(b, BLOCK)) as u32
+        }
+        _ => unreachable!(),
+    }
+}
+
+unsafe fn copy_byte(dst: usize, src: usize) {
+    write_volatile(dst as *mut u8, read_volatile(src as *const u8));
+}
+
+unsafe fn copy_word(dst: usize, src: usize) {
+    write_volatile(dst as *mut u32, read_volatile(src as *const u32));
+}
+
+unsafe fn copy_forward(dst: *mut u8, src: *const u8, len: usize) {
+    let (mut dst, mut src, mut len) = (dst as usize, src as usize, len);
+    if (dst ^ src) % WORD == 0 {
+        while dst % WORD != 0 && len > 0 {
+            copy_byte(dst, src);
+            (dst, src, len) = (dst + 1, src + 1, len - 1);
+        }
+        if (dst ^ src) % BLOCK == 0
+            && len >= 2 * BLOCK
+            && dst.abs_diff(src) >= BLOCK
+            && delegable(dst)
+            && delegable(src)
+        {
+            while dst % BLOCK != 0 {
+                copy_word(dst, src);
+                (dst, src, len) = (dst + WORD, src + WORD, len - WORD);
+            }
+            while len >= BLOCK {
+                delegate(OP_MEMCPY, dst, src);
+                (dst, src, len) = (dst + BLOCK, src + BLOCK, len - BLOCK);
+            }
+        }
+        while len >= WORD {
+            copy_word(dst, src);
+            (dst, src, len) = (dst + WORD, src + WORD, len - WORD);
+        }
+    }
+    while len > 0 {
+        copy_byte(dst, src);
+        (dst, src, len) = (dst + 1, src + 1, len - 1);
+    }
+}
+
+/// Copy from the end, for overlapping moves to a higher address.
+unsafe fn copy_backward(dst: *mut u8, src: *const u8, len: usize) {
+    let (mut dst_end, mut src_end, mut len) = (dst as usize + len, src as usize + len, len);
+    if (dst_end ^ src_end) % WORD == 0 {
+        while dst_end % WORD != 0 && len > 0 {
+            (dst_end, src_end, len) = (dst_end - 1, src_end - 1, len - 1);
+            copy_byte(dst_end, src_end);
+        }
+        while len >= WORD {
+            (dst_end, src_end, len) = (dst_end - WORD, src_end - WORD, len - WORD);
+            copy_word(dst_end, src_end);
+        }
+    }
+    while len > 0 {
+        (dst_end, src_end, len) = (dst_end - 1, src_end - 1, len - 1);
+        copy_byte(dst_end, src_end);
+    }
+}
+
+unsafe fn fill(dst: *mut u8, value: u8, len: usize) {
+    let (mut dst, mut len) = (dst as usize, len);
+    let word = u32::from_ne_bytes([value; WORD]);
+    while dst % WORD != 0 && len > 0 {
+        write_volatile(dst as *mut u8, value);
+        (dst, len) = (dst + 1, len - 1);
+    }
+    if len >= 2 * BLOCK && delegable(dst) {
+        while dst % BLOCK != 0 {
+            write_volatile(dst as *mut u32, word);
+            (dst, len) = (dst + WORD, len - WORD);
+        }
+        // Fill one block by hand and replicate it with delegated copies.
+        let pattern = dst;
+        for offset in (0..BLOCK).step_by(WORD) {
+            write_volatile((pattern + offset) as *mut u32, word);
+        }
+        (dst, len) = (dst + BLOCK, len - BLOCK);
+        while len >= BLOCK {
+            delegate(OP_MEMCPY, dst, pattern);
+            (dst, len) = (dst + BLOCK, len - BLOCK);
+        }
+    }
+    while len >= WORD {
+        write_volatile(dst as *mut u32, word);
+        (dst, len) = (dst + WORD, len - WORD);
+    }
+    while len > 0 {
+        write_volatile(dst as *mut u8, value);
+        (dst, len) = (dst + 1, len - 1);
+    }
+}
+
+unsafe fn compare(left: *const u8, right: *const u8, len: usize) -> i32 {
+    let (mut left, mut right, mut len) = (left as usize, right as usize, len);
+    if left == right {
+        return 0;
+    }
+    if (left ^ right) % WORD == 0 {
+        while left % WORD != 0 && len > 0 {
+            let (a, b) = (
+                read_volatile(left as *const u8),
+                read_volatile(right as *const u8),
+            );
+            if a != b {
+                return i32::from(a) - i32::from(b);
+            }
+            (left, right, len) = (left + 1, right + 1, len - 1);
+        }
+        if (left ^ right) % BLOCK == 0 && len >= 2 * BLOCK && delegable(left) && delegable(right) {
+            while left % BLOCK != 0
+                && read_volatile(left as *const u32) == read_volatile(right as *const u32)
+            {
+                (left, right, len) = (left + WORD, right + WORD, len - WORD);
+            }
+            // The equality op writes the left block back unchanged, so it needs RAM on both
+            // sides; a mismatching block is left for the loops below to pinpoint.
+            while left % BLOCK == 0 && len >= BLOCK && delegate(OP_EQ, left, right) != 0 {
+                (left, right, len) = (left + BLOCK, right + BLOCK, len - BLOCK);
+            }
+        }
+        while len >= WORD && read_volatile(left as *const u32) == read_volatile(right as *const u32)
+        {
+            (left, right, len) = (left + WORD, right + WORD, len - WORD);
+        }
+    }
+    while len > 0 {
+        let (a, b) = (
+            read_volatile(left as *const u8),
+            read_volatile(right as *const u8),
+        );
+        if a != b {
+            return i32::from(a) - i32::from(b);
+        }
+        (left, right, len) = (left + 1, right + 1, len - 1);
+    }
+    0
+}
+
+/// # Safety
+///
+/// Same contract as C `memcpy`.
+#[cfg(target_arch = "riscv32")]
+#[no_mangle]
+pub unsafe extern "C" fn memcpy(dst: *mut u8, src: *const u8, len: usize) -> *mut u8 {
+    copy_forward(dst, src, len);
+    dst
+}
+
+/// # Safety
+///
+/// Same contract as C `memmove`.
+#[cfg(target_arch = "riscv32")]
+#[no_mangle]
+pub unsafe extern "C" fn memmove(dst: *mut u8, src: *const u8, len: usize) -> *mut u8 {
+    if (dst as usize).wrapping_sub(src as usize) >= len {
+        copy_forward(dst, src, len);
+    } else {
+        copy_backward(dst, src, len);
+    }
+    dst
+}
+
+/// # Safety
+///
+/// Same contract as C `memset`.
+#[cfg(target_arch = "riscv32")]
+#[no_mangle]
+pub unsafe extern "C" fn memset(dst: *mut u8, value: i32, len: usize) -> *mut u8 {
+    fill(dst, value as u8, len);
+    dst
+}
+
+/// # Safety
+///
+/// Same contract as C `memcmp`.
+#[cfg(target_arch = "riscv32")]
+#[no_mangle]
+pub unsafe extern "C" fn memcmp(left: *const u8, right: *const u8, len: usize) -> i32 {
+    compare(left, right, len)
+}
+
+/// # Safety
+///
+/// Same contract as C `bcmp`.
+#[cfg(target_arch = "riscv32")]
+#[no_mangle]
+pub unsafe extern "C" fn bcmp(left: *const u8, right: *const u8, len: usize) -> i32 {
+    compare(left, right, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, copy_backward, copy_forward, fill};
+    use std::vec::Vec;
+
+    /// 32-byte aligned scratch buffer.
+    #[repr(C, align(32))]
+    struct Buffer([u8; 256]);
+
+    fn pattern() -> Buffer {
+        let mut buffer = Buffer([0; 256]);
+        for (index, byte) in buffer.0.iter_mut().enumerate() {
+            *byte = (index * 7 + 3) as u8;
+        }
+        buffer
+    }
+
+    #[test]
+    fn copies_and_fills_at_every_alignment() {
+        let source = pattern();
+        for dst_offset in 0..5 {
+            for src_offset in [0, 1, 4, 32] {
+                for len in [0, 3, 31, 64, 150] {
+                    let mut target = Buffer([0; 256]);
+                    unsafe {
+                        copy_forward(
+                            target.0.as_mut_ptr().add(dst_offset),
+                            source.0.as_ptr().add(src_offset),
+                            len,
+                        );
+                    }
+                    assert_eq!(
+                        &target.0[dst_offset..dst_offset + len],
+                        &source.0[src_offset..src_offset + len]
+                    );
+                    assert!(target.0[dst_offset + len..].iter().all(|byte| *byte == 0));
+                }
+            }
+
+            let mut target = Buffer([0; 256]);
+            unsafe { fill(target.0.as_mut_ptr().add(dst_offset), 0xab, 150) };
+            assert!(target.0[..dst_offset].iter().all(|byte| *byte == 0));
+            assert!(target.0[dst_offset..dst_offset + 150]
+                .iter()
+                .all(|byte| *byte == 0xab));
+            assert!(target.0[dst_offset + 150..].iter().all(|byte| *byte == 0));
+        }
+    }
+
+    #[test]
+    fn backward_copy_handles_overlap() {
+        let mut buffer = pattern();
+        let expected: Vec<u8> = buffer.0[..120].to_vec();
+        unsafe { copy_backward(buffer.0.as_mut_ptr().add(8), buffer.0.as_ptr(), 120) };
+        assert_eq!(&buffer.0[8..128], &expected[..]);
+    }
+
+    #[test]
+    fn compare_orders_by_first_differing_byte() {
+        let left = pattern();
+        for index in [0, 5, 40, 100, 199] {
+            let mut right = pattern();
+            right.0[index] = right.0[index].wrapping_add(1);
+            let result = unsafe { compare(left.0.as_ptr(), right.0.as_ptr(), 200) };
+            assert_eq!(
+                result,
+                i32::from(left.0[index]) - i32::from(right.0[index]),
+                "difference at {index}"
+            );
+            assert_eq!(
+                unsafe { compare(left.0.as_ptr(), right.0.as_ptr(), index) },
+                0
+            );
+        }
+    }
+}
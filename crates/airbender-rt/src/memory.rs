@@ -0,0 +1,144 @@
+//! Heap and stack usage tracking for the `memory-stats` feature.
+//!
+//! The built-in allocators report every allocation and release here; custom allocators can
+//! do the same through [`record_alloc`] and [`record_dealloc`]. At boot the runtime
+//! paints the stack region, which sits directly above the heap and grows down toward it,
+//! with a known pattern; at exit the lowest overwritten word gives the peak stack depth.
+//! The figures are written to the host right before the guest exits.
+
+use crate::cell::GuestCell;
+use airbender_core::memory::MemoryStats;
+
+/// Pattern written over the unused stack at boot.
+#[cfg(target_arch = "riscv32")]
+const STACK_PAINT: u32 = 0x5354_4b50; // "STKP"
+
+/// Bytes below the painting frame left untouched, as headroom for the painting loop.
+#[cfg(target_arch = "riscv32")]
+const PAINT_MARGIN: usize = 64;
+
+struct Tracker {
+    heap_bytes: u32,
+    heap_peak_bytes: u32,
+    allocations: u32,
+    deallocations: u32,
+    /// Painted stack range as `(lowest address, stack pointer at boot)`.
+    #[cfg(target_arch = "riscv32")]
+    stack: Option<(usize, usize)>,
+}
+
+impl Tracker {
+    const fn new() -> Self {
+        Self {
+            heap_bytes: 0,
+            heap_peak_bytes: 0,
+            allocations: 0,
+            deallocations: 0,
+            #[cfg(target_arch = "riscv32")]
+            stack: None,
+        }
+    }
+
+    fn record_alloc(&mut self, size: usize) {
+        self.allocations = self.allocations.saturating_add(1);
+        self.heap_bytes = self.heap_bytes.saturating_add(size as u32);
+        self.heap_peak_bytes = self.heap_peak_bytes.max(self.heap_bytes);
+    }
+
+    fn record_dealloc(&mut self, size: usize) {
+        self.deallocations = self.deallocations.saturating_add(1);
+        self.heap_bytes = self.heap_bytes.saturating_sub(size as u32);
+    }
+}
+
+static TRACKER: GuestCell<Tracker> = GuestCell::new(Tracker::new());
+
+fn with<R>(f: impl FnOnce(&mut Tracker) -> R) -> R {
+    TRACKER.with(f)
+}
+
+/// Record an allocation of `size` bytes; custom allocators can call this to be tracked too.
+pub fn record_alloc(size: usize) {
+    with(|tracker| tracker.record_alloc(size));
+}
+
+/// Record the release of a `size`-byte allocation.
+pub fn record_dealloc(size: usize) {
+    with(|tracker| tracker.record_dealloc(size));
+}
+
+/// Paint the stack between `stack_limit` and the current stack pointer.
+///
+/// # Safety
+///
+/// `stack_limit` must be the lowest address the stack may grow down to, with nothing but
+/// unused stack between it and the current stack pointer.
+#[cfg(target_arch = "riscv32")]
+#[inline(never)]
+pub(crate) unsafe fn paint_stack(stack_limit: *mut usize) {
+    let sp: usize;
+    core::arch::asm!("mv {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags));
+    let low = (stack_limit as usize + 3) & !3;
+    let high = sp.saturating_sub(PAINT_MARGIN) & !3;
+    let mut addr = low;
+    while addr < high {
+        core::ptr::write_volatile(addr as *mut u32, STACK_PAINT);
+        addr += 4;
+    }
+    with(|tracker| tracker.stack = Some((low, sp)));
+}
+
+/// Peak stack depth measured from the painted watermark.
+#[cfg(target_arch = "riscv32")]
+fn stack_peak_bytes(stack: Option<(usize, usize)>) -> u32 {
+    let Some((low, top)) = stack else {
+        return 0;
+    };
+    let mut addr = low;
+    while addr < top && unsafe { core::ptr::read_volatile(addr as *const u32) } == STACK_PAINT {
+        addr += 4;
+    }
+    (top - addr) as u32
+}
+
+/// Heap and stack usage so far.
+pub fn stats() -> MemoryStats {
+    with(|tracker| MemoryStats {
+        heap_bytes: tracker.heap_bytes,
+        heap_peak_bytes: tracker.heap_peak_bytes,
+        allocations: tracker.allocations,
+        deallocations: tracker.deallocations,
+        #[cfg(target_arch = "riscv32")]
+        stack_peak_bytes: stack_peak_bytes(tracker.stack),
+        #[cfg(not(target_arch = "riscv32"))]
+        stack_peak_bytes: 0,
+    })
+}
+
+/// Write the usage record to the host.
+#[cfg(target_arch = "riscv32")]
+pub(crate) fn emit_stats() {
+    crate::sys::write_word(airbender_core::memory::MEMORY_STATS_MARKER);
+    for word in stats().to_words() {
+        crate::sys::write_word(word);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tracker;
+
+    #[test]
+    fn tracks_current_and_peak_heap_bytes() {
+        let mut tracker = Tracker::new();
+        tracker.record_alloc(100);
+        tracker.record_alloc(50);
+        tracker.record_dealloc(100);
+        tracker.record_alloc(20);
+
+        assert_eq!(tracker.heap_bytes, 70);
+        assert_eq!(tracker.heap_peak_bytes, 150);
+        assert_eq!(tracker.allocations, 3);
+        assert_eq!(tracker.deallocations, 1);
+    }
+}
@@ -0,0 +1,41 @@
+//! `critical-section` implementation for the `critical-section` feature.
+//!
+//! Guest programs are single-threaded and never take interrupts, so a critical section only
+//! has to exist, not exclude anyone. This lets `critical-section` users such as
+//! `once_cell::sync`, `portable-atomic` and `critical_section::Mutex` run on `riscv32im`
+//! without the A extension.
+
+struct SingleCore;
+
+critical_section::set_impl!(SingleCore);
+
+unsafe impl critical_section::Impl for SingleCore {
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        // Whatever restore-state width the graph selected, there is nothing to restore.
+        Default::default()
+    }
+
+    unsafe fn release(_restore_state: critical_section::RawRestoreState) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use critical_section::Mutex;
+
+    #[test]
+    fn sections_nest_and_guard_shared_state() {
+        static COUNTER: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+        for _ in 0..3 {
+            critical_section::with(|outer| {
+                let counter = COUNTER.borrow(outer);
+                counter.set(counter.get() + 1);
+                critical_section::with(|inner| {
+                    let counter = COUNTER.borrow(inner);
+                    counter.set(counter.get() * 2);
+                });
+            });
+        }
+        assert_eq!(critical_section::with(|cs| COUNTER.borrow(cs).get()), 14);
+    }
+}
@@ -0,0 +1,100 @@
+//! Stack overflow detection.
+//!
+//! The stack sits directly above the heap and grows down toward it. At boot the runtime
+//! carves a guard region off the top of the heap and fills it with canary words. A stack
+//! pointer inside or below the guard, or an overwritten canary, means the stack grew into the
+//! heap, and the guest exits with a `STACK_OVERFLOW` report instead of running on with
+//! corrupted memory.
+//!
+//! Built-in allocators check the stack pointer and the top [`FAST_CHECK_BYTES`] of the guard
+//! on every call, which keeps allocation cheap. The exit path checks the whole guard, which
+//! catches frames that skipped over its top.
+
+/// Bytes taken from the top of the heap for the guard region.
+///
+/// Large enough that a frame reaching past it is unlikely to leave every canary intact.
+pub const GUARD_BYTES: usize = 4 << 10;
+
+/// Bytes at the top of the guard checked on every allocator call.
+pub const FAST_CHECK_BYTES: usize = 64;
+
+#[cfg(target_arch = "riscv32")]
+mod guard {
+    use crate::cell::GuestCell;
+
+    pub(super) const CANARY: u32 = 0x4755_4152; // "GUAR"
+
+    /// Lowest guard word address; zero when no guard is installed.
+    pub(super) static GUARD_BASE: GuestCell<usize> = GuestCell::new(0);
+}
+
+/// Fill the guard region at the top of `heap_start..heap_end` and return the reduced heap end.
+///
+/// Heaps too small to spare the guard are returned unchanged, without a guard.
+///
+/// # Safety
+///
+/// `heap_start` and `heap_end` must bound a writable, exclusively-owned heap region that sits
+/// directly below the stack.
+#[cfg(target_arch = "riscv32")]
+pub(crate) unsafe fn install(heap_start: *mut usize, heap_end: *mut usize) -> *mut usize {
+    let end = heap_end as usize & !3;
+    let base = end.saturating_sub(GUARD_BYTES);
+    if base < heap_start as usize {
+        return heap_end;
+    }
+    let mut addr = base;
+    while addr < end {
+        core::ptr::write_volatile(addr as *mut u32, guard::CANARY);
+        addr += 4;
+    }
+    guard::GUARD_BASE.set(base);
+    base as *mut usize
+}
+
+/// Exit with a `STACK_OVERFLOW` report if the stack has grown into the guard region.
+///
+/// Checks the stack pointer and every canary word.
+pub fn check() {
+    #[cfg(target_arch = "riscv32")]
+    check_guard(GUARD_BYTES);
+}
+
+/// Like [`check`], but only looks at the top [`FAST_CHECK_BYTES`] of the guard, for allocators.
+pub fn check_fast() {
+    #[cfg(target_arch = "riscv32")]
+    check_guard(FAST_CHECK_BYTES);
+}
+
+/// Checks the stack pointer and the top `bytes` of the guard.
+#[cfg(target_arch = "riscv32")]
+fn check_guard(bytes: usize) {
+    let base = guard::GUARD_BASE.get();
+    if base == 0 {
+        return;
+    }
+    let top = base + GUARD_BYTES;
+    let sp: usize;
+    // SAFETY: Reading the stack pointer has no side effects.
+    unsafe {
+        core::arch::asm!("mv {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags));
+    }
+    let intact = (top - bytes..top)
+        .step_by(4)
+        .all(|addr| unsafe { core::ptr::read_volatile(addr as *const u32) } == guard::CANARY);
+    if sp < top || !intact {
+        report_overflow();
+    }
+}
+
+#[cfg(target_arch = "riscv32")]
+#[cold]
+fn report_overflow() -> ! {
+    use core::fmt::Write as _;
+    // Disarm first so the error exit below does not report the overflow again.
+    guard::GUARD_BASE.set(0);
+    crate::sys::write_word(airbender_core::exit::GUEST_STACK_OVERFLOW_MARKER);
+    let _ = crate::uart::QuasiUart::new()
+        .write_str("STACK_OVERFLOW: stack grew into the heap guard region");
+    crate::sys::exit_error()
+}
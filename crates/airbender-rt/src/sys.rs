@@ -1,5 +1,10 @@
 //! Low-level CSR and exit helpers used by the guest runtime.
 
+use crate::cell::GuestCell;
+
+/// Number of cycle markers emitted so far.
+static MARKER_COUNT: GuestCell<u32> = GuestCell::new(0);
+
 #[cfg(target_arch = "riscv32")]
 pub fn read_word() -> u32 {
     riscv_common::csr_read_word()
@@ -22,6 +27,7 @@ pub fn write_word(_word: u32) {
 
 #[cfg(target_arch = "riscv32")]
 pub fn emit_cycle_marker() {
+    MARKER_COUNT.set(MARKER_COUNT.get() + 1);
     unsafe {
         // TODO: Make zksync-airbender the source of truth for this CSR value.
         core::arch::asm!(
@@ -34,10 +40,19 @@ pub fn emit_cycle_marker() {
 #[cfg(not(target_arch = "riscv32"))]
 pub fn emit_cycle_marker() {
     // Cycle markers are only meaningful on real guest targets.
+    MARKER_COUNT.set(MARKER_COUNT.get() + 1);
+}
+
+/// Number of [`emit_cycle_marker`] calls so far, which is the index of the next marker.
+pub fn cycle_markers_emitted() -> u32 {
+    MARKER_COUNT.get()
 }
 
 #[cfg(target_arch = "riscv32")]
 pub fn exit_success(words: &[u32; 8]) -> ! {
+    crate::stack_guard::check();
+    #[cfg(feature = "memory-stats")]
+    crate::memory::emit_stats();
     riscv_common::zksync_os_finish_success(words)
 }
 
@@ -48,6 +63,10 @@ pub fn exit_success(_words: &[u32; 8]) -> ! {
 
 #[cfg(target_arch = "riscv32")]
 pub fn exit_error() -> ! {
+    crate::stack_guard::check();
+    #[cfg(feature = "memory-stats")]
+    crate::memory::emit_stats();
+    write_word(airbender_core::exit::GUEST_ABORT_MARKER);
     riscv_common::zksync_os_finish_error()
 }
 
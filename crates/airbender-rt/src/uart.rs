@@ -1,6 +1,7 @@
 //! UART writer compatible with the existing quasi-uart protocol.
 
 use crate::sys;
+use airbender_core::log::{Level, GUEST_LOG_MARKER};
 use core::fmt;
 
 /// Minimal UART writer compatible with the existing quasi-uart protocol.
@@ -62,3 +63,11 @@ impl fmt::Write for QuasiUart {
         Ok(())
     }
 }
+
+/// Write `message` as one log record, which host runners forward to `tracing`.
+pub fn write_log_record(level: Level, message: &str) {
+    use fmt::Write as _;
+    sys::write_word(GUEST_LOG_MARKER);
+    sys::write_word(level.to_word());
+    let _ = QuasiUart::new().write_str(message);
+}
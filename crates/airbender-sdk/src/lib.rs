@@ -17,10 +17,11 @@ pub mod crypto {
 
 pub mod guest {
     pub use airbender_guest::*;
+    pub use airbender_macros::Commit;
 }
 
 pub mod rt {
     pub use airbender_rt::*;
 }
 
-pub use airbender_macros::main;
+pub use airbender_macros::{main, profile};
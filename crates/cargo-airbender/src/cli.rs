@@ -1,4 +1,6 @@
 use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
+use std::ops::Range;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -35,6 +37,10 @@ pub enum Commands {
     Run(RunArgs),
     /// Run app.bin with transpiler profiling and emit flamegraph SVG.
     Flamegraph(FlamegraphArgs),
+    /// Run app.bin under a GDB remote-protocol server.
+    Debug(DebugArgs),
+    /// Run app.bin and record an instruction-level execution trace.
+    Trace(TraceArgs),
     /// Generate a proof and write it as bincode.
     Prove(ProveArgs),
     /// Generate verification keys and write them as bincode.
@@ -112,6 +118,7 @@ pub struct NewArgs {
 pub enum NewAllocatorArg {
     Talc,
     Bump,
+    Arena,
     Custom,
 }
 
@@ -154,6 +161,91 @@ pub struct RunArgs {
         help = "Enable transpiler JIT execution (x86_64 only); default is portable non-JIT mode"
     )]
     pub jit: bool,
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "snapshot_point",
+        help = "Save the VM state to PATH at --snapshot-at-cycle or --snapshot-at-marker"
+    )]
+    pub snapshot: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "CYCLE",
+        group = "snapshot_point",
+        requires = "snapshot"
+    )]
+    pub snapshot_at_cycle: Option<usize>,
+    #[arg(
+        long,
+        value_name = "INDEX",
+        group = "snapshot_point",
+        requires = "snapshot",
+        help = "Take the snapshot right after the cycle marker with this index, counting from 0"
+    )]
+    pub snapshot_at_marker: Option<usize>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Resume from a snapshot file taken with the same app.bin and input"
+    )]
+    pub resume: Option<PathBuf>,
+    #[arg(
+        long,
+        conflicts_with = "jit",
+        help = "Print named cycle-marker regions as a table of inclusive and exclusive work"
+    )]
+    pub markers: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DebugArgs {
+    pub app_bin: PathBuf,
+    #[arg(short, long)]
+    pub input: PathBuf,
+    #[arg(short, long)]
+    pub cycles: Option<usize>,
+    #[arg(long)]
+    pub text_path: Option<PathBuf>,
+    #[arg(
+        long,
+        default_value = "127.0.0.1:1234",
+        help = "Address the GDB server listens on"
+    )]
+    pub listen: SocketAddr,
+}
+
+#[derive(Args, Debug)]
+pub struct TraceArgs {
+    pub app_bin: PathBuf,
+    #[arg(short, long)]
+    pub input: PathBuf,
+    #[arg(short, long, default_value = "trace.jsonl")]
+    pub output: PathBuf,
+    #[arg(long, value_enum, default_value_t = TraceFormatArg::Jsonl)]
+    pub format: TraceFormatArg,
+    #[arg(short, long)]
+    pub cycles: Option<usize>,
+    #[arg(
+        long,
+        value_name = "START..END",
+        value_parser = parse_cycle_window,
+        help = "Only record instructions executed in this cycle window"
+    )]
+    pub cycle_window: Option<Range<usize>>,
+    #[arg(
+        long,
+        value_name = "START..END",
+        value_parser = parse_pc_range,
+        requires = "cycle_window",
+        help = "Only record instructions at these addresses (needs --cycle-window)"
+    )]
+    pub pc_range: Option<Range<u32>>,
+    #[arg(long)]
+    pub text_path: Option<PathBuf>,
+    #[arg(long)]
+    pub elf_path: Option<PathBuf>,
+    #[arg(long, help = "Print the recorded trace with symbols from app.elf")]
+    pub print: bool,
 }
 
 #[derive(Args, Debug)]
@@ -202,6 +294,12 @@ pub struct VerifyProofArgs {
     pub expected_output: Option<String>,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum TraceFormatArg {
+    Jsonl,
+    Binary,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy)]
 pub enum ProverBackendArg {
     Dev,
@@ -216,6 +314,38 @@ pub enum ProverLevelArg {
     RecursionUnified,
 }
 
+fn parse_cycle_window(value: &str) -> Result<Range<usize>, String> {
+    let range = parse_range(value)?;
+    Ok(range.start as usize..range.end as usize)
+}
+
+fn parse_pc_range(value: &str) -> Result<Range<u32>, String> {
+    let range = parse_range(value)?;
+    let start = u32::try_from(range.start).map_err(|_| "start exceeds u32".to_string())?;
+    let end = u32::try_from(range.end).map_err(|_| "end exceeds u32".to_string())?;
+    Ok(start..end)
+}
+
+/// Parses `START..END` with decimal or `0x` hex bounds.
+fn parse_range(value: &str) -> Result<Range<u64>, String> {
+    let (start, end) = value
+        .split_once("..")
+        .ok_or_else(|| format!("expected START..END, got `{value}`"))?;
+    let parse = |bound: &str| {
+        let bound = bound.trim();
+        match bound.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => bound.parse(),
+        }
+        .map_err(|err| format!("invalid bound `{bound}`: {err}"))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if start >= end {
+        return Err(format!("empty range `{value}`"));
+    }
+    Ok(start..end)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,6 +370,126 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_run_snapshot_flags() {
+        let cli = Cli::parse_from([
+            "cargo-airbender",
+            "run",
+            "app.bin",
+            "--input",
+            "input.hex",
+            "--snapshot",
+            "run.snapshot",
+            "--snapshot-at-marker",
+            "2",
+        ]);
+        match cli.command {
+            Commands::Run(args) => {
+                assert_eq!(args.snapshot, Some(PathBuf::from("run.snapshot")));
+                assert_eq!(args.snapshot_at_marker, Some(2));
+                assert_eq!(args.snapshot_at_cycle, None);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        for flags in [
+            &["--snapshot", "run.snapshot"][..],
+            &["--snapshot-at-cycle", "10"][..],
+            &[
+                "--snapshot",
+                "run.snapshot",
+                "--snapshot-at-cycle",
+                "10",
+                "--snapshot-at-marker",
+                "0",
+            ][..],
+        ] {
+            let args = ["cargo-airbender", "run", "app.bin", "--input", "input.hex"];
+            let result = Cli::try_parse_from(args.iter().chain(flags));
+            assert!(result.is_err(), "{flags:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn parse_run_markers_flag() {
+        let args = ["cargo-airbender", "run", "app.bin", "--input", "input.hex"];
+        let cli = Cli::parse_from(args.iter().chain(&["--markers"]));
+        match cli.command {
+            Commands::Run(args) => assert!(args.markers),
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let result = Cli::try_parse_from(args.iter().chain(&["--markers", "--jit"]));
+        assert!(result.is_err(), "--markers should conflict with --jit");
+    }
+
+    #[test]
+    fn parse_debug_listen_address() {
+        let cli = Cli::parse_from([
+            "cargo-airbender",
+            "debug",
+            "app.bin",
+            "--input",
+            "input.hex",
+        ]);
+        match cli.command {
+            Commands::Debug(args) => {
+                assert_eq!(args.app_bin, PathBuf::from("app.bin"));
+                assert_eq!(args.listen, "127.0.0.1:1234".parse().unwrap());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_trace_ranges() {
+        let cli = Cli::parse_from([
+            "cargo-airbender",
+            "trace",
+            "app.bin",
+            "--input",
+            "input.hex",
+            "--cycle-window",
+            "100..2000",
+            "--pc-range",
+            "0x100..0x1f0",
+            "--format",
+            "binary",
+        ]);
+        match cli.command {
+            Commands::Trace(args) => {
+                assert_eq!(args.output, PathBuf::from("trace.jsonl"));
+                assert_eq!(args.cycle_window, Some(100..2000));
+                assert_eq!(args.pc_range, Some(0x100..0x1f0));
+                assert!(matches!(args.format, TraceFormatArg::Binary));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let err = Cli::try_parse_from([
+            "cargo-airbender",
+            "trace",
+            "app.bin",
+            "--input",
+            "input.hex",
+            "--pc-range",
+            "0x200..0x100",
+        ])
+        .expect_err("reversed range should fail");
+        assert!(err.to_string().contains("empty range"));
+
+        Cli::try_parse_from([
+            "cargo-airbender",
+            "trace",
+            "app.bin",
+            "--input",
+            "input.hex",
+            "--pc-range",
+            "0x100..0x1f0",
+        ])
+        .expect_err("a pc range needs a cycle window");
+    }
+
     #[test]
     fn parse_build_trailing_cargo_args() {
         let cli = Cli::parse_from([
@@ -61,6 +61,12 @@ pub fn run(args: BuildArgs) -> Result<()> {
     ui::field("app.bin", artifacts.app_bin.path.display());
     ui::field("app.elf", artifacts.app_elf.path.display());
     ui::field("app.text", artifacts.app_text.path.display());
+    if let Some(panic_table) = &artifacts.panic_table {
+        ui::field("panics.toml", panic_table.path.display());
+    }
+    if let Some(region_table) = &artifacts.region_table {
+        ui::field("regions.toml", region_table.path.display());
+    }
     ui::field("manifest", artifacts.manifest_path.display());
     ui::blank_line();
     ui::info("next step");
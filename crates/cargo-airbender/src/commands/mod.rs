@@ -7,6 +7,8 @@ mod vk;
 
 use crate::cli::{Cli, Commands};
 use crate::error::Result;
+use airbender_build::{Manifest, MemoryLayout};
+use std::path::Path;
 
 pub fn run(cli: Cli) -> Result<()> {
     match cli.command {
@@ -14,9 +16,17 @@ pub fn run(cli: Cli) -> Result<()> {
         Commands::New(args) => new::run(args),
         Commands::Run(args) => run::run(args),
         Commands::Flamegraph(args) => run::flamegraph(args),
+        Commands::Debug(args) => run::debug(args),
+        Commands::Trace(args) => run::trace(args),
         Commands::Prove(args) => prove::run(args),
         Commands::GenerateVk(args) => vk::generate(args),
         Commands::VerifyProof(args) => vk::verify(args),
         Commands::Clean => clean::run(),
     }
 }
+
+/// Memory layout recorded in the `manifest.toml` next to `app_bin`, if there is one.
+fn dist_memory_layout(app_bin: &Path) -> Option<MemoryLayout> {
+    let manifest_path = app_bin.with_file_name("manifest.toml");
+    Manifest::read_from_file(&manifest_path).ok()?.memory
+}
@@ -128,11 +128,12 @@ fn prompt_enable_std(default: bool) -> Result<bool> {
 }
 
 fn prompt_allocator(default: NewAllocatorArg) -> Result<NewAllocatorArg> {
-    let options = ["talc", "bump", "custom"];
+    let options = ["talc", "bump", "arena", "custom"];
     let default_index = match default {
         NewAllocatorArg::Talc => 0,
         NewAllocatorArg::Bump => 1,
-        NewAllocatorArg::Custom => 2,
+        NewAllocatorArg::Arena => 2,
+        NewAllocatorArg::Custom => 3,
     };
 
     let selected = Select::new()
@@ -145,7 +146,8 @@ fn prompt_allocator(default: NewAllocatorArg) -> Result<NewAllocatorArg> {
     Ok(match selected {
         0 => NewAllocatorArg::Talc,
         1 => NewAllocatorArg::Bump,
-        2 => NewAllocatorArg::Custom,
+        2 => NewAllocatorArg::Arena,
+        3 => NewAllocatorArg::Custom,
         _ => {
             return Err(CliError::new(format!(
                 "invalid allocator selection index `{selected}`"
@@ -272,6 +272,32 @@ mod tests {
         fs::remove_dir_all(&root).expect("remove test directories");
     }
 
+    #[test]
+    fn new_arena_allocator_selects_arena_feature() {
+        let root = test_workspace_dir("scaffold-arena-allocator");
+        let destination = root.join("hello-airbender");
+
+        run(NewArgs {
+            path: Some(destination.clone()),
+            name: Some("hello-airbender".to_string()),
+            enable_std: false,
+            allocator: NewAllocatorArg::Arena,
+            prover_backend: NewProverBackendArg::Dev,
+            yes: true,
+            sdk_path: None,
+            sdk_version: Some("0.1.0".to_string()),
+        })
+        .expect("create arena allocator scaffold");
+
+        assert_rendered_files_snapshot(
+            "new_arena_allocator_selects_arena_feature",
+            &destination,
+            &["guest/Cargo.toml"],
+        );
+
+        fs::remove_dir_all(&root).expect("remove test directories");
+    }
+
     #[test]
     fn new_custom_allocator_adds_allocator_hook() {
         let root = test_workspace_dir("scaffold-custom-allocator");
@@ -208,7 +208,9 @@ fn guest_attributes(enable_std: bool) -> &'static str {
 fn sdk_default_features(allocator: NewAllocatorArg) -> &'static str {
     match allocator {
         NewAllocatorArg::Talc => "",
-        NewAllocatorArg::Bump | NewAllocatorArg::Custom => ", default-features = false",
+        NewAllocatorArg::Bump | NewAllocatorArg::Arena | NewAllocatorArg::Custom => {
+            ", default-features = false"
+        }
     }
 }
 
@@ -220,6 +222,7 @@ fn sdk_features(enable_std: bool, allocator: NewAllocatorArg) -> String {
     match allocator {
         NewAllocatorArg::Talc => {}
         NewAllocatorArg::Bump => sdk_feature_flags.push("allocator-bump"),
+        NewAllocatorArg::Arena => sdk_feature_flags.push("allocator-arena"),
         NewAllocatorArg::Custom => sdk_feature_flags.push("allocator-custom"),
     }
 
@@ -238,7 +241,7 @@ fn sdk_features(enable_std: bool, allocator: NewAllocatorArg) -> String {
 fn main_attr_args(allocator: NewAllocatorArg) -> &'static str {
     match allocator {
         NewAllocatorArg::Custom => "(allocator_init = crate::custom_allocator::init)",
-        NewAllocatorArg::Talc | NewAllocatorArg::Bump => "",
+        NewAllocatorArg::Talc | NewAllocatorArg::Bump | NewAllocatorArg::Arena => "",
     }
 }
 
@@ -247,6 +250,6 @@ fn custom_allocator_block(allocator: NewAllocatorArg) -> String {
         NewAllocatorArg::Custom => {
             format!("\n\n{}", CUSTOM_ALLOCATOR_MODULE_TEMPLATE.trim_end())
         }
-        NewAllocatorArg::Talc | NewAllocatorArg::Bump => String::new(),
+        NewAllocatorArg::Talc | NewAllocatorArg::Bump | NewAllocatorArg::Arena => String::new(),
     }
 }
@@ -1,3 +1,4 @@
+use super::dist_memory_layout;
 use crate::cli::{ProveArgs, ProverBackendArg, ProverLevelArg};
 use crate::error::{CliError, Result};
 use crate::input;
@@ -85,6 +86,7 @@ pub fn run(args: ProveArgs) -> Result<()> {
                 .maybe_worker_threads(args.threads)
                 .maybe_cycles(args.cycles)
                 .maybe_ram_bound(args.ram_bound)
+                .maybe_memory_layout(dist_memory_layout(&args.app_bin))
                 .build()
                 .map_err(|err| {
                 CliError::with_source(
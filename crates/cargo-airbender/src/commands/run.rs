@@ -1,8 +1,10 @@
-use crate::cli::{FlamegraphArgs, RunArgs};
+use super::dist_memory_layout;
+use crate::cli::{DebugArgs, FlamegraphArgs, RunArgs, TraceArgs, TraceFormatArg};
 use crate::error::{CliError, Result};
 use crate::input;
 use crate::ui;
 use airbender_host::Runner;
+use std::path::Path;
 
 pub fn flamegraph(args: FlamegraphArgs) -> Result<()> {
     let input_words = input::parse_input_words(&args.input)?;
@@ -16,6 +18,7 @@ pub fn flamegraph(args: FlamegraphArgs) -> Result<()> {
     let runner = airbender_host::TranspilerRunnerBuilder::new(&args.app_bin)
         .with_flamegraph(flamegraph)
         .maybe_cycles(args.cycles)
+        .maybe_memory_layout(dist_memory_layout(&args.app_bin))
         .build()
         .map_err(|err| {
             CliError::with_source(
@@ -37,7 +40,7 @@ pub fn flamegraph(args: FlamegraphArgs) -> Result<()> {
         )
     })?;
 
-    report_execution_outcome("transpiler", &outcome);
+    report_execution_outcome("transpiler", &outcome, input_words.len());
     ui::field("flamegraph", flamegraph_output.display());
 
     Ok(())
@@ -47,10 +50,20 @@ pub fn run(args: RunArgs) -> Result<()> {
     let input_words = input::parse_input_words(&args.input)?;
     let mut builder = airbender_host::TranspilerRunnerBuilder::new(&args.app_bin)
         .maybe_cycles(args.cycles)
-        .maybe_text_path(args.text_path.as_ref());
+        .maybe_text_path(args.text_path.as_ref())
+        .maybe_memory_layout(dist_memory_layout(&args.app_bin));
     if args.jit {
         builder = builder.with_jit();
     }
+    if let Some(output) = &args.snapshot {
+        let at = match (args.snapshot_at_cycle, args.snapshot_at_marker) {
+            (Some(cycle), _) => airbender_host::SnapshotPoint::Cycle(cycle),
+            (None, Some(index)) => airbender_host::SnapshotPoint::Marker(index),
+            (None, None) => unreachable!("clap requires a snapshot point with --snapshot"),
+        };
+        builder = builder.with_snapshot(airbender_host::SnapshotConfig::new(output, at));
+    }
+    builder = builder.maybe_resume(args.resume.as_ref());
     let runner = builder.build().map_err(|err| {
         CliError::with_source(
             format!(
@@ -71,16 +84,229 @@ pub fn run(args: RunArgs) -> Result<()> {
         )
     })?;
 
-    report_execution_outcome("transpiler", &outcome);
+    report_execution_outcome("transpiler", &outcome, input_words.len());
+    if args.markers {
+        print_regions(&outcome);
+    }
+
+    Ok(())
+}
+
+pub fn debug(args: DebugArgs) -> Result<()> {
+    let input_words = input::parse_input_words(&args.input)?;
+    let runner = airbender_host::TranspilerRunnerBuilder::new(&args.app_bin)
+        .maybe_cycles(args.cycles)
+        .maybe_text_path(args.text_path.as_ref())
+        .maybe_memory_layout(dist_memory_layout(&args.app_bin))
+        .with_gdb_server(args.listen)
+        .build()
+        .map_err(|err| {
+            CliError::with_source(
+                format!(
+                    "failed to initialize transpiler runner for `{}`",
+                    args.app_bin.display()
+                ),
+                err,
+            )
+        })?;
+
+    ui::info(format!("waiting for GDB on {}", args.listen));
+    ui::command(format!(
+        "riscv32-elf-gdb -ex 'target remote {}'",
+        args.listen
+    ));
+
+    let outcome = runner.run(&input_words).map_err(|err| {
+        CliError::with_source(
+            format!("debug session failed for `{}`", args.app_bin.display()),
+            err,
+        )
+    })?;
+
+    report_execution_outcome("transpiler", &outcome, input_words.len());
 
     Ok(())
 }
 
-fn report_execution_outcome(mode: &str, outcome: &airbender_host::ExecutionResult) {
+pub fn trace(args: TraceArgs) -> Result<()> {
+    let input_words = input::parse_input_words(&args.input)?;
+    let trace = airbender_host::TraceConfig {
+        output: args.output.clone(),
+        format: match args.format {
+            TraceFormatArg::Jsonl => airbender_host::TraceFormat::Jsonl,
+            TraceFormatArg::Binary => airbender_host::TraceFormat::Binary,
+        },
+        cycles: args.cycle_window,
+        pc_range: args.pc_range,
+    };
+    let runner = airbender_host::TranspilerRunnerBuilder::new(&args.app_bin)
+        .maybe_cycles(args.cycles)
+        .maybe_text_path(args.text_path.as_ref())
+        .maybe_memory_layout(dist_memory_layout(&args.app_bin))
+        .with_trace(trace)
+        .build()
+        .map_err(|err| {
+            CliError::with_source(
+                format!(
+                    "failed to initialize transpiler runner for `{}`",
+                    args.app_bin.display()
+                ),
+                err,
+            )
+        })?;
+
+    let outcome = runner.run(&input_words).map_err(|err| {
+        CliError::with_source(format!("failed to trace `{}`", args.app_bin.display()), err)
+    })?;
+
+    report_execution_outcome("transpiler", &outcome, input_words.len());
+    ui::field("trace", args.output.display());
+
+    if args.print {
+        let elf_path = args
+            .elf_path
+            .unwrap_or_else(|| args.app_bin.with_extension("elf"));
+        print_trace(&args.output, &elf_path)?;
+    }
+
+    Ok(())
+}
+
+/// Print a recorded trace, headed by the function each run of instructions belongs to.
+fn print_trace(trace_path: &Path, elf_path: &Path) -> Result<()> {
+    use std::fmt::Write;
+
+    let entries = airbender_host::read_trace(trace_path).map_err(|err| {
+        CliError::with_source(
+            format!("failed to read trace `{}`", trace_path.display()),
+            err,
+        )
+    })?;
+    let symbols = match airbender_host::Symbolizer::load(elf_path) {
+        Ok(symbols) => Some(symbols),
+        Err(err) => {
+            ui::info(format!("printing trace without symbols: {err}"));
+            None
+        }
+    };
+
+    let mut listing = String::new();
+    let mut function = None;
+    for entry in &entries {
+        let current = symbols
+            .as_ref()
+            .and_then(|symbols| symbols.function(entry.pc));
+        if let Some(name) = current.as_ref().filter(|_| current != function) {
+            let _ = writeln!(listing, "<{name}>:");
+        }
+        function = current;
+        let _ = writeln!(listing, "{entry}");
+    }
+
+    ui::field("instructions", entries.len());
+    ui::text_block(&listing);
+    Ok(())
+}
+
+/// Print the named region tree of a run, one indented row per region with its inclusive
+/// delegation counts.
+fn print_regions(outcome: &airbender_host::ExecutionResult) {
+    use std::fmt::Write;
+
+    let regions = outcome
+        .cycle_markers
+        .as_ref()
+        .map(|markers| markers.regions.as_slice())
+        .unwrap_or_default();
+    if regions.is_empty() {
+        ui::info("guest recorded no named regions");
+        return;
+    }
+
+    let mut rows =
+        vec![["region", "calls", "inclusive", "exclusive", "delegations"].map(String::from)];
+    collect_region_rows(regions, 0, &mut rows);
+    let name_width = rows
+        .iter()
+        .map(|row| row[0].len())
+        .max()
+        .unwrap_or_default();
+    let mut table = String::new();
+    for [name, calls, inclusive, exclusive, delegations] in rows {
+        let row = format!(
+            "{name:<name_width$}  {calls:>6}  {inclusive:>12}  {exclusive:>12}  {delegations}"
+        );
+        let _ = writeln!(table, "{}", row.trim_end());
+    }
+
+    ui::field("regions", "");
+    ui::text_block(&table);
+}
+
+fn collect_region_rows(
+    regions: &[airbender_host::Region],
+    depth: usize,
+    rows: &mut Vec<[String; 5]>,
+) {
+    for region in regions {
+        let mut delegations: Vec<_> = region.inclusive.delegations.iter().collect();
+        delegations.sort();
+        let delegations = delegations
+            .iter()
+            .map(|(id, count)| format!("{id:#x}={count}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        rows.push([
+            format!("{}{}", "  ".repeat(depth), region.name),
+            region.calls.to_string(),
+            region.inclusive.cycles.to_string(),
+            region.exclusive.cycles.to_string(),
+            delegations,
+        ]);
+        collect_region_rows(&region.children, depth + 1, rows);
+    }
+}
+
+fn report_execution_outcome(
+    mode: &str,
+    outcome: &airbender_host::ExecutionResult,
+    supplied_input_words: usize,
+) {
     ui::success(format!("{mode} execution finished"));
     ui::field("cycles", outcome.cycles_executed);
-    ui::field("reached_end", outcome.reached_end);
+    ui::field("outcome", &outcome.outcome);
+    ui::field(
+        "inputs consumed",
+        format!(
+            "{}/{} words",
+            outcome.input_words_consumed, supplied_input_words
+        ),
+    );
     ui::field("outputs", format_output_registers(&outcome.receipt.output));
+    if let Some(stats) = &outcome.memory_stats {
+        ui::field(
+            "heap",
+            format!(
+                "{} bytes in use, {} bytes peak, {} allocations, {} deallocations",
+                stats.heap_bytes, stats.heap_peak_bytes, stats.allocations, stats.deallocations
+            ),
+        );
+        ui::field("stack", format!("{} bytes peak", stats.stack_peak_bytes));
+    }
+    if !outcome.stdout.is_empty() {
+        ui::field("stdout", "");
+        ui::text_block(&outcome.stdout.concat());
+    }
+    if let Some(backtrace) = &outcome.backtrace {
+        ui::field("backtrace", "");
+        ui::text_block(&backtrace.to_string());
+    }
+    if outcome.input_words_consumed < supplied_input_words {
+        ui::info(format!(
+            "guest left {} input words unread",
+            supplied_input_words - outcome.input_words_consumed
+        ));
+    }
 }
 
 fn format_output_registers(output: &[u32]) -> String {
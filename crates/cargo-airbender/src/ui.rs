@@ -25,6 +25,16 @@ pub fn field(key: &str, value: impl Display) {
     let _ = writeln!(stream, "  {key}: {value}");
 }
 
+pub fn text_block(text: &str) {
+    if !should_emit() {
+        return;
+    }
+    let mut stream = anstream::stdout();
+    for line in text.lines() {
+        let _ = writeln!(stream, "    {line}");
+    }
+}
+
 pub fn command(command: impl AsRef<str>) {
     if !should_emit() {
         return;
@@ -0,0 +1,37 @@
+#![no_std]
+#![no_main]
+
+use core::cell::Cell;
+use core::num::NonZeroUsize;
+use critical_section::Mutex;
+use once_cell::race::OnceNonZeroUsize;
+use once_cell::sync::Lazy;
+use portable_atomic::{AtomicU64, Ordering};
+
+static FIRST: OnceNonZeroUsize = OnceNonZeroUsize::new();
+static SQUARES: Lazy<[u64; 10]> = Lazy::new(|| core::array::from_fn(|i| (i as u64 + 1).pow(2)));
+static TOTAL: AtomicU64 = AtomicU64::new(0);
+static CALLS: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
+/// Returns the value of the first call, ignoring later ones.
+fn first(value: usize) -> usize {
+    FIRST
+        .get_or_init(|| NonZeroUsize::new(value).expect("value must be non-zero"))
+        .get()
+}
+
+#[airbender::main]
+fn main() -> u32 {
+    for square in SQUARES.iter() {
+        TOTAL.fetch_add(*square, Ordering::Relaxed);
+        critical_section::with(|cs| {
+            let calls = CALLS.borrow(cs);
+            calls.set(calls.get() + 1);
+        });
+    }
+
+    let first = first(7) + first(9);
+    let total = TOTAL.load(Ordering::Relaxed);
+    let calls = critical_section::with(|cs| CALLS.borrow(cs).get());
+    total as u32 + first as u32 + calls
+}
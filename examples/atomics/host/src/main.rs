@@ -0,0 +1,26 @@
+use airbender_host::{Program, Result, Runner};
+use std::path::PathBuf;
+
+/// Sum of squares 1..=10, twice the first `OnceNonZeroUsize` value, and the section count.
+const EXPECTED_OUTPUT: u32 = 385 + 14 + 10;
+
+fn main() -> Result<()> {
+    let dist_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../guest/dist/app");
+    let program = Program::load(&dist_dir)?;
+
+    let execution = program.transpiler_runner().build()?.run(&[])?;
+    println!(
+        "Execution finished: cycles={}, outcome={}, output={}",
+        execution.cycles_executed, execution.outcome, execution.receipt.output[0]
+    );
+    assert!(
+        execution.outcome.is_success(),
+        "guest execution did not succeed: {}",
+        execution.outcome
+    );
+    assert_eq!(
+        execution.receipt.output[0], EXPECTED_OUTPUT,
+        "unexpected atomics output"
+    );
+    Ok(())
+}
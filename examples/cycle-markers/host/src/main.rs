@@ -11,25 +11,44 @@ fn main() -> Result<()> {
         .as_ref()
         .expect("cycle markers must be collected for non-JIT transpiler runs");
 
-    assert!(execution.reached_end, "guest execution did not reach the exit loop");
-    assert_eq!(markers.markers.len(), 2, "expected exactly two cycle markers");
+    assert!(
+        execution.outcome.is_success(),
+        "guest execution did not succeed: {}",
+        execution.outcome
+    );
+    assert_eq!(
+        markers.markers.len(),
+        4,
+        "expected two cycle markers per region"
+    );
 
-    let profiled_section = markers.markers[1].diff(&markers.markers[0]);
+    let [profiled] = markers.regions.as_slice() else {
+        panic!("expected one top-level region, got {:?}", markers.regions);
+    };
+    assert_eq!(profiled.name, "digest_prefix");
+    let [keccak] = profiled.children.as_slice() else {
+        panic!("expected one nested region, got {:?}", profiled.children);
+    };
+    assert_eq!(keccak.name, "keccak");
     assert!(
-        profiled_section.cycles > 0,
+        keccak.inclusive.cycles > 0,
         "profiled section must consume at least one cycle"
     );
     assert!(
-        !profiled_section.delegations.is_empty(),
+        !keccak.inclusive.delegations.is_empty(),
         "delegated Keccak section should report delegation activity"
     );
 
     println!(
-        "Execution finished: cycles={}, output={}, section_cycles={}, section_delegations={:?}",
+        "Execution finished: cycles={}, output={}, {}_cycles={}, {}_cycles={}, {}_delegations={:?}",
         execution.cycles_executed,
         execution.receipt.output[0],
-        profiled_section.cycles,
-        profiled_section.delegations
+        profiled.name,
+        profiled.inclusive.cycles,
+        keccak.name,
+        keccak.inclusive.cycles,
+        keccak.name,
+        keccak.inclusive.delegations
     );
 
     Ok(())
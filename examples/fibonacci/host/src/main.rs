@@ -17,8 +17,8 @@ fn main() -> Result<()> {
     let execution = runner.run(inputs.words())?;
     let exec_output = execution.receipt.output[0];
     println!(
-        "Execution finished: cycles={}, reached_end={}, output={}",
-        execution.cycles_executed, execution.reached_end, exec_output
+        "Execution finished: cycles={}, outcome={}, output={}",
+        execution.cycles_executed, execution.outcome, exec_output
     );
     assert_eq!(exec_output, expected, "unexpected fibonacci output");
 
@@ -0,0 +1,28 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use airbender::guest::record_cycles;
+use alloc::vec;
+
+const LEN: usize = 64 * 1024;
+
+#[airbender::main]
+fn main() -> u32 {
+    record_cycles(|| {
+        // `memset`, `memcpy`, `memmove` and `bcmp` on large heap buffers.
+        let mut buffer = vec![0x5au8; LEN];
+        buffer[LEN / 2..].fill(0xa5);
+        let copy = buffer.clone();
+        buffer.copy_within(LEN / 4.., 0);
+        assert!(buffer[LEN / 4..3 * LEN / 4] == copy[LEN / 2..]);
+        assert!(buffer[..LEN / 4] == copy[LEN / 4..LEN / 2]);
+
+        buffer
+            .iter()
+            .step_by(4096)
+            .map(|byte| u32::from(*byte))
+            .sum()
+    })
+}
@@ -0,0 +1,53 @@
+use airbender_host::{Program, Result, Runner};
+use std::path::PathBuf;
+
+const EXPECTED_OUTPUT: u32 = 2340;
+
+/// Runs one build of the guest and returns the cycles spent in its profiled section.
+fn section_cycles(app_name: &str) -> Result<u64> {
+    let dist_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../guest/dist")
+        .join(app_name);
+    let program = Program::load(&dist_dir)?;
+    let execution = program.transpiler_runner().build()?.run(&[])?;
+    assert!(
+        execution.outcome.is_success(),
+        "{app_name} guest execution did not succeed: {}",
+        execution.outcome
+    );
+    assert_eq!(
+        execution.receipt.output[0], EXPECTED_OUTPUT,
+        "unexpected {app_name} output"
+    );
+
+    let markers = execution
+        .cycle_markers
+        .as_ref()
+        .expect("cycle markers must be collected for non-JIT transpiler runs");
+    assert_eq!(
+        markers.markers.len(),
+        2,
+        "expected exactly two cycle markers"
+    );
+    let section = markers.markers[1].diff(&markers.markers[0]);
+    println!(
+        "{app_name}: total_cycles={}, section_cycles={}, section_delegations={:?}",
+        execution.cycles_executed, section.cycles, section.delegations
+    );
+    Ok(section.cycles)
+}
+
+fn main() -> Result<()> {
+    let baseline = section_cycles("baseline")?;
+    let delegated = section_cycles("delegated")?;
+    assert!(
+        delegated < baseline,
+        "delegated memory routines should use fewer cycles ({delegated} >= {baseline})"
+    );
+    println!(
+        "mem-delegation saves {} cycles ({:.1}x faster)",
+        baseline - delegated,
+        baseline as f64 / delegated as f64
+    );
+    Ok(())
+}
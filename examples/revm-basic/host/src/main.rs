@@ -29,8 +29,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let execution = runner.run(inputs.words())?;
     let guest_output = execution.receipt.output[0];
     println!(
-        "Guest execution: cycles={}, reached_end={}, gas_used={}",
-        execution.cycles_executed, execution.reached_end, guest_output
+        "Guest execution: cycles={}, outcome={}, gas_used={}",
+        execution.cycles_executed, execution.outcome, guest_output
     );
     assert_eq!(guest_output, expected, "guest and native gas mismatch");
 
@@ -1,15 +1,10 @@
 #![no_std]
 #![no_main]
 
-use airbender::guest::read;
 use ruint::aliases::U256;
 
 #[airbender::main]
-fn main() -> bool {
-    let a: U256 = read().expect("failed to read input a");
-    let b: U256 = read().expect("failed to read input b");
-    let c: U256 = read().expect("failed to read input c");
-
+fn main(a: U256, b: U256, c: U256) -> bool {
     let sum = a + b;
     assert_eq!(sum, c, "u256 addition check failed");
 
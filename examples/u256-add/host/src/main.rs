@@ -13,17 +13,14 @@ fn main() -> Result<()> {
     let b = U256::from(2u64);
     let c = U256::from(3u64);
 
-    let mut inputs = Inputs::new();
-    inputs.push(&a)?;
-    inputs.push(&b)?;
-    inputs.push(&c)?;
+    let inputs = Inputs::from_tuple(&(a, b, c))?;
 
     let runner = program.transpiler_runner().build()?;
     let execution = runner.run(inputs.words())?;
     let exec_valid = execution.receipt.output[0] == 1;
     println!(
-        "Execution finished: cycles={}, reached_end={}, valid={}",
-        execution.cycles_executed, execution.reached_end, exec_valid
+        "Execution finished: cycles={}, outcome={}, valid={}",
+        execution.cycles_executed, execution.outcome, exec_valid
     );
     assert!(exec_valid, "guest reported invalid sum");
 